@@ -0,0 +1,296 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::sync::Arc;
+
+use http::header::InvalidHeaderValue;
+use http::header::AUTHORIZATION;
+use http::HeaderName;
+use http::HeaderValue;
+use http::Request;
+use http::Uri;
+
+use crate::encode::base64;
+use crate::encode::percent;
+use crate::Bytes;
+
+
+/// An error reported by an [`Auth`] provider failing to apply
+/// credentials to a request.
+#[derive(Debug)]
+pub struct Error(Box<dyn StdError + Send + Sync>);
+
+impl Error {
+  /// Create a new `Error` wrapping the provided one.
+  pub fn new<E>(error: E) -> Self
+  where
+    E: Into<Box<dyn StdError + Send + Sync>>,
+  {
+    Self(error.into())
+  }
+}
+
+impl Display for Error {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    write!(fmt, "{}", self.0)
+  }
+}
+
+impl StdError for Error {
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    self.0.source()
+  }
+}
+
+
+/// A trait for applying credentials to requests.
+///
+/// Authentication providers are invoked on the request created for an
+/// endpoint before it is handed to the transport, e.g., by a
+/// [`Client`][crate::Client].
+pub trait Auth {
+  /// Apply credentials to the given request.
+  ///
+  /// Requests for which an error is reported must not be sent.
+  fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), Error>;
+}
+
+impl<A> Auth for Box<A>
+where
+  A: Auth + ?Sized,
+{
+  fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), Error> {
+    (**self).authenticate(request)
+  }
+}
+
+impl<A> Auth for Arc<A>
+where
+  A: Auth + ?Sized,
+{
+  fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), Error> {
+    (**self).authenticate(request)
+  }
+}
+
+
+/// Create a header value marked as sensitive.
+fn sensitive(value: &str) -> Result<HeaderValue, InvalidHeaderValue> {
+  let mut value = HeaderValue::from_str(value)?;
+  value.set_sensitive(true);
+  Ok(value)
+}
+
+
+/// An [`Auth`] provider setting a bearer token in the `Authorization`
+/// header.
+#[derive(Clone)]
+pub struct Bearer {
+  value: HeaderValue,
+}
+
+impl Bearer {
+  /// Create a new `Bearer` provider for the given token.
+  pub fn new(token: &str) -> Result<Self, InvalidHeaderValue> {
+    Ok(Self {
+      value: sensitive(&format!("Bearer {}", token))?,
+    })
+  }
+}
+
+impl Debug for Bearer {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt.debug_struct("Bearer").finish_non_exhaustive()
+  }
+}
+
+impl Auth for Bearer {
+  fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), Error> {
+    let _prev = request
+      .headers_mut()
+      .insert(AUTHORIZATION, self.value.clone());
+    Ok(())
+  }
+}
+
+
+/// An [`Auth`] provider using HTTP Basic authentication.
+#[derive(Clone)]
+pub struct Basic {
+  value: HeaderValue,
+}
+
+impl Basic {
+  /// Create a new `Basic` provider for the given user name and
+  /// optional password.
+  pub fn new(user: &str, password: Option<&str>) -> Result<Self, InvalidHeaderValue> {
+    let credentials = format!("{}:{}", user, password.unwrap_or(""));
+    Ok(Self {
+      value: sensitive(&format!("Basic {}", base64(credentials.as_bytes())))?,
+    })
+  }
+}
+
+impl Debug for Basic {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt.debug_struct("Basic").finish_non_exhaustive()
+  }
+}
+
+impl Auth for Basic {
+  fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), Error> {
+    let _prev = request
+      .headers_mut()
+      .insert(AUTHORIZATION, self.value.clone());
+    Ok(())
+  }
+}
+
+
+#[derive(Clone)]
+enum Location {
+  Header(HeaderName, HeaderValue),
+  Query(String),
+}
+
+
+/// An [`Auth`] provider conveying an API key in a header or a query
+/// parameter.
+///
+/// Note that a key provided as query parameter is part of the
+/// request's URI and will not be redacted when the request is logged
+/// or recorded.
+#[derive(Clone)]
+pub struct ApiKey {
+  location: Location,
+}
+
+impl ApiKey {
+  /// Create a new `ApiKey` provider setting the header with the given
+  /// name to `key`.
+  pub fn header(name: HeaderName, key: &str) -> Result<Self, InvalidHeaderValue> {
+    Ok(Self {
+      location: Location::Header(name, sensitive(key)?),
+    })
+  }
+
+  /// Create a new `ApiKey` provider appending a query parameter with
+  /// the given name and `key` as value.
+  pub fn query(name: &str, key: &str) -> Self {
+    Self {
+      location: Location::Query(format!("{}={}", percent(name), percent(key))),
+    }
+  }
+}
+
+impl Debug for ApiKey {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    let mut debug = fmt.debug_struct("ApiKey");
+    if let Location::Header(name, _) = &self.location {
+      debug.field("header", name);
+    }
+    debug.finish_non_exhaustive()
+  }
+}
+
+impl Auth for ApiKey {
+  fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), Error> {
+    match &self.location {
+      Location::Header(name, value) => {
+        let _prev = request.headers_mut().insert(name.clone(), value.clone());
+      },
+      Location::Query(pair) => {
+        let uri = request.uri().to_string();
+        let separator = match request.uri().query() {
+          Some(query) if !query.is_empty() => "&",
+          Some(..) => "",
+          None => "?",
+        };
+        let uri = format!("{}{}{}", uri, separator, pair)
+          .parse::<Uri>()
+          .map_err(Error::new)?;
+        *request.uri_mut() = uri;
+      },
+    }
+    Ok(())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  fn request(uri: &str) -> Request<Bytes> {
+    Request::get(uri).body(Bytes::Borrowed(&[])).unwrap()
+  }
+
+
+  /// Check that the `Bearer` provider sets the expected header.
+  #[test]
+  fn bearer() {
+    let mut request = request("https://example.com/");
+    let () = Bearer::new("token")
+      .unwrap()
+      .authenticate(&mut request)
+      .unwrap();
+    let value = &request.headers()[AUTHORIZATION];
+    assert_eq!(value, "Bearer token");
+    assert!(value.is_sensitive());
+
+    assert!(Bearer::new("new\nline").is_err());
+  }
+
+  /// Check that the `Basic` provider sets the expected header.
+  #[test]
+  fn basic() {
+    let mut request = request("https://example.com/");
+    let auth = Basic::new("Aladdin", Some("open sesame")).unwrap();
+    let () = auth.authenticate(&mut request).unwrap();
+    assert_eq!(
+      request.headers()[AUTHORIZATION],
+      "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+    );
+
+    let auth = Basic::new("user", None).unwrap();
+    let () = auth.authenticate(&mut request).unwrap();
+    assert_eq!(request.headers()[AUTHORIZATION], "Basic dXNlcjo=");
+  }
+
+  /// Check that the `ApiKey` provider sets the expected header or
+  /// query parameter.
+  #[test]
+  fn api_key() {
+    let name = HeaderName::from_static("x-api-key");
+    let mut request = request("https://example.com/");
+    let () = ApiKey::header(name.clone(), "secret")
+      .unwrap()
+      .authenticate(&mut request)
+      .unwrap();
+    assert_eq!(request.headers()[&name], "secret");
+    assert!(request.headers()[&name].is_sensitive());
+
+    let auth = ApiKey::query("api key", "s&cret");
+    let mut request = self::request("https://example.com/orders");
+    let () = auth.authenticate(&mut request).unwrap();
+    assert_eq!(
+      request.uri(),
+      "https://example.com/orders?api%20key=s%26cret"
+    );
+
+    let mut request = self::request("https://example.com/orders?status=open");
+    let () = auth.authenticate(&mut request).unwrap();
+    assert_eq!(
+      request.uri(),
+      "https://example.com/orders?status=open&api%20key=s%26cret"
+    );
+
+    assert_eq!(format!("{auth:?}"), "ApiKey { .. }");
+  }
+}
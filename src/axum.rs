@@ -0,0 +1,85 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Integration with `axum` for serving endpoints.
+
+use std::future::Future;
+
+use ::axum::body::Body;
+use ::axum::body::Bytes as AxumBytes;
+use ::axum::routing::on;
+use ::axum::routing::MethodFilter;
+use ::axum::Router;
+
+use http::request::Parts;
+use http::Request;
+use http::Response;
+
+use crate::server::decode;
+use crate::server::respond;
+use crate::server::Serve;
+
+
+/// Serve an endpoint by means of the provided handler.
+async fn serve<E, H, F>(handler: H, parts: Parts, body: AxumBytes) -> Response<Body>
+where
+  E: Serve,
+  H: FnOnce(E::Input) -> F,
+  F: Future<Output = Result<E::Output, E::Error>>,
+{
+  let request = Request::from_parts(parts, body);
+  // Make sure to not hold on to the conversion error, which may not be
+  // `Send`, across the await point.
+  let input = match decode::<E, _>(&request) {
+    Ok(input) => input,
+    Err(err) => return err.into_response().map(Body::from),
+  };
+  respond::<E>(handler(input).await).map(Body::from)
+}
+
+
+/// An extension trait for [`Router`] for mounting endpoint handlers.
+pub trait RouterExt {
+  /// Mount a handler for endpoint `E` at its method and path template.
+  ///
+  /// Incoming requests are decoded into the endpoint's input, which is
+  /// passed to the handler. The handler's result is encoded into a
+  /// response with the status declared for the output or the
+  /// respective error variant. Requests that cannot be decoded are
+  /// rejected with status 400.
+  ///
+  /// # Panics
+  /// This method panics if the endpoint does not provide a path
+  /// template, uses a method not supported by `axum`, or if the path
+  /// conflicts with an already mounted route.
+  fn endpoint<E, H, F>(self, handler: H) -> Self
+  where
+    E: Serve + 'static,
+    H: Fn(E::Input) -> F + Clone + Send + Sync + 'static,
+    F: Future<Output = Result<E::Output, E::Error>> + Send + 'static;
+}
+
+impl<S> RouterExt for Router<S>
+where
+  S: Clone + Send + Sync + 'static,
+{
+  fn endpoint<E, H, F>(self, handler: H) -> Self
+  where
+    E: Serve + 'static,
+    H: Fn(E::Input) -> F + Clone + Send + Sync + 'static,
+    F: Future<Output = Result<E::Output, E::Error>> + Send + 'static,
+  {
+    let template =
+      E::path_template().unwrap_or_else(|| panic!("endpoint {} lacks a path template", E::NAME));
+    // Any templated query is not relevant for routing. Placeholders
+    // share the syntax used by `axum`.
+    let path = template.split('?').next().unwrap_or(template);
+    let filter = MethodFilter::try_from(E::method())
+      .unwrap_or_else(|err| panic!("endpoint {} cannot be served: {err}", E::NAME));
+
+    let method_router = on(filter, move |parts: Parts, body: AxumBytes| {
+      serve::<E, _, _>(handler.clone(), parts, body)
+    });
+    self.route(path, method_router)
+  }
+}
@@ -0,0 +1,57 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Generate `EndpointDef` based endpoint definitions from an OpenAPI
+//! specification.
+//!
+//! Usage: `endpoint-codegen <spec> [output]`
+//!
+//! The generated code is written to `output` or, if not provided, to
+//! stdout.
+
+use std::env::args_os;
+use std::ffi::OsString;
+use std::fs::read_to_string;
+use std::io::stdout;
+use std::io::Write as _;
+use std::process::ExitCode;
+
+use http_endpoint::codegen::generate;
+use http_endpoint::codegen::generate_file;
+use http_endpoint::codegen::Error;
+
+
+/// The usage text printed when invoked incorrectly.
+const USAGE: &str = "usage: endpoint-codegen <spec> [output]";
+
+
+fn run(spec: OsString, output: Option<OsString>) -> Result<(), Error> {
+  match output {
+    Some(output) => generate_file(spec, output),
+    None => {
+      let code = generate(&read_to_string(spec)?)?;
+      stdout().write_all(code.as_bytes())?;
+      Ok(())
+    },
+  }
+}
+
+
+fn main() -> ExitCode {
+  let mut args = args_os().skip(1);
+  let spec = match args.next() {
+    Some(spec) => spec,
+    None => {
+      eprintln!("{}", USAGE);
+      return ExitCode::from(2);
+    },
+  };
+
+  match run(spec, args.next()) {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("{}", err);
+      ExitCode::FAILURE
+    },
+  }
+}
@@ -0,0 +1,974 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! HTTP caching of responses.
+//!
+//! A [`Cache`] wraps a [`Transport`] and stores responses to `GET`
+//! requests, honoring the response's `Cache-Control` and `Vary`
+//! headers as per RFC 9111. Note that the freshness lifetime of a
+//! response is only derived from its `max-age` and `s-maxage`
+//! directives, `Expires` headers are not evaluated. Fresh responses
+//! are served without contacting the server. Stale responses carrying
+//! an `ETag` or `Last-Modified` header are revalidated by sending the
+//! request with `If-None-Match` and `If-Modified-Since` headers and if
+//! the server reports that the resource has not been modified, the
+//! stored response is provided in place of the `304 Not Modified` one,
+//! making the revalidation transparent to the endpoint. Responses are
+//! kept in a pluggable [`Storage`], with [`MemoryStorage`] and
+//! [`DirStorage`] being provided.
+//!
+//! Endpoints can override the caching behavior for requests to them
+//! by means of [`Endpoint::cache_policy`][crate::Endpoint::cache_policy].
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::fs::create_dir_all;
+use std::fs::read;
+use std::fs::remove_file;
+use std::fs::rename;
+use std::fs::write;
+use std::io::Error as IoError;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::from_utf8;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use http::header::AGE;
+use http::header::AUTHORIZATION;
+use http::header::CACHE_CONTROL;
+use http::header::CONNECTION;
+use http::header::ETAG;
+use http::header::IF_MODIFIED_SINCE;
+use http::header::IF_NONE_MATCH;
+use http::header::LAST_MODIFIED;
+use http::header::VARY;
+use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
+use http::Method;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use tokio::runtime::Handle;
+
+use crate::BoxFuture;
+use crate::Bytes;
+use crate::CachePolicy;
+use crate::Transport;
+
+
+/// Headers of a `304 Not Modified` response that must not replace
+/// those of a stored response, as per RFC 9111 section 3.2: the
+/// content length as well as hop-by-hop and framing headers.
+const UNMERGED: [&str; 7] = [
+  "connection",
+  "content-length",
+  "keep-alive",
+  "proxy-connection",
+  "te",
+  "transfer-encoding",
+  "upgrade",
+];
+
+
+/// The `Cache-Control` directives relevant for caching.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Directives {
+  no_store: bool,
+  no_cache: bool,
+  private: bool,
+  public: bool,
+  must_revalidate: bool,
+  max_age: Option<u64>,
+  s_maxage: Option<u64>,
+  stale_while_revalidate: Option<u64>,
+}
+
+impl Directives {
+  /// Parse the directives contained in the `Cache-Control` headers of
+  /// a request or response.
+  fn new(headers: &HeaderMap) -> Self {
+    let mut directives = Self::default();
+    let values = headers
+      .get_all(CACHE_CONTROL)
+      .iter()
+      .filter_map(|value| value.to_str().ok());
+
+    for directive in values.flat_map(|value| value.split(',')) {
+      let (name, value) = match directive.split_once('=') {
+        Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+        None => (directive.trim(), None),
+      };
+      let seconds = value.and_then(|value| value.parse().ok());
+
+      match name.to_ascii_lowercase().as_str() {
+        "no-store" => directives.no_store = true,
+        "no-cache" => directives.no_cache = true,
+        "private" => directives.private = true,
+        "public" => directives.public = true,
+        "must-revalidate" => directives.must_revalidate = true,
+        "max-age" => directives.max_age = seconds,
+        "s-maxage" => directives.s_maxage = seconds,
+        "stale-while-revalidate" => directives.stale_while_revalidate = seconds,
+        _ => (),
+      }
+    }
+    directives
+  }
+}
+
+
+/// Retrieve the names of the request headers a response varies on, or
+/// `None` if it varies on aspects other than request headers.
+fn vary(headers: &HeaderMap) -> Option<Vec<HeaderName>> {
+  let mut names = Vec::new();
+  let values = headers
+    .get_all(VARY)
+    .iter()
+    .filter_map(|value| value.to_str().ok());
+
+  for name in values.flat_map(|value| value.split(',')) {
+    let name = name.trim();
+    if name == "*" {
+      return None;
+    }
+    if let Ok(name) = HeaderName::from_bytes(name.as_bytes()) {
+      let () = names.push(name);
+    }
+  }
+  Some(names)
+}
+
+
+/// The freshness of a stored response.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Freshness {
+  /// The response may be served without contacting the server.
+  Fresh,
+  /// The response may be served while it is revalidated in the
+  /// background.
+  StaleWhileRevalidate,
+  /// The response has to be revalidated before it may be served.
+  Stale,
+}
+
+
+/// A stored response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+  /// The response's status.
+  pub status: StatusCode,
+  /// The response's headers.
+  pub headers: HeaderMap,
+  /// The response's body.
+  pub body: Vec<u8>,
+  /// The time the response was received or last revalidated at.
+  pub stored: SystemTime,
+  /// The request headers the response varies on, as nominated by its
+  /// `Vary` header, with the values they had in the request.
+  pub varying: HeaderMap,
+}
+
+impl Entry {
+  fn new(
+    request: &HeaderMap,
+    response: &Response<Vec<u8>>,
+    vary: &[HeaderName],
+    now: SystemTime,
+  ) -> Self {
+    let mut varying = HeaderMap::new();
+    for name in vary {
+      for value in request.get_all(name) {
+        let _existed = varying.append(name, value.clone());
+      }
+    }
+
+    Self {
+      status: response.status(),
+      headers: response.headers().clone(),
+      body: response.body().clone(),
+      stored: now,
+      varying,
+    }
+  }
+
+  /// Retrieve the entity tag of the stored response, if any.
+  pub fn etag(&self) -> Option<&HeaderValue> {
+    self.headers.get(ETAG)
+  }
+
+  /// Retrieve the last modification date of the stored response, if
+  /// any.
+  pub fn last_modified(&self) -> Option<&HeaderValue> {
+    self.headers.get(LAST_MODIFIED)
+  }
+
+  /// Calculate the age of the stored response at the given time.
+  fn age(&self, now: SystemTime) -> Duration {
+    let age = self
+      .headers
+      .get(AGE)
+      .and_then(|age| age.to_str().ok())
+      .and_then(|age| age.parse().ok())
+      .map(Duration::from_secs)
+      .unwrap_or_default();
+    age + now.duration_since(self.stored).unwrap_or_default()
+  }
+
+  /// Determine the freshness of the stored response at the given time.
+  fn freshness(&self, now: SystemTime, shared: bool) -> Freshness {
+    let directives = Directives::new(&self.headers);
+    let lifetime = if directives.no_cache {
+      0
+    } else if shared {
+      directives.s_maxage.or(directives.max_age).unwrap_or(0)
+    } else {
+      directives.max_age.unwrap_or(0)
+    };
+
+    let age = self.age(now);
+    let stale = if directives.must_revalidate {
+      0
+    } else {
+      directives.stale_while_revalidate.unwrap_or(0)
+    };
+
+    if age < Duration::from_secs(lifetime) {
+      Freshness::Fresh
+    } else if age < Duration::from_secs(lifetime.saturating_add(stale)) {
+      Freshness::StaleWhileRevalidate
+    } else {
+      Freshness::Stale
+    }
+  }
+
+  /// Check whether the stored response may be used for a request with
+  /// the given headers.
+  fn matches(&self, request: &HeaderMap) -> bool {
+    match vary(&self.headers) {
+      Some(names) => names.iter().all(|name| {
+        request
+          .get_all(name)
+          .iter()
+          .eq(self.varying.get_all(name).iter())
+      }),
+      None => false,
+    }
+  }
+
+  /// Update the stored headers with the ones of a `304 Not Modified`
+  /// response.
+  fn update(&mut self, headers: &HeaderMap, now: SystemTime) {
+    // Headers nominated by the `Connection` header are hop-by-hop as
+    // well.
+    let connection = headers
+      .get_all(CONNECTION)
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .flat_map(|value| value.split(','))
+      .map(|name| name.trim().to_ascii_lowercase())
+      .collect::<Vec<_>>();
+
+    for name in headers.keys() {
+      let name_str = name.as_str();
+      if UNMERGED.contains(&name_str) || connection.iter().any(|other| other == name_str) {
+        continue;
+      }
+
+      let _prev = self.headers.remove(name);
+      for value in headers.get_all(name) {
+        let _existed = self.headers.append(name, value.clone());
+      }
+    }
+    self.stored = now;
+  }
+
+  fn into_response(self, now: SystemTime) -> Response<Vec<u8>> {
+    let age = self.age(now).as_secs();
+    let mut response = Response::new(self.body);
+    *response.status_mut() = self.status;
+    *response.headers_mut() = self.headers;
+    let _prev = response.headers_mut().insert(AGE, HeaderValue::from(age));
+    response
+  }
+
+  /// Serialize the entry, along with the key it is stored under.
+  fn to_bytes(&self, key: &str) -> Vec<u8> {
+    fn headers(data: &mut Vec<u8>, headers: &HeaderMap) {
+      for (name, value) in headers {
+        let () = data.extend_from_slice(name.as_str().as_bytes());
+        let () = data.extend_from_slice(b": ");
+        let () = data.extend_from_slice(value.as_bytes());
+        let () = data.push(b'\n');
+      }
+      let () = data.push(b'\n');
+    }
+
+    let stored = self
+      .stored
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .as_millis();
+    let mut data = format!("{}\n{}\n{}\n", key, self.status.as_u16(), stored).into_bytes();
+    let () = headers(&mut data, &self.varying);
+    let () = headers(&mut data, &self.headers);
+    let () = data.extend_from_slice(&self.body);
+    data
+  }
+
+  /// Deserialize an entry, checking that it was stored under the given
+  /// key.
+  fn from_bytes(key: &str, data: &[u8]) -> Option<Self> {
+    fn line<'d>(data: &mut &'d [u8]) -> Option<&'d [u8]> {
+      let end = data.iter().position(|b| *b == b'\n')?;
+      let line = &data[..end];
+      *data = &data[end + 1..];
+      Some(line)
+    }
+
+    fn headers(data: &mut &[u8]) -> Option<HeaderMap> {
+      let mut headers = HeaderMap::new();
+      loop {
+        let header = line(data)?;
+        if header.is_empty() {
+          break Some(headers);
+        }
+        let colon = header.iter().position(|b| *b == b':')?;
+        let name = HeaderName::from_bytes(&header[..colon]).ok()?;
+        let value = header[colon + 1..].strip_prefix(b" ")?;
+        let value = HeaderValue::from_bytes(value).ok()?;
+        let _existed = headers.append(name, value);
+      }
+    }
+
+    let mut data = data;
+    if line(&mut data)? != key.as_bytes() {
+      return None;
+    }
+    let status = StatusCode::from_bytes(line(&mut data)?).ok()?;
+    let stored = from_utf8(line(&mut data)?).ok()?.parse().ok()?;
+    let varying = headers(&mut data)?;
+    let headers = headers(&mut data)?;
+
+    Some(Self {
+      status,
+      headers,
+      body: data.to_vec(),
+      stored: UNIX_EPOCH + Duration::from_millis(stored),
+      varying,
+    })
+  }
+}
+
+
+/// A trait for storing responses on behalf of a [`Cache`].
+///
+/// Storage is best effort: failures to store or retrieve an entry
+/// merely result in a cache miss.
+pub trait Storage: Send + Sync {
+  /// Retrieve the entry stored under the given key.
+  fn get(&self, key: &str) -> Option<Entry>;
+
+  /// Store an entry under the given key, replacing any existing one.
+  fn put(&self, key: &str, entry: Entry);
+
+  /// Remove the entry stored under the given key, if any.
+  fn remove(&self, key: &str);
+}
+
+
+/// The state of a [`MemoryStorage`].
+#[derive(Debug, Default)]
+struct Lru {
+  /// The stored entries, along with the time they were last used at.
+  entries: HashMap<String, (Entry, u64)>,
+  /// The logical time, advanced on each use of an entry.
+  time: u64,
+}
+
+
+/// A [`Storage`] keeping a bounded number of entries in memory,
+/// evicting the least recently used one when full.
+#[derive(Debug)]
+pub struct MemoryStorage {
+  capacity: usize,
+  lru: Mutex<Lru>,
+}
+
+impl MemoryStorage {
+  /// Create a new `MemoryStorage` holding at most `capacity` entries.
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      lru: Mutex::default(),
+    }
+  }
+
+  /// Retrieve the number of entries currently stored.
+  pub fn len(&self) -> usize {
+    self.lru.lock().unwrap().entries.len()
+  }
+
+  /// Check whether no entries are currently stored.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl Default for MemoryStorage {
+  /// Create a new `MemoryStorage` holding at most 128 entries.
+  fn default() -> Self {
+    Self::new(128)
+  }
+}
+
+impl Storage for MemoryStorage {
+  fn get(&self, key: &str) -> Option<Entry> {
+    let mut lru = self.lru.lock().unwrap();
+    lru.time += 1;
+    let time = lru.time;
+    lru.entries.get_mut(key).map(|(entry, used)| {
+      *used = time;
+      entry.clone()
+    })
+  }
+
+  fn put(&self, key: &str, entry: Entry) {
+    if self.capacity == 0 {
+      return;
+    }
+
+    let mut lru = self.lru.lock().unwrap();
+    if !lru.entries.contains_key(key) && lru.entries.len() >= self.capacity {
+      let oldest = lru
+        .entries
+        .iter()
+        .min_by_key(|(_, (_, used))| *used)
+        .map(|(key, _)| key.clone());
+      if let Some(oldest) = oldest {
+        let _entry = lru.entries.remove(&oldest);
+      }
+    }
+
+    lru.time += 1;
+    let time = lru.time;
+    let _prev = lru.entries.insert(key.to_string(), (entry, time));
+  }
+
+  fn remove(&self, key: &str) {
+    let _entry = self.lru.lock().unwrap().entries.remove(key);
+  }
+}
+
+
+/// Calculate the 64 bit FNV-1a hash of the given data.
+fn fnv1a(data: &[u8]) -> u64 {
+  data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+    (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+  })
+}
+
+
+/// A [`Storage`] keeping entries as files in a directory.
+///
+/// Each entry is stored in a file named after a hash of its key.
+/// Entries are not evicted automatically.
+#[derive(Debug)]
+pub struct DirStorage {
+  dir: PathBuf,
+}
+
+impl DirStorage {
+  /// Create a new `DirStorage` storing entries in the given directory,
+  /// creating it if it does not exist.
+  pub fn new<P>(dir: P) -> Result<Self, IoError>
+  where
+    P: AsRef<Path>,
+  {
+    let dir = dir.as_ref().to_path_buf();
+    let () = create_dir_all(&dir)?;
+    Ok(Self { dir })
+  }
+
+  /// Retrieve the directory entries are stored in.
+  pub fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  fn path(&self, key: &str) -> PathBuf {
+    self.dir.join(format!("{:016x}", fnv1a(key.as_bytes())))
+  }
+}
+
+impl Storage for DirStorage {
+  fn get(&self, key: &str) -> Option<Entry> {
+    let data = read(self.path(key)).ok()?;
+    Entry::from_bytes(key, &data)
+  }
+
+  fn put(&self, key: &str, entry: Entry) {
+    // Write to a temporary file first, so that concurrent readers
+    // never observe a partially written entry.
+    let path = self.path(key);
+    let tmp = path.with_extension("tmp");
+    if write(&tmp, entry.to_bytes(key)).is_ok() {
+      let _result = rename(&tmp, &path);
+    }
+  }
+
+  fn remove(&self, key: &str) {
+    let _result = remove_file(self.path(key));
+  }
+}
+
+
+/// Check whether a response may be stored and, if so, retrieve the
+/// names of the request headers it varies on.
+fn storable(
+  request: &HeaderMap,
+  response: &Response<Vec<u8>>,
+  shared: bool,
+) -> Option<Vec<HeaderName>> {
+  if response.status() != StatusCode::OK {
+    return None;
+  }
+
+  let directives = Directives::new(response.headers());
+  if directives.no_store || (shared && directives.private) {
+    return None;
+  }
+
+  // Shared caches must not store responses to authorized requests,
+  // unless explicitly allowed to.
+  if shared
+    && request.contains_key(AUTHORIZATION)
+    && !(directives.public || directives.must_revalidate || directives.s_maxage.is_some())
+  {
+    return None;
+  }
+
+  let validated =
+    response.headers().contains_key(ETAG) || response.headers().contains_key(LAST_MODIFIED);
+  let expires = directives.max_age.is_some() || (shared && directives.s_maxage.is_some());
+  if !validated && !expires {
+    return None;
+  }
+
+  vary(response.headers())
+}
+
+
+/// The state of a [`Cache`], shared with background revalidations.
+struct Inner<T, S> {
+  transport: T,
+  storage: S,
+}
+
+impl<T, S> Inner<T, S>
+where
+  T: Transport,
+  S: Storage,
+{
+  /// Send a request, revalidating the provided stored response, if
+  /// any, and update the storage with the outcome.
+  async fn fetch(
+    &self,
+    mut request: Request<Bytes>,
+    key: String,
+    cached: Option<Entry>,
+    shared: bool,
+  ) -> Result<Response<Vec<u8>>, T::Error> {
+    if let Some(entry) = &cached {
+      if let Some(etag) = entry.etag() {
+        let _prev = request.headers_mut().insert(IF_NONE_MATCH, etag.clone());
+      }
+      if let Some(last_modified) = entry.last_modified() {
+        let _prev = request
+          .headers_mut()
+          .insert(IF_MODIFIED_SINCE, last_modified.clone());
+      }
+    }
+
+    let headers = request.headers().clone();
+    let response = self.transport.send(request).await?;
+    let now = SystemTime::now();
+
+    match (response.status(), cached) {
+      (StatusCode::NOT_MODIFIED, Some(mut entry)) => {
+        let () = entry.update(response.headers(), now);
+        let () = self.storage.put(&key, entry.clone());
+        Ok(entry.into_response(now))
+      },
+      (StatusCode::OK, _) => {
+        match storable(&headers, &response, shared) {
+          Some(vary) => {
+            let entry = Entry::new(&headers, &response, &vary, now);
+            let () = self.storage.put(&key, entry);
+          },
+          None => {
+            let () = self.storage.remove(&key);
+          },
+        }
+        Ok(response)
+      },
+      _ => Ok(response),
+    }
+  }
+}
+
+
+/// A [`Transport`] caching responses as per RFC 9111.
+///
+/// Only successful responses (status 200) to `GET` requests are
+/// stored, and only if they carry an `ETag` or `Last-Modified` header
+/// or an explicit freshness lifetime, as provided by the `max-age` or
+/// `s-maxage` directives. By default the cache acts as a private
+/// cache. Requests already carrying an `If-None-Match` or
+/// `If-Modified-Since` header are passed through unchanged, and so are
+/// the responses to them.
+///
+/// Stale responses that may be served while being revalidated, as per
+/// their `stale-while-revalidate` directive, are revalidated in a task
+/// spawned on the current `tokio` runtime. Outside of a runtime they
+/// are revalidated before being served instead.
+pub struct Cache<T, S = MemoryStorage> {
+  inner: Arc<Inner<T, S>>,
+  shared: bool,
+}
+
+impl<T> Cache<T> {
+  /// Create a new `Cache` keeping responses in a [`MemoryStorage`]
+  /// with default capacity.
+  pub fn new(transport: T) -> Self {
+    Self::with_storage(transport, MemoryStorage::default())
+  }
+}
+
+impl<T, S> Cache<T, S> {
+  /// Create a new `Cache` keeping responses in the provided storage.
+  pub fn with_storage(transport: T, storage: S) -> Self {
+    Self {
+      inner: Arc::new(Inner { transport, storage }),
+      shared: false,
+    }
+  }
+
+  /// Set whether the cache acts as a shared cache.
+  ///
+  /// Shared caches do not store responses marked as `private` and
+  /// prefer the `s-maxage` directive over `max-age`.
+  pub fn with_shared(mut self, shared: bool) -> Self {
+    self.shared = shared;
+    self
+  }
+
+  /// Retrieve the transport used for sending requests.
+  pub fn transport(&self) -> &T {
+    &self.inner.transport
+  }
+
+  /// Retrieve the storage responses are kept in.
+  pub fn storage(&self) -> &S {
+    &self.inner.storage
+  }
+}
+
+impl<T, S> Debug for Cache<T, S>
+where
+  T: Debug,
+  S: Debug,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("Cache")
+      .field("transport", &self.inner.transport)
+      .field("storage", &self.inner.storage)
+      .field("shared", &self.shared)
+      .finish()
+  }
+}
+
+impl<T, S> Transport for Cache<T, S>
+where
+  T: Transport + Send + Sync + 'static,
+  S: Storage + 'static,
+{
+  type Error = T::Error;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let inner = &self.inner;
+    let shared = self.shared;
+    let key = request.uri().to_string();
+    let policy = request
+      .extensions()
+      .get::<CachePolicy>()
+      .copied()
+      .unwrap_or_default();
+
+    if policy == CachePolicy::Bypass {
+      return inner.transport.send(request);
+    }
+
+    let method = request.method().clone();
+    if method != Method::GET {
+      if method.is_safe() {
+        return inner.transport.send(request);
+      }
+
+      // Successful requests using an unsafe method invalidate the
+      // response stored for the target URI.
+      return Box::pin(async move {
+        let response = inner.transport.send(request).await?;
+        if response.status().is_success() || response.status().is_redirection() {
+          let () = inner.storage.remove(&key);
+        }
+        Ok(response)
+      });
+    }
+
+    let directives = Directives::new(request.headers());
+    let conditional = request.headers().contains_key(IF_NONE_MATCH)
+      || request.headers().contains_key(IF_MODIFIED_SINCE);
+    if conditional || directives.no_store {
+      return inner.transport.send(request);
+    }
+
+    let now = SystemTime::now();
+    let cached = inner
+      .storage
+      .get(&key)
+      .filter(|entry| entry.matches(request.headers()));
+
+    if let Some(entry) = &cached {
+      let freshness = if policy == CachePolicy::ForceCache {
+        Freshness::Fresh
+      } else if directives.no_cache || directives.max_age == Some(0) {
+        Freshness::Stale
+      } else {
+        entry.freshness(now, shared)
+      };
+
+      match freshness {
+        Freshness::Fresh => {
+          let response = entry.clone().into_response(now);
+          return Box::pin(async move { Ok(response) });
+        },
+        Freshness::StaleWhileRevalidate => {
+          if let Ok(handle) = Handle::try_current() {
+            let response = entry.clone().into_response(now);
+            let inner = Arc::clone(inner);
+            let _handle = handle.spawn(async move {
+              let _result = inner.fetch(request, key, cached, shared).await;
+            });
+            return Box::pin(async move { Ok(response) });
+          }
+        },
+        Freshness::Stale => (),
+      }
+    }
+
+    Box::pin(inner.fetch(request, key, cached, shared))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  fn entry(etag: &'static str) -> Entry {
+    let mut headers = HeaderMap::new();
+    let _prev = headers.insert(ETAG, HeaderValue::from_static(etag));
+    let _existed = headers.append("x-multi", HeaderValue::from_static("a"));
+    let _existed = headers.append("x-multi", HeaderValue::from_static("b: c"));
+    Entry {
+      status: StatusCode::OK,
+      headers,
+      body: b"line 1\n\nline 2\n".to_vec(),
+      stored: UNIX_EPOCH + Duration::from_millis(1_792_324_800_123),
+      varying: HeaderMap::new(),
+    }
+  }
+
+  fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+    pairs
+      .iter()
+      .map(|(name, value)| {
+        (
+          HeaderName::from_static(name),
+          HeaderValue::from_static(value),
+        )
+      })
+      .collect()
+  }
+
+
+  /// Check that entries survive a round trip through their serialized
+  /// form.
+  #[test]
+  fn entry_serialization() {
+    let key = "https://example.com/orders?page=1";
+    let mut entry = entry("\"v1\"");
+    let data = entry.to_bytes(key);
+    assert_eq!(Entry::from_bytes(key, &data), Some(entry.clone()));
+    assert_eq!(Entry::from_bytes("https://example.com/orders", &data), None);
+    assert_eq!(Entry::from_bytes(key, &data[..key.len() + 3]), None);
+
+    entry.varying = headers(&[("accept-language", "en"), ("accept", "text/plain")]);
+    let data = entry.to_bytes(key);
+    assert_eq!(Entry::from_bytes(key, &data), Some(entry));
+  }
+
+  /// Check that headers of `304` responses replace stored ones.
+  #[test]
+  fn entry_update() {
+    let mut entry = entry("\"v1\"");
+    let now = SystemTime::now();
+    let () = entry.update(&headers(&[("etag", "\"v2\"")]), now);
+    assert_eq!(entry.etag(), Some(&HeaderValue::from_static("\"v2\"")));
+    assert_eq!(entry.headers.get_all("x-multi").iter().count(), 2);
+    assert_eq!(entry.stored, now);
+  }
+
+  /// Make sure that the content length as well as hop-by-hop headers
+  /// of `304` responses are not merged into stored ones.
+  #[test]
+  fn entry_update_skipped() {
+    let mut entry = entry("\"v1\"");
+    let _prev = entry
+      .headers
+      .insert("content-length", HeaderValue::from_static("15"));
+    let update = headers(&[
+      ("content-length", "0"),
+      ("transfer-encoding", "chunked"),
+      ("connection", "keep-alive, X-Hop"),
+      ("keep-alive", "timeout=5"),
+      ("x-hop", "1"),
+      ("x-multi", "d"),
+    ]);
+    let () = entry.update(&update, SystemTime::now());
+
+    assert_eq!(entry.headers["content-length"], "15");
+    assert_eq!(entry.headers.get("transfer-encoding"), None);
+    assert_eq!(entry.headers.get("connection"), None);
+    assert_eq!(entry.headers.get("keep-alive"), None);
+    assert_eq!(entry.headers.get("x-hop"), None);
+    assert_eq!(entry.headers["x-multi"], "d");
+  }
+
+  /// Check that we parse `Cache-Control` directives correctly.
+  #[test]
+  fn directive_parsing() {
+    let mut headers = headers(&[(
+      "cache-control",
+      "Max-Age=60, private=\"set-cookie\", stale-while-revalidate=30",
+    )]);
+    let _existed = headers.append(
+      CACHE_CONTROL,
+      HeaderValue::from_static("no-cache,s-maxage=\"10\""),
+    );
+    assert_eq!(
+      Directives::new(&headers),
+      Directives {
+        no_cache: true,
+        private: true,
+        max_age: Some(60),
+        s_maxage: Some(10),
+        stale_while_revalidate: Some(30),
+        ..Default::default()
+      }
+    );
+    assert_eq!(Directives::new(&HeaderMap::new()), Directives::default());
+  }
+
+  /// Check that we determine the freshness of stored responses
+  /// correctly.
+  #[test]
+  fn freshness() {
+    let now = SystemTime::now();
+    let later = |secs| now + Duration::from_secs(secs);
+    let entry = |pairs| {
+      let mut response = Response::new(Vec::new());
+      *response.headers_mut() = headers(pairs);
+      Entry::new(&HeaderMap::new(), &response, &[], now)
+    };
+
+    let fresh = entry(&[("cache-control", "max-age=60")]);
+    assert_eq!(fresh.freshness(now, false), Freshness::Fresh);
+    assert_eq!(fresh.freshness(later(60), false), Freshness::Stale);
+
+    let aged = entry(&[("cache-control", "max-age=60"), ("age", "59")]);
+    assert_eq!(aged.freshness(now, false), Freshness::Fresh);
+    assert_eq!(aged.freshness(later(1), false), Freshness::Stale);
+
+    let shared = entry(&[("cache-control", "max-age=60, s-maxage=0")]);
+    assert_eq!(shared.freshness(now, false), Freshness::Fresh);
+    assert_eq!(shared.freshness(now, true), Freshness::Stale);
+
+    let swr = entry(&[
+      ("cache-control", "max-age=1, stale-while-revalidate=10"),
+      ("age", "5"),
+    ]);
+    assert_eq!(swr.freshness(now, false), Freshness::StaleWhileRevalidate);
+    assert_eq!(swr.freshness(later(6), false), Freshness::Stale);
+
+    let must = entry(&[(
+      "cache-control",
+      "max-age=1, stale-while-revalidate=10, must-revalidate",
+    )]);
+    assert_eq!(must.freshness(later(2), false), Freshness::Stale);
+
+    let no_cache = entry(&[("cache-control", "no-cache, max-age=60")]);
+    assert_eq!(no_cache.freshness(now, false), Freshness::Stale);
+  }
+
+  /// Check that stored responses are only used for requests matching
+  /// their `Vary` header.
+  #[test]
+  fn vary_matching() {
+    let request = headers(&[("accept-language", "en"), ("accept", "text/plain")]);
+    let mut response = Response::new(Vec::new());
+    *response.headers_mut() = headers(&[("vary", "Accept-Language")]);
+    let names = vary(response.headers()).unwrap();
+    let entry = Entry::new(&request, &response, &names, SystemTime::now());
+
+    assert!(entry.matches(&request));
+    assert!(entry.matches(&headers(&[("accept-language", "en")])));
+    assert!(!entry.matches(&headers(&[("accept-language", "de")])));
+    assert!(!entry.matches(&HeaderMap::new()));
+
+    *response.headers_mut() = headers(&[("vary", "accept, *")]);
+    assert_eq!(vary(response.headers()), None);
+  }
+
+  /// Check that the least recently used entry gets evicted from a
+  /// `MemoryStorage`.
+  #[test]
+  fn lru_eviction() {
+    let storage = MemoryStorage::new(2);
+    let () = storage.put("a", entry("\"a\""));
+    let () = storage.put("b", entry("\"b\""));
+    assert!(storage.get("a").is_some());
+
+    let () = storage.put("c", entry("\"c\""));
+    assert_eq!(storage.len(), 2);
+    assert!(storage.get("b").is_none());
+    assert!(storage.get("a").is_some());
+    assert!(storage.get("c").is_some());
+
+    let () = storage.remove("a");
+    assert_eq!(storage.len(), 1);
+
+    let storage = MemoryStorage::new(0);
+    let () = storage.put("a", entry("\"a\""));
+    assert!(storage.is_empty());
+  }
+}
@@ -0,0 +1,443 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::error::Error as StdError;
+#[cfg(feature = "client")]
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::future::Future;
+use std::io::Error as IoError;
+use std::pin::Pin;
+use std::time::Duration;
+
+#[cfg(feature = "client")]
+use http::HeaderMap;
+use http::Request;
+use http::Response;
+#[cfg(feature = "client")]
+use http::Uri;
+
+#[cfg(feature = "client")]
+use tokio::time::sleep;
+#[cfg(feature = "client")]
+use tokio::time::timeout;
+#[cfg(feature = "client")]
+use tokio::time::Instant;
+
+#[cfg(any(
+  feature = "brotli",
+  feature = "deflate",
+  feature = "gzip",
+  feature = "zstd"
+))]
+use crate::decompress;
+#[cfg(feature = "client")]
+use crate::rate_limit::host;
+#[cfg(feature = "client")]
+use crate::request;
+#[cfg(feature = "client")]
+use crate::trace;
+#[cfg(feature = "client")]
+use crate::Auth;
+use crate::AuthError;
+use crate::Bytes;
+#[cfg(feature = "client")]
+use crate::Endpoint;
+#[cfg(feature = "client")]
+use crate::Observer;
+#[cfg(feature = "client")]
+use crate::RateLimiter;
+#[cfg(feature = "client")]
+use crate::Str;
+#[cfg(feature = "client")]
+use crate::Timeout;
+
+
+/// A boxed future as returned by [`Transport::send`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+
+/// A trait representing the means of actually sending HTTP requests.
+pub trait Transport {
+  /// The error the transport may report.
+  type Error: StdError + 'static;
+
+  /// Send a request, returning the received response.
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>>;
+}
+
+
+/// An error as reported when issuing a request to an endpoint.
+#[derive(Debug)]
+pub enum Error<E, T> {
+  /// An error reported by the endpoint.
+  Endpoint(E),
+  /// An error reported by the transport.
+  Transport(T),
+  /// The request did not complete within the given timeout.
+  Timeout(Duration),
+  /// The body of the response could not be decompressed.
+  Decompress(IoError),
+  /// The authentication provider failed to apply credentials.
+  Auth(AuthError),
+}
+
+impl<E, T> Display for Error<E, T>
+where
+  E: Display,
+  T: Display,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Error::Endpoint(err) => write!(fmt, "{}", err),
+      Error::Transport(err) => write!(fmt, "{}", err),
+      Error::Timeout(timeout) => write!(fmt, "request timed out after {:?}", timeout),
+      Error::Decompress(err) => write!(fmt, "failed to decompress response body: {}", err),
+      Error::Auth(err) => write!(fmt, "failed to authenticate request: {}", err),
+    }
+  }
+}
+
+impl<E, T> StdError for Error<E, T>
+where
+  E: StdError + 'static,
+  T: StdError + 'static,
+{
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Error::Endpoint(err) => err.source(),
+      Error::Transport(err) => err.source(),
+      Error::Timeout(..) => None,
+      Error::Decompress(err) => err.source(),
+      Error::Auth(err) => err.source(),
+    }
+  }
+}
+
+
+impl<E, T> Error<E, T> {
+  /// Retrieve the name of the error's variant, resolving endpoint
+  /// errors to the variant of the endpoint's error.
+  #[cfg(feature = "client")]
+  fn variant<P>(&self) -> &'static str
+  where
+    P: Endpoint<Error = E>,
+  {
+    match self {
+      Error::Endpoint(err) => P::error_variant(err),
+      Error::Transport(..) => "Transport",
+      Error::Timeout(..) => "Timeout",
+      Error::Decompress(..) => "Decompress",
+      Error::Auth(..) => "Auth",
+    }
+  }
+}
+
+
+/// A client for issuing requests to endpoints over a [`Transport`].
+#[cfg(feature = "client")]
+pub struct Client<T> {
+  /// The transport used for sending requests.
+  transport: T,
+  /// The base URL to use for endpoints not providing their own.
+  base_url: Option<Str>,
+  /// The timeout to apply to requests to endpoints not providing one.
+  timeout: Option<Duration>,
+  /// The rate limiter to consult before sending requests.
+  rate_limiter: Option<RateLimiter>,
+  /// The observer to inform about issued requests.
+  observer: Option<Box<dyn Observer + Send + Sync>>,
+  /// The authentication provider to use for requests not covered by a
+  /// base URL specific one.
+  auth: Option<Box<dyn Auth + Send + Sync>>,
+  /// Authentication providers for requests to specific base URLs.
+  base_url_auth: Vec<(String, Box<dyn Auth + Send + Sync>)>,
+  /// The maximum size of decompressed response bodies.
+  #[cfg(any(
+    feature = "brotli",
+    feature = "deflate",
+    feature = "gzip",
+    feature = "zstd"
+  ))]
+  decompression_limit: usize,
+}
+
+#[cfg(feature = "client")]
+impl<T> Debug for Client<T>
+where
+  T: Debug,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    let mut debug = fmt.debug_struct("Client");
+    let debug = debug
+      .field("transport", &self.transport)
+      .field("base_url", &self.base_url)
+      .field("timeout", &self.timeout)
+      .field("rate_limiter", &self.rate_limiter)
+      .field("observer", &self.observer.as_ref().map(|_| ".."))
+      .field("auth", &self.auth.as_ref().map(|_| ".."))
+      .field(
+        "base_url_auth",
+        &self
+          .base_url_auth
+          .iter()
+          .map(|(base_url, _)| base_url)
+          .collect::<Vec<_>>(),
+      );
+    #[cfg(any(
+      feature = "brotli",
+      feature = "deflate",
+      feature = "gzip",
+      feature = "zstd"
+    ))]
+    let debug = debug.field("decompression_limit", &self.decompression_limit);
+    debug.finish()
+  }
+}
+
+#[cfg(feature = "client")]
+impl<T> Client<T>
+where
+  T: Transport,
+{
+  /// Create a new client using the given transport.
+  pub fn new(transport: T) -> Self {
+    Self {
+      transport,
+      base_url: None,
+      timeout: None,
+      rate_limiter: None,
+      observer: None,
+      auth: None,
+      base_url_auth: Vec::new(),
+      #[cfg(any(
+        feature = "brotli",
+        feature = "deflate",
+        feature = "gzip",
+        feature = "zstd"
+      ))]
+      decompression_limit: decompress::DEFAULT_LIMIT,
+    }
+  }
+
+  /// Set the base URL to use for endpoints not providing one.
+  pub fn with_base_url<S>(mut self, base_url: S) -> Self
+  where
+    S: Into<Str>,
+  {
+    self.base_url = Some(base_url.into());
+    self
+  }
+
+  /// Set the timeout to apply to requests to endpoints not providing
+  /// one themselves.
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Set the rate limiter to honor when issuing requests.
+  pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+    self.rate_limiter = Some(rate_limiter);
+    self
+  }
+
+  /// Set the observer to inform about requests being issued.
+  pub fn with_observer<O>(mut self, observer: O) -> Self
+  where
+    O: Observer + Send + Sync + 'static,
+  {
+    self.observer = Some(Box::new(observer));
+    self
+  }
+
+  /// Set the authentication provider to apply to requests not covered
+  /// by a provider for a specific base URL.
+  pub fn with_auth<A>(mut self, auth: A) -> Self
+  where
+    A: Auth + Send + Sync + 'static,
+  {
+    self.auth = Some(Box::new(auth));
+    self
+  }
+
+  /// Set the authentication provider to apply to requests whose URI
+  /// starts with the given base URL.
+  ///
+  /// URLs are compared with their scheme and host in lower case and
+  /// with the effective port, i.e., a provider for
+  /// `https://Example.com/v2` applies to requests to
+  /// `https://example.com:443/v2/orders`. If multiple base URLs match a
+  /// request, the longest one wins.
+  pub fn with_base_url_auth<A>(mut self, base_url: &str, auth: A) -> Self
+  where
+    A: Auth + Send + Sync + 'static,
+  {
+    let base_url = base_url
+      .parse::<Uri>()
+      .ok()
+      .and_then(|uri| normalize(&uri))
+      .unwrap_or_else(|| base_url.trim_end_matches('/').to_string());
+    self.base_url_auth.retain(|(other, _)| *other != base_url);
+    self.base_url_auth.push((base_url, Box::new(auth)));
+    self
+  }
+
+  /// Set the maximum size of decompressed response bodies.
+  ///
+  /// Responses whose bodies decompress to more than `limit` bytes are
+  /// reported as [`Error::Decompress`]. The default is 64 MiB.
+  #[cfg(any(
+    feature = "brotli",
+    feature = "deflate",
+    feature = "gzip",
+    feature = "zstd"
+  ))]
+  pub fn with_decompression_limit(mut self, limit: usize) -> Self {
+    self.decompression_limit = limit;
+    self
+  }
+
+  /// Retrieve the transport used by this client.
+  pub fn transport(&self) -> &T {
+    &self.transport
+  }
+
+  /// Issue a request to the endpoint `E`.
+  pub async fn issue<E>(&self, input: &E::Input) -> Result<E::Output, Error<E::Error, T::Error>>
+  where
+    E: Endpoint,
+  {
+    let (_headers, output) = self.issue_with_headers::<E>(input).await?;
+    Ok(output)
+  }
+
+  /// Issue a request to the endpoint `E`, returning the output along
+  /// with the headers of the response.
+  pub(crate) async fn issue_with_headers<E>(
+    &self,
+    input: &E::Input,
+  ) -> Result<(HeaderMap, E::Output), Error<E::Error, T::Error>>
+  where
+    E: Endpoint,
+  {
+    let start = Instant::now();
+    let name = E::NAME;
+    if let Some(observer) = &self.observer {
+      observer.started(name);
+    }
+
+    let result = trace::instrument::<E, _>(self.issue_inner::<E>(input)).await;
+
+    if let Some(observer) = &self.observer {
+      let error = result.as_ref().err().map(Error::variant::<E>);
+      observer.finished(name, error, start.elapsed());
+    }
+    result
+  }
+
+  /// Find the authentication provider to use for a request to the
+  /// given URI.
+  fn auth_for(&self, uri: &Uri) -> Option<&(dyn Auth + Send + Sync)> {
+    let uri = normalize(uri);
+    self
+      .base_url_auth
+      .iter()
+      .filter(|(base_url, _)| {
+        uri
+          .as_deref()
+          .and_then(|uri| uri.strip_prefix(base_url.as_str()))
+          .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+      })
+      .max_by_key(|(base_url, _)| base_url.len())
+      .map(|(_, auth)| auth.as_ref())
+      .or(self.auth.as_deref())
+  }
+
+  async fn issue_inner<E>(
+    &self,
+    input: &E::Input,
+  ) -> Result<(HeaderMap, E::Output), Error<E::Error, T::Error>>
+  where
+    E: Endpoint,
+  {
+    let mut request = request::<E>(self.base_url.as_deref(), input).map_err(Error::Endpoint)?;
+    let uri = request.uri().clone();
+
+    #[cfg(any(
+      feature = "brotli",
+      feature = "deflate",
+      feature = "gzip",
+      feature = "zstd"
+    ))]
+    let () = decompress::advertise(request.headers_mut());
+
+    if let Some(timeout) = self.timeout {
+      if request.extensions().get::<Timeout>().is_none() {
+        request.extensions_mut().insert(Timeout(timeout));
+      }
+    }
+    let request_timeout = request.extensions().get::<Timeout>().copied();
+
+    if let Some(rate_limiter) = &self.rate_limiter {
+      let delay = rate_limiter.delay::<E>(&uri);
+      if !delay.is_zero() {
+        sleep(delay).await;
+      }
+    }
+
+    // Authentication happens last, so that providers signing requests
+    // see all headers and time stamps are not invalidated by waiting
+    // for the rate limiter.
+    if let Some(auth) = self.auth_for(&uri) {
+      let () = auth.authenticate(&mut request).map_err(Error::Auth)?;
+    }
+
+    let sent = Instant::now();
+    let response = self.transport.send(request);
+    let response = match request_timeout {
+      Some(Timeout(duration)) => timeout(duration, response)
+        .await
+        .map_err(|_elapsed| Error::Timeout(duration))?,
+      None => response.await,
+    }
+    .map_err(Error::Transport)?;
+
+    if let Some(observer) = &self.observer {
+      observer.responded(E::NAME, response.status(), sent.elapsed());
+    }
+    if let Some(rate_limiter) = &self.rate_limiter {
+      rate_limiter.observe(&uri, response.headers());
+    }
+
+    #[cfg(any(
+      feature = "brotli",
+      feature = "deflate",
+      feature = "gzip",
+      feature = "zstd"
+    ))]
+    let response = {
+      let mut response = response;
+      let () = decompress::decompress(&mut response, self.decompression_limit)
+        .map_err(Error::Decompress)?;
+      response
+    };
+
+    let (parts, body) = response.into_parts();
+    let output = E::evaluate(parts.status, &body).map_err(Error::Endpoint)?;
+    Ok((parts.headers, output))
+  }
+}
+
+
+/// Normalize a URI for matching it against base URLs, e.g., to
+/// `https://example.com:443/v2`, ignoring its query.
+#[cfg(feature = "client")]
+fn normalize(uri: &Uri) -> Option<String> {
+  let scheme = uri.scheme_str().unwrap_or("https").to_ascii_lowercase();
+  let host = host(uri)?;
+  let path = uri.path().trim_end_matches('/');
+  Some(format!("{scheme}://{host}{path}"))
+}
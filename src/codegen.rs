@@ -0,0 +1,942 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Generation of [`EndpointDef`] based endpoint
+//! definitions from OpenAPI 3.x specifications.
+//!
+//! For each operation in the specification an endpoint is generated,
+//! along with a type for its input comprising path and query
+//! parameters as well as the request body, if any. Schemas in the
+//! specification's components section are turned into types with
+//! `serde` support. Header and cookie parameters are not supported.
+//!
+//! The generated code refers to the `http`, `http-endpoint`, `serde`
+//! (with the `derive` feature), and `serde_json` crates, which
+//! consequently have to be dependencies of the crate using it.
+//!
+//! From a build script, usage typically looks like:
+//! ```no_run
+//! # use std::env::var_os;
+//! # use std::path::Path;
+//! let out_dir = var_os("OUT_DIR").unwrap();
+//! let output = Path::new(&out_dir).join("api.rs");
+//! http_endpoint::codegen::generate_file("api.yaml", output).unwrap();
+//! ```
+//! with the generated code being pulled in via
+//! `include!(concat!(env!("OUT_DIR"), "/api.rs"))`.
+
+use std::collections::BTreeSet;
+use std::error::Error as StdError;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::fmt::Write as _;
+use std::fs::read_to_string;
+use std::fs::write;
+use std::io::Error as IoError;
+use std::path::Path;
+use std::ptr;
+
+use http::StatusCode;
+
+use serde_json::Error as JsonError;
+use serde_json::Map;
+use serde_json::Value;
+
+use serde_yaml::Error as YamlError;
+
+
+/// The HTTP methods supported by OpenAPI, in the order we emit
+/// operations in.
+const METHODS: [&str; 8] = [
+  "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Rust keywords that can't be used as identifiers without escaping.
+const KEYWORDS: [&str; 39] = [
+  "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+  "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+  "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+  "use", "where", "while", "yield",
+];
+
+/// The prelude emitted at the start of all generated code.
+const PRELUDE: &str = r#"// Generated by http-endpoint's code generator. Do not edit.
+
+/// An error as reported by the API.
+#[derive(Clone, Debug, PartialEq, ::serde::Deserialize, ::serde::Serialize)]
+pub struct ApiError(pub ::serde_json::Value);
+
+impl ::std::fmt::Display for ApiError {
+  fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+    write!(fmt, "{}", self.0)
+  }
+}
+
+impl ::std::error::Error for ApiError {}
+
+/// Percent-encode a path segment or query value.
+#[allow(dead_code)]
+fn encode(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+  for byte in value.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+        encoded.push(char::from(byte))
+      },
+      _ => encoded.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  encoded
+}
+"#;
+
+
+/// An error as reported by the code generator.
+#[derive(Debug)]
+pub enum Error {
+  /// An I/O error.
+  Io(IoError),
+  /// The specification could not be parsed as JSON.
+  Json(JsonError),
+  /// The specification could not be parsed as YAML.
+  Yaml(YamlError),
+  /// The specification is invalid or unsupported.
+  Spec(String),
+}
+
+impl Display for Error {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Error::Io(err) => write!(fmt, "{}", err),
+      Error::Json(err) => write!(fmt, "failed to parse JSON specification: {}", err),
+      Error::Yaml(err) => write!(fmt, "failed to parse YAML specification: {}", err),
+      Error::Spec(err) => write!(fmt, "invalid specification: {}", err),
+    }
+  }
+}
+
+impl StdError for Error {
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Error::Io(err) => err.source(),
+      Error::Json(err) => err.source(),
+      Error::Yaml(err) => err.source(),
+      Error::Spec(..) => None,
+    }
+  }
+}
+
+impl From<IoError> for Error {
+  fn from(e: IoError) -> Self {
+    Error::Io(e)
+  }
+}
+
+impl From<JsonError> for Error {
+  fn from(e: JsonError) -> Self {
+    Error::Json(e)
+  }
+}
+
+impl From<YamlError> for Error {
+  fn from(e: YamlError) -> Self {
+    Error::Yaml(e)
+  }
+}
+
+
+/// Generate Rust code from an OpenAPI specification in JSON or YAML
+/// format.
+pub fn generate(spec: &str) -> Result<String, Error> {
+  let spec = if spec.trim_start().starts_with('{') {
+    serde_json::from_str::<Value>(spec)?
+  } else {
+    serde_yaml::from_str::<Value>(spec)?
+  };
+
+  let version = spec
+    .get("openapi")
+    .and_then(Value::as_str)
+    .ok_or_else(|| Error::Spec("missing OpenAPI version".into()))?;
+  if !version.starts_with("3.") {
+    return Err(Error::Spec(format!(
+      "unsupported OpenAPI version {}",
+      version
+    )));
+  }
+
+  let mut generator = Generator {
+    spec: &spec,
+    code: PRELUDE.to_string(),
+    types: BTreeSet::from(["ApiError".to_string()]),
+  };
+  generator.schemas()?;
+  generator.operations()?;
+  Ok(generator.code)
+}
+
+/// Generate Rust code from the OpenAPI specification file at `spec`,
+/// writing it to `output`.
+pub fn generate_file<P, Q>(spec: P, output: Q) -> Result<(), Error>
+where
+  P: AsRef<Path>,
+  Q: AsRef<Path>,
+{
+  let spec = read_to_string(spec)?;
+  let code = generate(&spec)?;
+  write(output, code)?;
+  Ok(())
+}
+
+
+/// A query or path parameter.
+struct Parameter {
+  /// The name of the parameter as used in the specification.
+  name: String,
+  /// The name of the field representing the parameter.
+  field: String,
+  /// The Rust type of the parameter, excluding any `Option`.
+  type_: String,
+  /// Whether the parameter is an array.
+  array: bool,
+  /// Whether the parameter is required.
+  required: bool,
+  /// The parameter's description, if any.
+  description: Option<String>,
+}
+
+
+struct Generator<'s> {
+  spec: &'s Value,
+  code: String,
+  /// The names of all types defined so far.
+  types: BTreeSet<String>,
+}
+
+impl<'s> Generator<'s> {
+  /// Resolve a reference, if `value` is one.
+  fn resolve(&self, value: &'s Value) -> Result<&'s Value, Error> {
+    match value.get("$ref").and_then(Value::as_str) {
+      Some(reference) => {
+        let pointer = reference
+          .strip_prefix('#')
+          .ok_or_else(|| Error::Spec(format!("unsupported reference {}", reference)))?;
+        self
+          .spec
+          .pointer(pointer)
+          .ok_or_else(|| Error::Spec(format!("unresolvable reference {}", reference)))
+      },
+      None => Ok(value),
+    }
+  }
+
+  /// Register the name of a type to be emitted, making sure that it
+  /// does not clash with any other.
+  fn define(&mut self, name: &str) -> Result<(), Error> {
+    if self.types.insert(name.to_string()) {
+      Ok(())
+    } else {
+      Err(Error::Spec(format!("multiple types named {}", name)))
+    }
+  }
+
+  /// Check whether `schema` refers to `target`, other than through an
+  /// array, meaning that a type for `target` containing it would be
+  /// of infinite size.
+  fn is_recursive(
+    &self,
+    schema: &'s Value,
+    target: &Value,
+    visited: &mut BTreeSet<&'s str>,
+  ) -> bool {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+      if !visited.insert(reference) {
+        return false;
+      }
+      return match self.resolve(schema) {
+        Ok(resolved) => ptr::eq(resolved, target) || self.is_recursive(resolved, target, visited),
+        Err(_) => false,
+      };
+    }
+
+    schema
+      .get("properties")
+      .and_then(Value::as_object)
+      .is_some_and(|properties| {
+        properties
+          .values()
+          .any(|schema| self.is_recursive(schema, target, visited))
+      })
+  }
+
+  /// Determine the Rust type representing the given schema.
+  fn type_of(&self, schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+      if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+        return type_name(name);
+      }
+    }
+
+    let type_ = match schema.get("type") {
+      Some(Value::String(type_)) => type_.as_str(),
+      Some(Value::Array(types)) => types
+        .iter()
+        .filter_map(Value::as_str)
+        .find(|type_| *type_ != "null")
+        .unwrap_or(""),
+      _ => "",
+    };
+    let format = schema.get("format").and_then(Value::as_str);
+
+    match (type_, format) {
+      ("integer", Some("int32")) => "i32".into(),
+      ("integer", _) => "i64".into(),
+      ("number", Some("float")) => "f32".into(),
+      ("number", _) => "f64".into(),
+      ("boolean", _) => "bool".into(),
+      ("string", _) => "String".into(),
+      ("array", _) => {
+        let items = schema.get("items").unwrap_or(&Value::Null);
+        format!("Vec<{}>", self.type_of(items))
+      },
+      _ => "::serde_json::Value".into(),
+    }
+  }
+
+  /// Emit types for all schemas in the components section.
+  fn schemas(&mut self) -> Result<(), Error> {
+    let schemas = match self.spec.pointer("/components/schemas") {
+      Some(Value::Object(schemas)) => schemas,
+      _ => return Ok(()),
+    };
+
+    for (name, schema) in sorted(schemas) {
+      let name = type_name(name);
+      let () = self.define(&name)?;
+      let variants = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .map(|values| values.iter().filter_map(Value::as_str).collect::<Vec<_>>());
+
+      match variants {
+        Some(variants) if !variants.is_empty() => self.enum_(&name, schema, &variants),
+        _ if schema.get("properties").is_some() => self.struct_(&name, schema)?,
+        _ => {
+          self.code.push('\n');
+          self.docs(schema.get("description").and_then(Value::as_str), "");
+          let _ = writeln!(self.code, "pub type {} = {};", name, self.type_of(schema));
+        },
+      }
+    }
+    Ok(())
+  }
+
+  /// Emit an enum for a string enumeration schema.
+  fn enum_(&mut self, name: &str, schema: &Value, variants: &[&str]) {
+    let code = &mut self.code;
+    code.push('\n');
+    docs(code, schema.get("description").and_then(Value::as_str), "");
+    code.push_str(
+      "#[derive(Clone, Copy, Debug, Eq, PartialEq, ::serde::Deserialize, ::serde::Serialize)]\n",
+    );
+    let _ = writeln!(code, "pub enum {} {{", name);
+    for variant in variants {
+      let _ = writeln!(code, "  #[serde(rename = {:?})]", variant);
+      let _ = writeln!(code, "  {},", type_name(variant));
+    }
+    code.push_str("}\n\n");
+
+    let _ = writeln!(code, "impl ::std::fmt::Display for {} {{", name);
+    code.push_str("  fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {\n");
+    code.push_str("    let value = match self {\n");
+    for variant in variants {
+      let _ = writeln!(code, "      Self::{} => {:?},", type_name(variant), variant);
+    }
+    code.push_str("    };\n");
+    code.push_str("    fmt.write_str(value)\n");
+    code.push_str("  }\n");
+    code.push_str("}\n");
+  }
+
+  /// Emit a struct for an object schema.
+  ///
+  /// Properties referring back to the schema itself are boxed.
+  fn struct_(&mut self, name: &str, schema: &'s Value) -> Result<(), Error> {
+    let required = required(schema);
+    let properties = schema
+      .get("properties")
+      .and_then(Value::as_object)
+      .map(sorted)
+      .unwrap_or_default();
+
+    let mut names = BTreeSet::new();
+    let mut fields = String::new();
+    for (property, property_schema) in properties {
+      let field = field_name(property);
+      if !names.insert(field.clone()) {
+        return Err(Error::Spec(format!(
+          "multiple properties of {} map to field {}",
+          name, field
+        )));
+      }
+
+      let mut type_ = self.type_of(property_schema);
+      if self.is_recursive(property_schema, schema, &mut BTreeSet::new()) {
+        type_ = format!("Box<{}>", type_);
+      }
+      let optional = !required.contains(property.as_str());
+      if optional {
+        type_ = format!("Option<{}>", type_);
+      }
+
+      docs(
+        &mut fields,
+        property_schema.get("description").and_then(Value::as_str),
+        "  ",
+      );
+      let mut attrs = Vec::new();
+      if optional {
+        attrs.push("default".to_string());
+      }
+      if field.trim_start_matches("r#") != property {
+        attrs.push(format!("rename = {:?}", property));
+      }
+      if optional {
+        attrs.push("skip_serializing_if = \"Option::is_none\"".to_string());
+      }
+      if !attrs.is_empty() {
+        let _ = writeln!(fields, "  #[serde({})]", attrs.join(", "));
+      }
+      let _ = writeln!(fields, "  pub {}: {},", field, type_);
+    }
+
+    let code = &mut self.code;
+    code.push('\n');
+    docs(code, schema.get("description").and_then(Value::as_str), "");
+    code.push_str("#[derive(Clone, Debug, PartialEq, ::serde::Deserialize, ::serde::Serialize)]\n");
+    let _ = writeln!(code, "pub struct {} {{", name);
+    code.push_str(&fields);
+    code.push_str("}\n");
+    Ok(())
+  }
+
+  /// Emit endpoints for all operations.
+  fn operations(&mut self) -> Result<(), Error> {
+    let paths = match self.spec.get("paths") {
+      Some(Value::Object(paths)) => paths,
+      _ => return Ok(()),
+    };
+
+    for (path, item) in sorted(paths) {
+      let item = self.resolve(item)?;
+      for method in METHODS {
+        if let Some(operation) = item.get(method) {
+          self.operation(path, method, item, operation)?;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Collect the path and query parameters of an operation.
+  fn parameters(&self, item: &'s Value, operation: &'s Value) -> Result<Vec<Parameter>, Error> {
+    let mut parameters = Vec::<Parameter>::new();
+    let all = [item, operation]
+      .into_iter()
+      .filter_map(|value| value.get("parameters").and_then(Value::as_array))
+      .flatten();
+
+    for parameter in all {
+      let parameter = self.resolve(parameter)?;
+      let location = parameter.get("in").and_then(Value::as_str);
+      if !matches!(location, Some("path") | Some("query")) {
+        continue;
+      }
+
+      let name = parameter
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::Spec("parameter without name".into()))?;
+      let schema = parameter.get("schema").unwrap_or(&Value::Null);
+      let schema = self.resolve(schema)?;
+      let array = schema.get("type").and_then(Value::as_str) == Some("array");
+      let type_ = if array {
+        self.type_of(schema.get("items").unwrap_or(&Value::Null))
+      } else {
+        self.type_of(schema)
+      };
+
+      let parameter = Parameter {
+        name: name.to_string(),
+        field: field_name(name),
+        type_,
+        array,
+        required: location == Some("path")
+          || parameter.get("required").and_then(Value::as_bool) == Some(true),
+        description: parameter
+          .get("description")
+          .and_then(Value::as_str)
+          .map(str::to_string),
+      };
+
+      // Operation level parameters override path item level ones.
+      parameters.retain(|other| other.name != parameter.name);
+      parameters.push(parameter);
+    }
+    Ok(parameters)
+  }
+
+  /// Retrieve the type of the JSON content of a request body or
+  /// response, if any.
+  fn content_type(&self, value: &'s Value) -> Result<Option<String>, Error> {
+    let value = self.resolve(value)?;
+    let schema = value
+      .get("content")
+      .and_then(Value::as_object)
+      .and_then(|content| {
+        content
+          .iter()
+          .find(|(media, _)| media.starts_with("application/json"))
+      })
+      .and_then(|(_, media)| media.get("schema"));
+
+    Ok(schema.map(|schema| self.type_of(schema)))
+  }
+
+  /// Emit the endpoint and input type for an operation.
+  fn operation(
+    &mut self,
+    path: &str,
+    method: &str,
+    item: &'s Value,
+    operation: &'s Value,
+  ) -> Result<(), Error> {
+    let name = match operation.get("operationId").and_then(Value::as_str) {
+      Some(id) => type_name(id),
+      None => type_name(&format!("{} {}", method, path)),
+    };
+
+    let parameters = self.parameters(item, operation)?;
+    let mut fields = BTreeSet::new();
+    for parameter in &parameters {
+      if !fields.insert(parameter.field.as_str()) {
+        return Err(Error::Spec(format!(
+          "multiple parameters of {} map to field {}",
+          name, parameter.field
+        )));
+      }
+    }
+    let (path_params, query_params) = parameters
+      .iter()
+      .partition::<Vec<_>, _>(|parameter| path.contains(&format!("{{{}}}", parameter.name)));
+    let body = match operation.get("requestBody") {
+      Some(body) => Some(
+        self
+          .content_type(body)?
+          .unwrap_or_else(|| "::serde_json::Value".into()),
+      ),
+      None => None,
+    };
+    if body.is_some() && fields.contains("body") {
+      return Err(Error::Spec(format!(
+        "parameter of {} clashes with request body field",
+        name
+      )));
+    }
+
+    let () = self.define(&name)?;
+    let () = self.define(&format!("{}Error", name))?;
+
+    let mut ok = Vec::new();
+    let mut err = Vec::new();
+    let mut output = None;
+    let responses = operation
+      .get("responses")
+      .and_then(Value::as_object)
+      .map(sorted)
+      .unwrap_or_default();
+    for (status, response) in responses {
+      let status = match status
+        .parse::<u16>()
+        .ok()
+        .and_then(|s| StatusCode::from_u16(s).ok())
+      {
+        Some(status) => status,
+        None => continue,
+      };
+      let constant = match status_constant(status) {
+        Some(constant) => constant,
+        None => continue,
+      };
+
+      if status.is_success() {
+        if output.is_none() {
+          output = self.content_type(response)?;
+        }
+        ok.push((status, constant));
+      } else {
+        err.push((status, constant));
+      }
+    }
+
+    // Emit the input type.
+    let input = if parameters.is_empty() && body.is_none() {
+      "()".to_string()
+    } else {
+      let input = format!("{}Input", name);
+      let () = self.define(&input)?;
+      let code = &mut self.code;
+      code.push('\n');
+      let _ = writeln!(code, "/// The input to the [`{}`] endpoint.", name);
+      code.push_str("#[derive(Clone, Debug, PartialEq)]\n");
+      let _ = writeln!(code, "pub struct {} {{", input);
+      for parameter in &parameters {
+        docs(code, parameter.description.as_deref(), "  ");
+        let mut type_ = parameter.type_.clone();
+        if parameter.array {
+          type_ = format!("Vec<{}>", type_);
+        }
+        if !parameter.required {
+          type_ = format!("Option<{}>", type_);
+        }
+        let _ = writeln!(code, "  pub {}: {},", parameter.field, type_);
+      }
+      if let Some(body) = &body {
+        code.push_str("  /// The request body.\n");
+        let _ = writeln!(code, "  pub body: {},", body);
+      }
+      code.push_str("}\n");
+      input
+    };
+
+    let template = if query_params.is_empty() {
+      path.to_string()
+    } else {
+      let query = query_params
+        .iter()
+        .map(|parameter| format!("{}={{{}}}", parameter.name, parameter.name))
+        .collect::<Vec<_>>()
+        .join("&");
+      format!("{}?{}", path, query)
+    };
+
+    // Emit the endpoint itself.
+    let code = &mut self.code;
+    code.push_str("\n::http_endpoint::EndpointDef! {\n");
+    let summary = operation.get("summary").and_then(Value::as_str);
+    let description = operation.get("description").and_then(Value::as_str);
+    docs(code, summary, "  ");
+    if summary.is_some() && description.is_some() {
+      code.push_str("  ///\n");
+    }
+    docs(code, description, "  ");
+    let _ = writeln!(code, "  pub {}({}),", name, input);
+
+    let output = output.unwrap_or_else(|| "()".into());
+    let _ = writeln!(code, "  Ok => {}, [", output);
+    for (status, constant) in &ok {
+      let _ = writeln!(code, "    /* {} */ {},", status.as_str(), constant);
+    }
+    code.push_str("  ],\n");
+    let _ = writeln!(code, "  Err => {}Error, [", name);
+    for (status, constant) in &err {
+      let variant = type_name(&constant.to_lowercase());
+      let _ = writeln!(
+        code,
+        "    /* {} */ {} => {},",
+        status.as_str(),
+        constant,
+        variant
+      );
+    }
+    code.push_str("  ],\n");
+    code.push_str("  ConversionErr => ::serde_json::Error,\n");
+    code.push_str("  ApiErr => ApiError,\n");
+
+    if method != "get" {
+      code.push('\n');
+      code.push_str("  fn method() -> ::http::Method {\n");
+      let _ = writeln!(code, "    ::http::Method::{}", method.to_uppercase());
+      code.push_str("  }\n");
+    }
+
+    code.push('\n');
+    if path_params.is_empty() {
+      code.push_str("  fn path(_input: &Self::Input) -> ::http_endpoint::Str {\n");
+      let _ = writeln!(code, "    {:?}.into()", path);
+    } else {
+      code.push_str("  fn path(input: &Self::Input) -> ::http_endpoint::Str {\n");
+      let mut format = path.to_string();
+      let mut args = Vec::new();
+      for parameter in &path_params {
+        format = format.replace(&format!("{{{}}}", parameter.name), "{}");
+        // Arrays are serialized as comma separated lists, in
+        // accordance with the default `simple` style.
+        let arg = if parameter.array {
+          format!(
+            "input.{}.iter().map(|value| encode(&value.to_string())).collect::<Vec<_>>().join(\",\")",
+            parameter.field
+          )
+        } else {
+          format!("encode(&input.{}.to_string())", parameter.field)
+        };
+        args.push(arg);
+      }
+      let _ = writeln!(
+        code,
+        "    format!({:?}, {}).into()",
+        format,
+        args.join(", ")
+      );
+    }
+    code.push_str("  }\n\n");
+
+    code.push_str("  fn path_template() -> Option<&'static str> {\n");
+    let _ = writeln!(code, "    Some({:?})", template);
+    code.push_str("  }\n");
+
+    if !query_params.is_empty() {
+      code.push('\n');
+      code.push_str(
+        "  fn query(input: &Self::Input) -> Result<Option<::http_endpoint::Str>, Self::ConversionError> {\n",
+      );
+      code.push_str("    let mut query = Vec::new();\n");
+      for parameter in &query_params {
+        let push = format!(
+          "query.push(format!(\"{}={{}}\", encode(&value.to_string())));",
+          parameter.name
+        );
+        match (parameter.required, parameter.array) {
+          (true, false) => {
+            let _ = writeln!(code, "    let value = &input.{};", parameter.field);
+            let _ = writeln!(code, "    {}", push);
+          },
+          (true, true) => {
+            let _ = writeln!(code, "    for value in &input.{} {{", parameter.field);
+            let _ = writeln!(code, "      {}", push);
+            code.push_str("    }\n");
+          },
+          (false, false) => {
+            let _ = writeln!(
+              code,
+              "    if let Some(value) = &input.{} {{",
+              parameter.field
+            );
+            let _ = writeln!(code, "      {}", push);
+            code.push_str("    }\n");
+          },
+          (false, true) => {
+            let _ = writeln!(
+              code,
+              "    for value in input.{}.iter().flatten() {{",
+              parameter.field
+            );
+            let _ = writeln!(code, "      {}", push);
+            code.push_str("    }\n");
+          },
+        }
+      }
+      code.push_str("    if query.is_empty() {\n");
+      code.push_str("      Ok(None)\n");
+      code.push_str("    } else {\n");
+      code.push_str("      Ok(Some(query.join(\"&\").into()))\n");
+      code.push_str("    }\n");
+      code.push_str("  }\n");
+    }
+
+    if body.is_some() {
+      code.push('\n');
+      code.push_str(
+        "  fn body(input: &Self::Input) -> Result<Option<::http_endpoint::Bytes>, Self::ConversionError> {\n",
+      );
+      code.push_str("    let body = ::serde_json::to_vec(&input.body)?;\n");
+      code.push_str("    Ok(Some(body.into()))\n");
+      code.push_str("  }\n");
+    }
+
+    code.push('\n');
+    if output == "()" {
+      code.push_str("  fn parse(_body: &[u8]) -> Result<Self::Output, Self::ConversionError> {\n");
+      code.push_str("    Ok(())\n");
+    } else {
+      code.push_str("  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {\n");
+      code.push_str("    ::serde_json::from_slice::<Self::Output>(body)\n");
+    }
+    code.push_str("  }\n\n");
+
+    code.push_str("  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {\n");
+    code.push_str(
+      "    ::serde_json::from_slice::<Self::ApiError>(body).map_err(|_| body.to_vec())\n",
+    );
+    code.push_str("  }\n");
+    code.push_str("}\n");
+    Ok(())
+  }
+
+  /// Emit documentation at the top level.
+  fn docs(&mut self, docs_: Option<&str>, indent: &str) {
+    docs(&mut self.code, docs_, indent)
+  }
+}
+
+
+/// Emit doc comments with the given indentation.
+fn docs(code: &mut String, docs: Option<&str>, indent: &str) {
+  if let Some(docs) = docs {
+    for line in docs.trim().lines() {
+      let line = line.trim_end();
+      if line.is_empty() {
+        let _ = writeln!(code, "{}///", indent);
+      } else {
+        let _ = writeln!(code, "{}/// {}", indent, line);
+      }
+    }
+  }
+}
+
+/// Retrieve the entries of a JSON object sorted by key, to make the
+/// generated code independent of the order in the specification.
+fn sorted(map: &Map<String, Value>) -> Vec<(&String, &Value)> {
+  let mut entries = map.iter().collect::<Vec<_>>();
+  entries.sort_by(|a, b| a.0.cmp(b.0));
+  entries
+}
+
+/// Retrieve the set of required properties of an object schema.
+fn required(schema: &Value) -> BTreeSet<&str> {
+  schema
+    .get("required")
+    .and_then(Value::as_array)
+    .map(|required| required.iter().filter_map(Value::as_str).collect())
+    .unwrap_or_default()
+}
+
+/// Split a name into its words.
+fn words(name: &str) -> Vec<String> {
+  let mut words = Vec::new();
+  let mut word = String::new();
+  let mut prev_lower = false;
+
+  for c in name.chars() {
+    if !c.is_alphanumeric() {
+      if !word.is_empty() {
+        words.push(word.split_off(0));
+      }
+      prev_lower = false;
+      continue;
+    }
+    if c.is_uppercase() && prev_lower && !word.is_empty() {
+      words.push(word.split_off(0));
+    }
+    prev_lower = c.is_lowercase() || c.is_ascii_digit();
+    word.push(c);
+  }
+  if !word.is_empty() {
+    words.push(word);
+  }
+  words
+}
+
+/// Convert a name into an upper camel case type name.
+fn type_name(name: &str) -> String {
+  let name = words(name)
+    .into_iter()
+    .map(|word| {
+      let mut chars = word.chars();
+      match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+        None => String::new(),
+      }
+    })
+    .collect::<String>();
+
+  if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+    format!("_{}", name)
+  } else {
+    name
+  }
+}
+
+/// Convert a name into a snake case field name.
+fn field_name(name: &str) -> String {
+  let name = words(name)
+    .into_iter()
+    .map(|word| word.to_lowercase())
+    .collect::<Vec<_>>()
+    .join("_");
+
+  if KEYWORDS.contains(&name.as_str()) {
+    format!("r#{}", name)
+  } else if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+    format!("_{}", name)
+  } else {
+    name
+  }
+}
+
+/// Determine the name of the `http::StatusCode` constant for a status.
+fn status_constant(status: StatusCode) -> Option<String> {
+  let reason = status.canonical_reason()?;
+  let constant = reason
+    .chars()
+    .filter(|c| *c != '\'')
+    .map(|c| {
+      if c.is_ascii_alphanumeric() {
+        c.to_ascii_uppercase()
+      } else {
+        '_'
+      }
+    })
+    .collect();
+  Some(constant)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  /// Check that names are converted as expected.
+  #[test]
+  fn name_conversion() {
+    assert_eq!(type_name("listPets"), "ListPets");
+    assert_eq!(type_name("pet-store"), "PetStore");
+    assert_eq!(type_name("get /pets/{petId}"), "GetPetsPetId");
+    assert_eq!(type_name("HTTPServer"), "HTTPServer");
+    assert_eq!(field_name("petId"), "pet_id");
+    assert_eq!(field_name("photo_urls"), "photo_urls");
+    assert_eq!(field_name("type"), "r#type");
+    assert_eq!(field_name("X-Rate-Limit"), "x_rate_limit");
+  }
+
+  /// Make sure that we map status codes to the names of the
+  /// corresponding constants.
+  #[test]
+  fn status_constants() {
+    assert_eq!(
+      status_constant(StatusCode::NOT_FOUND).as_deref(),
+      Some("NOT_FOUND")
+    );
+    assert_eq!(
+      status_constant(StatusCode::IM_A_TEAPOT).as_deref(),
+      Some("IM_A_TEAPOT")
+    );
+    assert_eq!(
+      status_constant(StatusCode::MULTI_STATUS).as_deref(),
+      Some("MULTI_STATUS")
+    );
+    assert_eq!(status_constant(StatusCode::from_u16(599).unwrap()), None);
+  }
+
+  /// Check that we reject unsupported specifications.
+  #[test]
+  fn unsupported_version() {
+    let err = generate(r#"{"swagger": "2.0"}"#).unwrap_err();
+    assert!(matches!(err, Error::Spec(..)), "{err}");
+
+    let err = generate("openapi: '2.0'").unwrap_err();
+    assert!(matches!(err, Error::Spec(..)), "{err}");
+  }
+}
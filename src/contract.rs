@@ -0,0 +1,321 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Verification of endpoint definitions against recorded traffic.
+//!
+//! A [`Verifier`] knows about a set of endpoints and checks recorded
+//! interactions, e.g., as captured in a [`Cassette`], against their
+//! definitions: the observed statuses have to be declared for the
+//! endpoint and response bodies have to parse using
+//! [`Endpoint::parse`] or [`Endpoint::parse_err`], respectively. The
+//! outcome is summarized in a [`Report`].
+
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use http::Method;
+use http::Request;
+use http::StatusCode;
+use http::Uri;
+
+use serde::Serialize;
+
+use crate::server::matches;
+use crate::vcr::Cassette;
+use crate::vcr::Interaction;
+use crate::Endpoint;
+
+
+/// A way in which a recorded response violates an endpoint's
+/// definition.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum Violation {
+  /// The status is declared neither as success nor as error.
+  UnexpectedStatus,
+  /// The body of a response with a success status failed to parse.
+  Conversion(String),
+  /// The body of a response with a declared error status failed to
+  /// parse into an API error. Contained is the name of the error
+  /// variant.
+  ErrorBody(String),
+}
+
+impl Display for Violation {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::UnexpectedStatus => write!(fmt, "status is not declared"),
+      Self::Conversion(err) => write!(fmt, "failed to parse body: {}", err),
+      Self::ErrorBody(variant) => {
+        write!(fmt, "failed to parse body of {} error", variant)
+      },
+    }
+  }
+}
+
+
+/// A recorded interaction violating the definition of an endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Failure {
+  /// The index of the interaction in the recording.
+  pub interaction: usize,
+  /// The method of the request.
+  pub method: String,
+  /// The URI of the request.
+  pub uri: String,
+  /// The status of the response.
+  pub status: u16,
+  /// The violation.
+  pub violation: Violation,
+}
+
+impl Display for Failure {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    write!(
+      fmt,
+      "#{} {} {} ({}): {}",
+      self.interaction, self.method, self.uri, self.status, self.violation
+    )
+  }
+}
+
+
+/// The outcome of verifying a single endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct EndpointReport {
+  /// The name of the endpoint.
+  pub name: &'static str,
+  /// The number of interactions checked against the endpoint.
+  pub checked: usize,
+  /// The distinct statuses observed, in ascending order.
+  pub statuses: Vec<u16>,
+  /// The interactions violating the endpoint's definition.
+  pub failures: Vec<Failure>,
+}
+
+
+/// The outcome of verifying a set of endpoints against recorded
+/// interactions.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct Report {
+  /// The reports for the individual endpoints, in the order in which
+  /// they were registered.
+  pub endpoints: Vec<EndpointReport>,
+  /// The indices of the interactions whose requests did not match any
+  /// endpoint.
+  pub unmatched: Vec<usize>,
+}
+
+impl Report {
+  /// Check whether no interaction violated an endpoint's definition.
+  ///
+  /// Unmatched interactions are not considered violations.
+  pub fn is_ok(&self) -> bool {
+    self
+      .endpoints
+      .iter()
+      .all(|endpoint| endpoint.failures.is_empty())
+  }
+
+  /// Iterate over all failures, across all endpoints.
+  pub fn failures(&self) -> impl Iterator<Item = (&'static str, &Failure)> {
+    self.endpoints.iter().flat_map(|endpoint| {
+      endpoint
+        .failures
+        .iter()
+        .map(move |failure| (endpoint.name, failure))
+    })
+  }
+}
+
+impl Display for Report {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    for endpoint in &self.endpoints {
+      writeln!(
+        fmt,
+        "{}: {} checked, {} failed",
+        endpoint.name,
+        endpoint.checked,
+        endpoint.failures.len()
+      )?;
+      for failure in &endpoint.failures {
+        writeln!(fmt, "  {}", failure)?;
+      }
+    }
+    if !self.unmatched.is_empty() {
+      writeln!(fmt, "{} unmatched interaction(s)", self.unmatched.len())?;
+    }
+    Ok(())
+  }
+}
+
+
+/// Check a response status and body against endpoint `E`.
+///
+/// If the endpoint declares statuses in its
+/// [`EndpointMeta`][crate::EndpointMeta], as is the case for those
+/// defined using [`EndpointDef!`][crate::EndpointDef], the status has to
+/// be one of them. Otherwise the response is classified solely based on
+/// the outcome of [`Endpoint::evaluate`].
+fn check<E>(status: StatusCode, body: &[u8]) -> Result<(), Violation>
+where
+  E: Endpoint,
+{
+  let meta = E::meta();
+  if !meta.ok.is_empty() || !meta.err.is_empty() {
+    let declared = meta.ok.contains(&status) || meta.err.iter().any(|(err, _)| *err == status);
+    if !declared {
+      return Err(Violation::UnexpectedStatus);
+    }
+  }
+
+  let err = match E::evaluate(status, body) {
+    Ok(_output) => return Ok(()),
+    Err(err) => err,
+  };
+
+  let variant = E::error_variant(&err);
+  match E::error_status(&err) {
+    Some((_, Ok(_api_error))) => Ok(()),
+    Some((_, Err(_body))) => Err(Violation::ErrorBody(variant.to_string())),
+    None => Err(Violation::Conversion(err.to_string())),
+  }
+}
+
+
+/// A registered endpoint.
+struct Check {
+  /// The name of the endpoint.
+  name: &'static str,
+  /// Check whether a request is directed at the endpoint.
+  matches: fn(&Request<()>) -> bool,
+  /// Check a response against the endpoint's definition.
+  check: fn(StatusCode, &[u8]) -> Result<(), Violation>,
+}
+
+
+/// A type verifying endpoint definitions against recorded
+/// interactions.
+///
+/// Interactions are attributed to the first registered endpoint whose
+/// method and path template match the request.
+#[derive(Default)]
+pub struct Verifier {
+  checks: Vec<Check>,
+}
+
+impl Verifier {
+  /// Create a new `Verifier` not knowing about any endpoints.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Register endpoint `E` for verification.
+  ///
+  /// Only endpoints providing a [path
+  /// template][Endpoint::path_template] can be matched against
+  /// requests.
+  pub fn with_endpoint<E>(mut self) -> Self
+  where
+    E: Endpoint,
+  {
+    let () = self.checks.push(Check {
+      name: E::NAME,
+      matches: matches::<E, ()>,
+      check: check::<E>,
+    });
+    self
+  }
+
+  /// Verify the registered endpoints against the given interactions.
+  pub fn verify(&self, interactions: &[Interaction]) -> Report {
+    let mut endpoints = self
+      .checks
+      .iter()
+      .map(|check| EndpointReport {
+        name: check.name,
+        checked: 0,
+        statuses: Vec::new(),
+        failures: Vec::new(),
+      })
+      .collect::<Vec<_>>();
+    let mut unmatched = Vec::new();
+
+    for (index, interaction) in interactions.iter().enumerate() {
+      let request = &interaction.request;
+      let method = request.method.parse::<Method>();
+      let uri = request.uri.parse::<Uri>();
+      let request = match (method, uri) {
+        (Ok(method), Ok(uri)) => {
+          let mut req = Request::new(());
+          *req.method_mut() = method;
+          *req.uri_mut() = uri;
+          req
+        },
+        _ => {
+          let () = unmatched.push(index);
+          continue;
+        },
+      };
+
+      let position = self
+        .checks
+        .iter()
+        .position(|check| (check.matches)(&request));
+      let (check, report) = match position {
+        Some(position) => (&self.checks[position], &mut endpoints[position]),
+        None => {
+          let () = unmatched.push(index);
+          continue;
+        },
+      };
+
+      let response = &interaction.response;
+      report.checked += 1;
+      if let Err(position) = report.statuses.binary_search(&response.status) {
+        let () = report.statuses.insert(position, response.status);
+      }
+
+      let result = match StatusCode::from_u16(response.status) {
+        Ok(status) => (check.check)(status, response.body.as_bytes()),
+        Err(_) => Err(Violation::UnexpectedStatus),
+      };
+      if let Err(violation) = result {
+        let () = report.failures.push(Failure {
+          interaction: index,
+          method: interaction.request.method.clone(),
+          uri: interaction.request.uri.clone(),
+          status: response.status,
+          violation,
+        });
+      }
+    }
+
+    Report {
+      endpoints,
+      unmatched,
+    }
+  }
+
+  /// Verify the registered endpoints against the interactions recorded
+  /// in a cassette.
+  pub fn verify_cassette(&self, cassette: &Cassette) -> Report {
+    self.verify(&cassette.interactions)
+  }
+}
+
+impl Debug for Verifier {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    let endpoints = self
+      .checks
+      .iter()
+      .map(|check| check.name)
+      .collect::<Vec<_>>();
+    fmt
+      .debug_struct("Verifier")
+      .field("endpoints", &endpoints)
+      .finish()
+  }
+}
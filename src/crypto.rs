@@ -0,0 +1,62 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Cryptographic primitives shared by request signers.
+
+use hmac::Hmac;
+use hmac::Mac as _;
+
+use sha2::Digest as _;
+use sha2::Sha256;
+
+
+/// Calculate the SHA-256 digest of the provided data.
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+  Sha256::digest(data).into()
+}
+
+/// Calculate the HMAC-SHA256 of the provided data using `key`.
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+  // HMAC accepts keys of any length, so creation cannot fail.
+  let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC key rejected");
+  let () = mac.update(data);
+  mac.finalize().into_bytes().into()
+}
+
+/// Compare two byte strings in time independent of their contents.
+#[allow(unused)]
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::encode::hex;
+
+
+  /// Check that we compute digests and MACs correctly.
+  #[test]
+  fn digests() {
+    assert_eq!(
+      hex(&sha256(b"")),
+      "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    // Test case 2 from RFC 4231.
+    assert_eq!(
+      hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+      "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+  }
+
+  /// Check that our constant time comparison works as expected.
+  #[test]
+  fn comparison() {
+    assert!(constant_time_eq(b"", b""));
+    assert!(constant_time_eq(b"abc", b"abc"));
+    assert!(!constant_time_eq(b"abc", b"abd"));
+    assert!(!constant_time_eq(b"abc", b"ab"));
+  }
+}
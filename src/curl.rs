@@ -0,0 +1,240 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use http::Method;
+use http::Request;
+
+use crate::request::is_sensitive;
+use crate::request::request;
+use crate::Bytes;
+use crate::Endpoint;
+
+
+/// A renderer of requests as equivalent `curl` command lines, e.g., for
+/// the purpose of reproducing them outside of the program.
+///
+/// By default, the values of authorization headers, cookies, and other
+/// sensitive headers are redacted.
+#[derive(Clone, Copy, Debug)]
+pub struct Curl {
+  redact: bool,
+}
+
+impl Curl {
+  /// Create a new `Curl` object with default settings.
+  pub fn new() -> Self {
+    Self { redact: true }
+  }
+
+  /// Set whether to redact sensitive header values.
+  pub fn with_redaction(mut self, redact: bool) -> Self {
+    self.redact = redact;
+    self
+  }
+
+  /// Render the request for issuing endpoint `E` with the given input
+  /// as a `curl` command.
+  ///
+  /// The request is created as per [`request`][crate::request()].
+  pub fn endpoint<E>(&self, base_url: Option<&str>, input: &E::Input) -> Result<String, E::Error>
+  where
+    E: Endpoint,
+  {
+    let request = request::<E>(base_url, input)?;
+    Ok(self.request(&request))
+  }
+
+  /// Render the given request as a `curl` command.
+  pub fn request(&self, request: &Request<Bytes>) -> String {
+    let mut command = String::from("curl");
+    let body = request.body();
+
+    // `-X HEAD` would make `curl` wait for a response body that never
+    // arrives; `-I` issues a proper HEAD request instead.
+    if request.method() == Method::HEAD && body.is_empty() {
+      command.push_str(" -I");
+    } else if request.method() != Method::GET || !body.is_empty() {
+      command.push_str(" -X ");
+      command.push_str(request.method().as_str());
+    }
+    command.push(' ');
+    command.push_str(&quote(request.uri().to_string().as_bytes()));
+
+    for (name, value) in request.headers() {
+      let value = if self.redact && is_sensitive(name, value) {
+        b"<redacted>"
+      } else {
+        value.as_bytes()
+      };
+      let mut header = Vec::with_capacity(name.as_str().len() + value.len() + 2);
+      header.extend_from_slice(name.as_str().as_bytes());
+      header.extend_from_slice(b": ");
+      header.extend_from_slice(value);
+
+      command.push_str(" -H ");
+      command.push_str(&quote(&header));
+    }
+
+    if !body.is_empty() {
+      command.push_str(" --data-binary ");
+      command.push_str(&quote(body));
+    }
+    command
+  }
+}
+
+impl Default for Curl {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+
+/// Render the request for issuing endpoint `E` with the given input as
+/// a `curl` command, redacting sensitive header values.
+///
+/// This is a shorthand for `Curl::new().endpoint::<E>(base_url, input)`.
+pub fn curl<E>(base_url: Option<&str>, input: &E::Input) -> Result<String, E::Error>
+where
+  E: Endpoint,
+{
+  Curl::new().endpoint::<E>(base_url, input)
+}
+
+
+/// Quote a byte string for use as a single shell word.
+///
+/// Printable UTF-8 is quoted using single quotes, everything else
+/// using ANSI-C quoting (`$'...'`), as supported by `bash` and `zsh`.
+fn quote(bytes: &[u8]) -> String {
+  match std::str::from_utf8(bytes) {
+    Ok(s) if !s.chars().any(|c| c.is_control() && c != '\n' && c != '\t') => {
+      format!("'{}'", s.replace('\'', r"'\''"))
+    },
+    _ => {
+      let mut quoted = String::from("$'");
+      for byte in bytes {
+        match byte {
+          b'\'' => quoted.push_str(r"\'"),
+          b'\\' => quoted.push_str(r"\\"),
+          0x20..=0x7e => quoted.push(char::from(*byte)),
+          _ => quoted.push_str(&format!("\\x{:02x}", byte)),
+        }
+      }
+      quoted.push('\'');
+      quoted
+    },
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http::header::AUTHORIZATION;
+  use http::header::CONTENT_TYPE;
+  use http::HeaderMap;
+  use http::HeaderValue;
+
+  use crate::Str;
+
+
+  EndpointDef! {
+    #[allow(unused)]
+    CreateOrder(String),
+    Ok => (), [
+      /* 201 */ CREATED,
+    ],
+    Err => CreateError, [],
+    ConversionErr => ::std::fmt::Error,
+    ApiErr => ::std::fmt::Error,
+
+    fn method() -> Method {
+      Method::POST
+    }
+
+    fn path(_: &Self::Input) -> Str {
+      "/orders".into()
+    }
+
+    fn query(_: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+      Ok(Some("dry_run=true&note=it's".into()))
+    }
+
+    fn headers(_: &Self::Input) -> Result<Option<HeaderMap>, Self::ConversionError> {
+      let mut headers = HeaderMap::new();
+      headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+      headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+      Ok(Some(headers))
+    }
+
+    fn body(input: &Self::Input) -> Result<Option<Bytes>, Self::ConversionError> {
+      Ok(Some(input.clone().into_bytes().into()))
+    }
+
+    fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+      Ok(())
+    }
+
+    fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+      Err(body.to_vec())
+    }
+  }
+
+
+  /// Check that we quote strings as expected.
+  #[test]
+  fn quoting() {
+    assert_eq!(quote(b"abc"), "'abc'");
+    assert_eq!(quote(b"it's"), r"'it'\''s'");
+    assert_eq!(quote(b"a\nb"), "'a\nb'");
+    assert_eq!(quote(b"\x00\xff'\\"), r"$'\x00\xff\'\\'");
+  }
+
+  /// Check that we can render an endpoint's request as `curl` command.
+  #[test]
+  fn render_endpoint() {
+    let input = r#"{"item":"it's"}"#.to_string();
+    let command = curl::<CreateOrder>(Some("https://example.com/"), &input).unwrap();
+    let expected = concat!(
+      r"curl -X POST 'https://example.com/orders?dry_run=true&note=it'\''s'",
+      r" -H 'content-type: application/json'",
+      r" -H 'authorization: <redacted>'",
+      r#" --data-binary '{"item":"it'\''s"}'"#,
+    );
+    assert_eq!(command, expected);
+
+    let command = Curl::new()
+      .with_redaction(false)
+      .endpoint::<CreateOrder>(None, &String::new())
+      .unwrap();
+    let expected = concat!(
+      r"curl -X POST '/orders?dry_run=true&note=it'\''s'",
+      r" -H 'content-type: application/json'",
+      r" -H 'authorization: Bearer secret'",
+    );
+    assert_eq!(command, expected);
+  }
+
+  /// Check that HEAD requests are rendered using `-I`.
+  #[test]
+  fn render_head_request() {
+    let request = Request::head("https://example.com/")
+      .body(Bytes::Borrowed(&[]))
+      .unwrap();
+    assert_eq!(
+      Curl::new().request(&request),
+      "curl -I 'https://example.com/'"
+    );
+  }
+
+  /// Make sure that a plain GET request results in a minimal command.
+  #[test]
+  fn render_get_request() {
+    let request = Request::get("https://example.com/")
+      .body(Bytes::Borrowed(&[]))
+      .unwrap();
+    assert_eq!(Curl::new().request(&request), "curl 'https://example.com/'");
+  }
+}
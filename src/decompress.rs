@@ -0,0 +1,191 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Transparent decompression of response bodies.
+
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::io::Read;
+
+use http::header::Entry;
+use http::header::ACCEPT_ENCODING;
+use http::header::CONTENT_ENCODING;
+use http::header::CONTENT_LENGTH;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::Response;
+
+#[cfg(feature = "brotli")]
+use brotli_decompressor::Decompressor;
+#[cfg(feature = "deflate")]
+use flate2::read::DeflateDecoder;
+#[cfg(feature = "gzip")]
+use flate2::read::MultiGzDecoder;
+#[cfg(feature = "deflate")]
+use flate2::read::ZlibDecoder;
+#[cfg(feature = "zstd")]
+use zstd::stream::read::Decoder as ZstdDecoder;
+
+
+/// The default maximum size of decompressed response bodies.
+pub(crate) const DEFAULT_LIMIT: usize = 64 * 1024 * 1024;
+
+/// The supported content codings, in order of preference.
+const CODINGS: &[&str] = &[
+  #[cfg(feature = "zstd")]
+  "zstd",
+  #[cfg(feature = "brotli")]
+  "br",
+  #[cfg(feature = "gzip")]
+  "gzip",
+  #[cfg(feature = "deflate")]
+  "deflate",
+];
+
+
+/// Advertise the supported content codings in the `Accept-Encoding`
+/// header, unless one is present already.
+pub(crate) fn advertise(headers: &mut HeaderMap) {
+  if let Entry::Vacant(entry) = headers.entry(ACCEPT_ENCODING) {
+    let codings = HeaderValue::try_from(CODINGS.join(", ")).expect("invalid content coding");
+    let _value = entry.insert(codings);
+  }
+}
+
+
+/// Read all data from `reader` into `body`, failing if there is more
+/// than `limit` bytes of it.
+fn read_to_end<R>(reader: R, body: &mut Vec<u8>, limit: usize) -> Result<(), IoError>
+where
+  R: Read,
+{
+  let max = u64::try_from(limit).unwrap_or(u64::MAX).saturating_add(1);
+  let _count = reader.take(max).read_to_end(body)?;
+  if body.len() > limit {
+    let message = format!("decompressed body exceeds limit of {} bytes", limit);
+    return Err(IoError::new(ErrorKind::InvalidData, message));
+  }
+  Ok(())
+}
+
+
+/// Decode data using the given content coding, returning `None` if it
+/// is not supported.
+///
+/// Decoding fails if the decoded data exceeds `limit` bytes, which
+/// guards against decompression bombs.
+fn decode(coding: &str, data: &[u8], limit: usize) -> Option<Result<Vec<u8>, IoError>> {
+  let mut body = Vec::new();
+  let result = match coding {
+    #[cfg(feature = "brotli")]
+    "br" => read_to_end(Decompressor::new(data, 4096), &mut body, limit),
+    #[cfg(feature = "deflate")]
+    "deflate" => {
+      // The "deflate" coding denotes zlib wrapped data, but some
+      // servers send raw deflate data instead.
+      read_to_end(ZlibDecoder::new(data), &mut body, limit).or_else(|err| {
+        // Data exceeding the limit is not worth a second attempt.
+        if body.len() > limit {
+          return Err(err);
+        }
+        let () = body.clear();
+        read_to_end(DeflateDecoder::new(data), &mut body, limit)
+      })
+    },
+    #[cfg(feature = "gzip")]
+    "gzip" | "x-gzip" => read_to_end(MultiGzDecoder::new(data), &mut body, limit),
+    #[cfg(feature = "zstd")]
+    "zstd" => ZstdDecoder::new(data).and_then(|decoder| read_to_end(decoder, &mut body, limit)),
+    _ => return None,
+  };
+  Some(result.map(|()| body))
+}
+
+
+/// Decode the body of a response as per its `Content-Encoding` header.
+///
+/// On success the `Content-Encoding` and `Content-Length` headers are
+/// removed. Responses using a content coding that is not supported are
+/// left untouched. Bodies decoding to more than `limit` bytes are
+/// reported as errors.
+pub(crate) fn decompress(response: &mut Response<Vec<u8>>, limit: usize) -> Result<(), IoError> {
+  let codings = response
+    .headers()
+    .get_all(CONTENT_ENCODING)
+    .iter()
+    .filter_map(|value| value.to_str().ok())
+    .flat_map(|value| value.split(','))
+    .map(|coding| coding.trim().to_ascii_lowercase())
+    .filter(|coding| !coding.is_empty() && coding != "identity")
+    .collect::<Vec<_>>();
+
+  // Bodies of responses to `HEAD` requests and the like are empty
+  // irrespective of the content coding.
+  if codings.is_empty() || response.body().is_empty() {
+    return Ok(());
+  }
+
+  // Codings are listed in the order they were applied in, so undo
+  // them in reverse.
+  let mut body = None::<Vec<u8>>;
+  for coding in codings.iter().rev() {
+    let data = body.as_deref().unwrap_or(response.body());
+    match decode(coding, data, limit) {
+      Some(result) => body = Some(result?),
+      None => return Ok(()),
+    }
+  }
+
+  if let Some(body) = body {
+    *response.body_mut() = body;
+    let _prev = response.headers_mut().remove(CONTENT_ENCODING);
+    let _prev = response.headers_mut().remove(CONTENT_LENGTH);
+  }
+  Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  /// Check that we advertise supported codings without overriding
+  /// explicitly set ones.
+  #[cfg(all(
+    feature = "brotli",
+    feature = "deflate",
+    feature = "gzip",
+    feature = "zstd"
+  ))]
+  #[test]
+  fn accept_encoding() {
+    let mut headers = HeaderMap::new();
+    let () = advertise(&mut headers);
+    assert_eq!(headers[ACCEPT_ENCODING], "zstd, br, gzip, deflate");
+
+    let mut headers = HeaderMap::new();
+    let _prev = headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+    let () = advertise(&mut headers);
+    assert_eq!(headers[ACCEPT_ENCODING], "identity");
+  }
+
+  /// Check that responses with unsupported or no content codings are
+  /// left untouched.
+  #[test]
+  fn unsupported_coding() {
+    let mut response = Response::new(b"data".to_vec());
+    let _prev = response
+      .headers_mut()
+      .insert(CONTENT_ENCODING, HeaderValue::from_static("compress"));
+    let () = decompress(&mut response, DEFAULT_LIMIT).unwrap();
+    assert_eq!(response.body(), b"data");
+    assert!(response.headers().contains_key(CONTENT_ENCODING));
+
+    let _prev = response
+      .headers_mut()
+      .insert(CONTENT_ENCODING, HeaderValue::from_static("identity"));
+    let () = decompress(&mut response, DEFAULT_LIMIT).unwrap();
+    assert_eq!(response.body(), b"data");
+  }
+}
@@ -0,0 +1,203 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Encoding helpers shared across the crate.
+
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+
+/// Encode binary data using the standard base64 alphabet.
+pub(crate) fn base64(data: &[u8]) -> String {
+  const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+  let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+  for chunk in data.chunks(3) {
+    let b = [
+      chunk[0],
+      chunk.get(1).copied().unwrap_or(0),
+      chunk.get(2).copied().unwrap_or(0),
+    ];
+    let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+    for i in 0..4 {
+      if i <= chunk.len() {
+        encoded.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize]));
+      } else {
+        encoded.push('=');
+      }
+    }
+  }
+  encoded
+}
+
+/// Encode binary data as lower case hexadecimal string.
+#[allow(unused)]
+pub(crate) fn hex(data: &[u8]) -> String {
+  data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Percent-encode a string for use in a URI component, leaving only
+/// unreserved characters untouched.
+pub(crate) fn percent<V>(value: V) -> String
+where
+  V: AsRef<[u8]>,
+{
+  let value = value.as_ref();
+  let mut encoded = String::with_capacity(value.len());
+  for &byte in value {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+        encoded.push(char::from(byte))
+      },
+      _ => encoded.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  encoded
+}
+
+/// Decode a percent-encoded URI component. Malformed escape sequences
+/// are retained as they are.
+#[allow(unused)]
+pub(crate) fn percent_decode(value: &str) -> Vec<u8> {
+  let bytes = value.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let escaped = bytes
+      .get(i + 1..i + 3)
+      .filter(|_| bytes[i] == b'%')
+      .and_then(|hex| std::str::from_utf8(hex).ok())
+      .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+    match escaped {
+      Some(byte) => {
+        decoded.push(byte);
+        i += 3;
+      },
+      None => {
+        decoded.push(bytes[i]);
+        i += 1;
+      },
+    }
+  }
+  decoded
+}
+
+
+/// A point in time broken down into its calendar date and time of day
+/// in UTC.
+#[allow(unused)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DateTime {
+  pub year: i64,
+  pub month: u64,
+  pub day: u64,
+  pub hour: u64,
+  pub minute: u64,
+  pub second: u64,
+  pub millis: u32,
+}
+
+#[allow(unused)]
+impl DateTime {
+  pub fn new(time: SystemTime) -> Self {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = duration.as_secs();
+    let days = (secs / 86400) as i64;
+    let secs = secs % 86400;
+
+    // Convert days since the epoch into a civil date, as per Howard
+    // Hinnant's `civil_from_days` algorithm.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    Self {
+      year,
+      month: month as u64,
+      day: day as u64,
+      hour: secs / 3600,
+      minute: secs % 3600 / 60,
+      second: secs % 60,
+      millis: duration.subsec_millis(),
+    }
+  }
+
+  /// Format the point in time as ISO 8601 timestamp with millisecond
+  /// precision, e.g., `2000-02-29T00:00:00.123Z`.
+  pub fn iso8601(&self) -> String {
+    format!(
+      "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+      self.year, self.month, self.day, self.hour, self.minute, self.second, self.millis
+    )
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::time::Duration;
+
+
+  /// Check that we base64 encode data correctly.
+  #[test]
+  fn base64_encoding() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(base64(b"foob"), "Zm9vYg==");
+    assert_eq!(base64(&[0xde, 0xad, 0xbe, 0xef]), "3q2+7w==");
+  }
+
+  /// Check that we percent-encode strings correctly.
+  #[test]
+  fn percent_encoding() {
+    assert_eq!(percent("abc-._~"), "abc-._~");
+    assert_eq!(percent("a b&c=d"), "a%20b%26c%3Dd");
+    assert_eq!(percent("ü"), "%C3%BC");
+  }
+
+  /// Check that we decode percent-encoded strings correctly.
+  #[test]
+  fn percent_decoding() {
+    assert_eq!(percent_decode("a%20b%26c%3dd"), b"a b&c=d");
+    assert_eq!(percent_decode("%C3%BC"), "ü".as_bytes());
+    assert_eq!(percent_decode("100%"), b"100%");
+    assert_eq!(percent_decode("%zz%4"), b"%zz%4");
+  }
+
+  /// Check that we hex encode data correctly.
+  #[test]
+  fn hex_encoding() {
+    assert_eq!(hex(b""), "");
+    assert_eq!(hex(&[0x00, 0xde, 0xad, 0xbe, 0xef]), "00deadbeef");
+  }
+
+  /// Check that we break down points in time correctly.
+  #[test]
+  fn date_time() {
+    let time = UNIX_EPOCH + Duration::from_millis(951_782_400_123);
+    let date_time = DateTime::new(time);
+    assert_eq!(
+      date_time,
+      DateTime {
+        year: 2000,
+        month: 2,
+        day: 29,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        millis: 123,
+      }
+    );
+    assert_eq!(date_time.iso8601(), "2000-02-29T00:00:00.123Z");
+  }
+}
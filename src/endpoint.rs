@@ -2,13 +2,17 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use std::error::Error;
+use std::time::Duration;
 
 use http::Error as HttpError;
 use http::HeaderMap;
 use http::Method;
 use http::StatusCode;
 
+use crate::meta::default_meta;
 use crate::Bytes;
+use crate::CachePolicy;
+use crate::EndpointMeta;
 use crate::Str;
 
 
@@ -33,6 +37,16 @@ pub trait Endpoint {
   /// An error emitted by the API.
   type ApiError: Error;
 
+  /// The name of the endpoint.
+  ///
+  /// The name should be unique among all endpoints, as it is used to
+  /// tell them apart, e.g., in metrics and generated OpenAPI documents.
+  /// When defined using [`EndpointDef`][crate::EndpointDef], this is
+  /// the identifier the endpoint was defined with, qualified with the
+  /// path of the module it was defined in, e.g.,
+  /// `api::orders::GetOrder`.
+  const NAME: &'static str;
+
   /// Retrieve the base URL to use.
   ///
   /// By default no URL is provided for the endpoint, in which case it
@@ -51,6 +65,18 @@ pub trait Endpoint {
   /// Inquire the path the request should go to.
   fn path(input: &Self::Input) -> Str;
 
+  /// Retrieve the template of the path the request should go to.
+  ///
+  /// The template is the path with all input dependent parts replaced
+  /// by named placeholders in curly braces, e.g., `/orders/{id}`. It
+  /// may optionally include a templated query, as in
+  /// `/orders?status={status}`. The template is used where the actual
+  /// path would be too specific or may reveal sensitive data, such as
+  /// in logs. By default no template is provided.
+  fn path_template() -> Option<&'static str> {
+    None
+  }
+
   /// Inquire the query the request should use.
   ///
   /// By default no query is emitted.
@@ -73,12 +99,44 @@ pub trait Endpoint {
     Ok(None)
   }
 
+  /// Retrieve the timeout to apply to requests to this endpoint.
+  ///
+  /// The timeout covers the entire request, from connection
+  /// establishment until the response has been received. By default
+  /// no endpoint specific timeout is set and it is up to the client to
+  /// decide on one.
+  fn timeout() -> Option<Duration> {
+    None
+  }
+
+  /// Retrieve the policy caches should apply to requests to this
+  /// endpoint.
+  ///
+  /// By default responses are cached as per the usual HTTP caching
+  /// rules.
+  fn cache_policy() -> CachePolicy {
+    CachePolicy::Default
+  }
+
   /// Parse the body into the final result.
   fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError>;
 
   /// Parse an API error.
   fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>>;
 
+  /// Retrieve metadata describing the endpoint.
+  ///
+  /// This method is not meant to be implemented manually. It will be
+  /// auto-generated. By default, metadata without any status codes or
+  /// documentation is provided.
+  ///
+  /// The metadata is created on first access and lives for the
+  /// remainder of the program, so that references to it can be held
+  /// on to.
+  fn meta() -> &'static EndpointMeta {
+    default_meta::<Self>()
+  }
+
   /// Evaluate an HTTP status and body, converting it into an output or
   /// error, depending on the status.
   ///
@@ -86,14 +144,120 @@ pub trait Endpoint {
   /// auto-generated.
   #[doc(hidden)]
   fn evaluate(status: StatusCode, body: &[u8]) -> Result<Self::Output, Self::Error>;
+
+  /// Retrieve the name of the variant of the given error.
+  ///
+  /// This method is not meant to be implemented manually. It will be
+  /// auto-generated. By default, all errors are reported as `Error`.
+  #[doc(hidden)]
+  #[allow(unused)]
+  fn error_variant(error: &Self::Error) -> &'static str {
+    "Error"
+  }
+
+  /// Retrieve the HTTP status represented by the given error, along
+  /// with the API error or raw body it conveys, if any.
+  ///
+  /// This method is not meant to be implemented manually. It will be
+  /// auto-generated. By default, no status is reported.
+  #[doc(hidden)]
+  #[allow(clippy::type_complexity, unused)]
+  fn error_status(error: &Self::Error) -> Option<(StatusCode, Result<&Self::ApiError, &[u8]>)> {
+    None
+  }
+}
+
+
+/// Extract the documentation from an attribute.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __endpoint_doc {
+  (doc = $doc:literal) => {
+    concat!($doc, "\n")
+  };
+  ($($attr:tt)*) => {
+    ""
+  };
+}
+
+
+/// Register an endpoint with the registry.
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __endpoint_register {
+  ($name:ident) => {
+    ::http_endpoint::__private::inventory::submit! {
+      ::http_endpoint::registry::Registration::new(
+        <$name as ::http_endpoint::Endpoint>::meta
+      )
+    }
+  };
+}
+
+/// Register an endpoint with the registry, which is disabled.
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __endpoint_register {
+  ($name:ident) => {};
 }
 
 
 /// A macro used for defining the properties for a request to a
 /// particular HTTP endpoint.
+///
+/// An optional `Timeout => <duration>,` clause following the API error
+/// type provides the endpoint's [`Endpoint::timeout`].
+///
+/// Endpoints are added to the [`registry`][crate::registry], if
+/// enabled. Prefixing the definition with `@unregistered` excludes an
+/// endpoint, e.g., because it is an implementation detail.
 #[macro_export]
 macro_rules! EndpointDef {
-  ( $(#[$docs:meta])* $pub:vis $name:ident($in:ty),
+  ( $(@$unregistered:ident)?
+    $(#[$($docs:tt)*])* $pub:vis $name:ident($in:ty),
+    Ok => $out:ty, [$($(#[$ok_docs:meta])* $ok_status:ident,)*],
+    Err => $err:ident, [$($(#[$err_docs:meta])* $err_status:ident => $variant:ident,)*],
+    ConversionErr => $conv_err:ty,
+    ApiErr => $api_err:ty,
+    Timeout => $timeout:expr,
+    $($defs:tt)* ) => {
+    ::http_endpoint::EndpointDef! {
+      $(@$unregistered)?
+      $(#[$($docs)*])* $pub $name($in),
+      Ok => $out, [$($(#[$ok_docs])* $ok_status,)*],
+      Err => $err, [$($(#[$err_docs])* $err_status => $variant,)*],
+      ConversionErr => $conv_err,
+      ApiErr => $api_err,
+
+      fn timeout() -> Option<::std::time::Duration> {
+        Some($timeout)
+      }
+
+      $($defs)*
+    }
+  };
+  ( $(#[$($docs:tt)*])* $pub:vis $name:ident($in:ty),
+    Ok => $out:ty, [$($(#[$ok_docs:meta])* $ok_status:ident,)*],
+    Err => $err:ident, [$($(#[$err_docs:meta])* $err_status:ident => $variant:ident,)*],
+    ConversionErr => $conv_err:ty,
+    ApiErr => $api_err:ty,
+    $($defs:tt)* ) => {
+    ::http_endpoint::EndpointDef! {
+      @unregistered
+      $(#[$($docs)*])* $pub $name($in),
+      Ok => $out, [$($(#[$ok_docs])* $ok_status,)*],
+      Err => $err, [$($(#[$err_docs])* $err_status => $variant,)*],
+      ConversionErr => $conv_err,
+      ApiErr => $api_err,
+      $($defs)*
+    }
+
+    ::http_endpoint::__endpoint_register!($name);
+  };
+  ( @unregistered
+    $(#[$($docs:tt)*])* $pub:vis $name:ident($in:ty),
     // We just ignore any documentation for success cases: there is
     // nowhere we can put it.
     Ok => $out:ty, [$($(#[$ok_docs:meta])* $ok_status:ident,)*],
@@ -102,7 +266,7 @@ macro_rules! EndpointDef {
     ApiErr => $api_err:ty,
     $($defs:tt)* ) => {
 
-    $(#[$docs])*
+    $(#[$($docs)*])*
     #[derive(Clone, Copy, Debug)]
     $pub struct $name;
 
@@ -133,7 +297,7 @@ macro_rules! EndpointDef {
             Err(body) => {
               match ::std::str::from_utf8(&body) {
                 Ok(body) => format!("{}", body),
-                Err(err) => format!("{:?}", body),
+                Err(_) => format!("{:?}", body),
               }
             },
           }
@@ -222,14 +386,34 @@ macro_rules! EndpointDef {
       type ConversionError = $conv_err;
       type ApiError = $api_err;
 
+      const NAME: &'static str = concat!(module_path!(), "::", stringify!($name));
+
       $($defs)*
 
+      #[allow(unused_qualifications)]
+      fn meta() -> &'static ::http_endpoint::EndpointMeta {
+        static META: ::std::sync::OnceLock<::http_endpoint::EndpointMeta> =
+          ::std::sync::OnceLock::new();
+
+        META.get_or_init(|| {
+          ::http_endpoint::__private::meta(
+            Self::NAME,
+            <$name as ::http_endpoint::Endpoint>::method(),
+            <$name as ::http_endpoint::Endpoint>::base_url(),
+            <$name as ::http_endpoint::Endpoint>::path_template(),
+            &[$(::http::StatusCode::$ok_status,)*],
+            &[$((::http::StatusCode::$err_status, stringify!($variant)),)*],
+            concat!($(::http_endpoint::__endpoint_doc!($($docs)*),)*),
+          )
+        })
+      }
+
       #[allow(unused_qualifications)]
       fn evaluate(
         status: ::http::StatusCode,
         body: &[u8],
       ) -> Result<$out, $err> {
-        match status {
+        let result = match status {
           $(
             ::http::StatusCode::$ok_status => {
               <$name as ::http_endpoint::Endpoint>::parse(&body).map_err($err::from)
@@ -246,6 +430,37 @@ macro_rules! EndpointDef {
               _ => Err($err::UnexpectedStatus(status, res)),
             }
           },
+        };
+        ::http_endpoint::__private::evaluated::<$name>(status, body, &result);
+        result
+      }
+
+      #[allow(unused_qualifications)]
+      fn error_variant(error: &$err) -> &'static str {
+        match error {
+          $(
+            $err::$variant(..) => stringify!($variant),
+          )*
+          $err::UnexpectedStatus(..) => "UnexpectedStatus",
+          $err::Http(..) => "Http",
+          $err::Conversion(..) => "Conversion",
+        }
+      }
+
+      #[allow(unused_qualifications)]
+      fn error_status(
+        error: &$err,
+      ) -> Option<(::http::StatusCode, Result<&$api_err, &[u8]>)> {
+        match error {
+          $(
+            $err::$variant(res) => {
+              Some((::http::StatusCode::$err_status, res.as_ref().map_err(Vec::as_slice)))
+            },
+          )*
+          $err::UnexpectedStatus(status, res) => {
+            Some((*status, res.as_ref().map_err(Vec::as_slice)))
+          },
+          $err::Http(..) | $err::Conversion(..) => None,
         }
       }
     }
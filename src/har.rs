@@ -0,0 +1,287 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Recording of HTTP traffic in the HTTP Archive (HAR) 1.2 format, as
+//! understood by browser developer tools and many other viewers.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Error as IoError;
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use http::header::CONTENT_TYPE;
+use http::HeaderMap;
+use http::Request;
+use http::Response;
+use http::Version;
+
+use serde_json::json;
+use serde_json::Value;
+
+use crate::encode::base64;
+use crate::encode::DateTime;
+use crate::request::is_sensitive;
+use crate::BoxFuture;
+use crate::Bytes;
+use crate::Transport;
+
+
+/// The HAR version of generated archives.
+const HAR_VERSION: &str = "1.2";
+/// The default maximum number of bytes of a body to record.
+const DEFAULT_BODY_LIMIT: usize = 64 * 1024;
+
+
+/// A [`Transport`] recording all requests it sends along with the
+/// received responses as HAR entries.
+///
+/// Bodies are recorded up to a configurable size limit, beyond which
+/// they are truncated. By default, the values of authorization headers,
+/// cookies, and other sensitive headers are redacted.
+#[derive(Debug)]
+pub struct Recorder<T> {
+  transport: T,
+  entries: Mutex<Vec<Value>>,
+  body_limit: usize,
+  redact: bool,
+}
+
+impl<T> Recorder<T> {
+  /// Create a new `Recorder` wrapping the provided transport.
+  pub fn new(transport: T) -> Self {
+    Self {
+      transport,
+      entries: Mutex::new(Vec::new()),
+      body_limit: DEFAULT_BODY_LIMIT,
+      redact: true,
+    }
+  }
+
+  /// Set the maximum number of bytes of request and response bodies
+  /// to record.
+  pub fn with_body_limit(mut self, limit: usize) -> Self {
+    self.body_limit = limit;
+    self
+  }
+
+  /// Set whether to redact sensitive header values.
+  pub fn with_redaction(mut self, redact: bool) -> Self {
+    self.redact = redact;
+    self
+  }
+
+  /// Retrieve the wrapped transport.
+  pub fn transport(&self) -> &T {
+    &self.transport
+  }
+
+  /// Retrieve the archive of all interactions recorded so far.
+  pub fn to_json(&self) -> Value {
+    let entries = self.entries.lock().unwrap().clone();
+    json!({
+      "log": {
+        "version": HAR_VERSION,
+        "creator": {
+          "name": env!("CARGO_PKG_NAME"),
+          "version": env!("CARGO_PKG_VERSION"),
+        },
+        "entries": entries,
+      },
+    })
+  }
+
+  /// Write the archive of all interactions recorded so far to a
+  /// `.har` file.
+  pub fn save<P>(&self, path: P) -> Result<(), IoError>
+  where
+    P: AsRef<Path>,
+  {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let () = serde_json::to_writer_pretty(&mut writer, &self.to_json())?;
+    let () = writer.write_all(b"\n")?;
+    writer.flush()
+  }
+
+  fn headers(&self, headers: &HeaderMap) -> Vec<Value> {
+    headers
+      .iter()
+      .map(|(name, value)| {
+        let value = if self.redact && is_sensitive(name, value) {
+          "<redacted>".into()
+        } else {
+          String::from_utf8_lossy(value.as_bytes())
+        };
+        json!({"name": name.as_str(), "value": value})
+      })
+      .collect()
+  }
+
+  /// Create the content object for a body.
+  fn content(&self, headers: &HeaderMap, body: &[u8]) -> Value {
+    let mime_type = headers
+      .get(CONTENT_TYPE)
+      .and_then(|value| value.to_str().ok())
+      .unwrap_or("");
+    let truncated = body.len() > self.body_limit;
+    let recorded = &body[..body.len().min(self.body_limit)];
+
+    let mut content = json!({
+      "size": body.len(),
+      "mimeType": mime_type,
+    });
+    let text = match std::str::from_utf8(recorded) {
+      Ok(text) => Some(text),
+      // Truncation may have split a character, in which case we record
+      // the text up to it.
+      Err(err) if truncated && err.error_len().is_none() => {
+        std::str::from_utf8(&recorded[..err.valid_up_to()]).ok()
+      },
+      Err(..) => None,
+    };
+    let size = match text {
+      Some(text) => {
+        content["text"] = text.into();
+        text.len()
+      },
+      None => {
+        content["text"] = base64(recorded).into();
+        content["encoding"] = "base64".into();
+        recorded.len()
+      },
+    };
+    if truncated {
+      content["comment"] = format!("truncated to {} bytes", size).into();
+    }
+    content
+  }
+
+  fn request(&self, request: &Request<Bytes>) -> Value {
+    let query = request
+      .uri()
+      .query()
+      .unwrap_or("")
+      .split('&')
+      .filter(|pair| !pair.is_empty())
+      .map(|pair| {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        json!({"name": name, "value": value})
+      })
+      .collect::<Vec<_>>();
+
+    let mut recorded = json!({
+      "method": request.method().as_str(),
+      "url": request.uri().to_string(),
+      "httpVersion": version(request.version()),
+      "cookies": [],
+      "headers": self.headers(request.headers()),
+      "queryString": query,
+      "headersSize": -1,
+      "bodySize": request.body().len(),
+    });
+    if !request.body().is_empty() {
+      let content = self.content(request.headers(), request.body());
+      let mut post_data = json!({
+        "mimeType": content["mimeType"],
+        "text": content["text"],
+      });
+      // HAR 1.2 does not provide for binary post data. We follow the
+      // approach taken for response content and mark the text as base64
+      // encoded.
+      if let Some(encoding) = content.get("encoding") {
+        post_data["encoding"] = encoding.clone();
+      }
+      if let Some(comment) = content.get("comment") {
+        post_data["comment"] = comment.clone();
+      }
+      recorded["postData"] = post_data;
+    }
+    recorded
+  }
+
+  fn response(&self, response: &Response<Vec<u8>>) -> Value {
+    json!({
+      "status": response.status().as_u16(),
+      "statusText": response.status().canonical_reason().unwrap_or(""),
+      "httpVersion": version(response.version()),
+      "cookies": [],
+      "headers": self.headers(response.headers()),
+      "content": self.content(response.headers(), response.body()),
+      "redirectURL": "",
+      "headersSize": -1,
+      "bodySize": response.body().len(),
+    })
+  }
+}
+
+impl<T> Transport for Recorder<T>
+where
+  T: Transport + Sync,
+{
+  type Error = T::Error;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let recorded = self.request(&request);
+    let started = SystemTime::now();
+
+    Box::pin(async move {
+      let start = Instant::now();
+      let response = self.transport.send(request).await?;
+      let time = start.elapsed().as_secs_f64() * 1000.0;
+
+      let entry = json!({
+        "startedDateTime": timestamp(started),
+        "time": time,
+        "request": recorded,
+        "response": self.response(&response),
+        "cache": {},
+        // We have no insight into the phases of a request and attribute
+        // all time to waiting for the response.
+        "timings": {
+          "blocked": -1,
+          "dns": -1,
+          "connect": -1,
+          "send": 0,
+          "wait": time,
+          "receive": 0,
+          "ssl": -1,
+        },
+      });
+      self.entries.lock().unwrap().push(entry);
+      Ok(response)
+    })
+  }
+}
+
+
+fn version(version: Version) -> String {
+  format!("{:?}", version)
+}
+
+/// Format a point in time as ISO 8601 timestamp in UTC.
+fn timestamp(time: SystemTime) -> String {
+  DateTime::new(time).iso8601()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::time::Duration;
+  use std::time::UNIX_EPOCH;
+
+
+  /// Check that we format timestamps correctly.
+  #[test]
+  fn timestamp_formatting() {
+    assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    let time = UNIX_EPOCH + Duration::from_millis(951_782_400_123);
+    assert_eq!(timestamp(time), "2000-02-29T00:00:00.123Z");
+    let time = UNIX_EPOCH + Duration::from_secs(1_792_329_045);
+    assert_eq!(timestamp(time), "2026-10-18T13:10:45.000Z");
+  }
+}
@@ -1,14 +1,127 @@
 // Copyright (C) 2020 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+// Allow for referring to this crate as `http_endpoint`, which is what
+// macros expand to, from within the crate itself.
+extern crate self as http_endpoint;
+
 #[macro_use]
 mod endpoint;
+mod auth;
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "cache")]
+pub mod cache;
+mod client;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+#[cfg(feature = "contract")]
+pub mod contract;
+#[cfg(any(feature = "signer", feature = "sigv4"))]
+mod crypto;
+mod curl;
+#[cfg(any(
+  feature = "brotli",
+  feature = "deflate",
+  feature = "gzip",
+  feature = "zstd"
+))]
+mod decompress;
+mod encode;
 mod error;
+#[cfg(feature = "har")]
+pub mod har;
+mod meta;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "oauth2")]
+pub mod oauth2;
+mod observe;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+#[cfg(feature = "paginate")]
+pub mod paginate;
+mod rate_limit;
+#[cfg(feature = "registry")]
+pub mod registry;
+mod request;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "signer")]
+pub mod signer;
+#[cfg(feature = "sigv4")]
+pub mod sigv4;
+#[cfg(feature = "tower")]
+pub mod tower;
+mod trace;
+#[cfg(feature = "vcr")]
+pub mod vcr;
+#[cfg(feature = "webhook")]
+pub mod webhook;
 
 use std::borrow::Cow;
 
+pub use auth::ApiKey;
+pub use auth::Auth;
+pub use auth::Basic;
+pub use auth::Bearer;
+pub use auth::Error as AuthError;
+pub use client::BoxFuture;
+#[cfg(feature = "client")]
+pub use client::Client;
+pub use client::Error as ClientError;
+pub use client::Transport;
+pub use curl::curl;
+pub use curl::Curl;
 pub use endpoint::Endpoint;
 pub use error::Error;
+pub use meta::EndpointMeta;
+#[cfg(feature = "metrics")]
+pub use observe::MetricsObserver;
+pub use observe::Observer;
+pub use rate_limit::Quota;
+pub use rate_limit::RateLimiter;
+pub use request::request;
+pub use request::CachePolicy;
+pub use request::Timeout;
 
 pub type Str = Cow<'static, str>;
 pub type Bytes = Cow<'static, [u8]>;
+
+
+/// Functionality used by code generated by the crate's macros. Not
+/// part of the public API.
+#[doc(hidden)]
+pub mod __private {
+  use http::Method;
+  use http::StatusCode;
+
+  use crate::EndpointMeta;
+  use crate::Str;
+
+  #[cfg(feature = "registry")]
+  pub use inventory;
+
+  pub use crate::trace::evaluated;
+
+  /// Create an `EndpointMeta` object.
+  pub fn meta(
+    name: &'static str,
+    method: Method,
+    base_url: Option<Str>,
+    path: Option<&'static str>,
+    ok: &'static [StatusCode],
+    err: &'static [(StatusCode, &'static str)],
+    docs: &'static str,
+  ) -> EndpointMeta {
+    EndpointMeta {
+      name,
+      method,
+      base_url,
+      path,
+      ok,
+      err,
+      docs,
+    }
+  }
+}
@@ -0,0 +1,175 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::any::type_name;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use http::Method;
+use http::StatusCode;
+
+use crate::Endpoint;
+use crate::Str;
+
+
+/// Metadata describing an endpoint, as retrieved via
+/// [`Endpoint::meta`][crate::Endpoint::meta].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct EndpointMeta {
+  /// The name of the endpoint, as per [`Endpoint::NAME`][crate::Endpoint::NAME].
+  pub name: &'static str,
+  /// The HTTP method used by the endpoint.
+  pub method: Method,
+  /// The base URL of the endpoint, if any.
+  pub base_url: Option<Str>,
+  /// The template of the endpoint's path, if any.
+  pub path: Option<&'static str>,
+  /// The status codes indicating success.
+  pub ok: &'static [StatusCode],
+  /// The status codes indicating an error, along with the name of the
+  /// error variant they map to.
+  pub err: &'static [(StatusCode, &'static str)],
+  /// The endpoint's documentation, one line per line of doc comment
+  /// and with leading white space preserved.
+  pub docs: &'static str,
+}
+
+impl EndpointMeta {
+  /// Retrieve the endpoint's documentation with the leading white
+  /// space common to all lines removed.
+  pub fn description(&self) -> String {
+    let indent = self
+      .docs
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(|line| line.len() - line.trim_start().len())
+      .min()
+      .unwrap_or(0);
+
+    self
+      .docs
+      .lines()
+      .map(|line| line.get(indent..).unwrap_or("").trim_end())
+      .collect::<Vec<_>>()
+      .join("\n")
+      .trim()
+      .to_string()
+  }
+}
+
+
+/// Retrieve the default metadata of endpoint `E`, as used for endpoints
+/// not defined using [`EndpointDef`][crate::EndpointDef].
+///
+/// The metadata is created on first access and lives for the remainder
+/// of the program.
+pub(crate) fn default_meta<E>() -> &'static EndpointMeta
+where
+  E: Endpoint + ?Sized,
+{
+  static METAS: OnceLock<Mutex<HashMap<&'static str, &'static EndpointMeta>>> = OnceLock::new();
+
+  // `TypeId` would require `E: 'static`, which is not a given. The
+  // type's name is sufficiently unique for our purposes.
+  let key = type_name::<E>();
+  let metas = METAS.get_or_init(Mutex::default);
+  if let Some(meta) = metas.lock().unwrap().get(key) {
+    return meta;
+  }
+
+  // Create the metadata without holding the lock, as the endpoint's
+  // methods are user provided.
+  let meta = EndpointMeta {
+    name: E::NAME,
+    method: E::method(),
+    base_url: E::base_url(),
+    path: E::path_template(),
+    ok: &[],
+    err: &[],
+    docs: "",
+  };
+  metas
+    .lock()
+    .unwrap()
+    .entry(key)
+    .or_insert_with(|| Box::leak(Box::new(meta)))
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::ptr;
+
+
+  EndpointDef! {
+    /// Retrieve an order.
+    ///
+    ///   Orders are identified by their ID.
+    #[allow(unused)]
+    pub GetOrder(u64),
+    Ok => (), [
+      /* 200 */ OK,
+    ],
+    Err => GetError, [
+      /* 404 */ NOT_FOUND => NotFound,
+      /* 410 */ GONE => Gone,
+    ],
+    ConversionErr => ::std::fmt::Error,
+    ApiErr => ::std::fmt::Error,
+
+    fn base_url() -> Option<Str> {
+      Some("https://example.com".into())
+    }
+
+    fn path(id: &Self::Input) -> Str {
+      format!("/orders/{}", id).into()
+    }
+
+    fn path_template() -> Option<&'static str> {
+      Some("/orders/{id}")
+    }
+
+    fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+      Ok(())
+    }
+
+    fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+      Err(body.to_vec())
+    }
+  }
+
+
+  /// Check that the metadata of an endpoint is generated correctly.
+  #[test]
+  fn endpoint_meta() {
+    assert_eq!(GetOrder::NAME, "http_endpoint::meta::tests::GetOrder");
+
+    let meta = GetOrder::meta();
+    assert_eq!(meta.name, GetOrder::NAME);
+    assert_eq!(meta.method, Method::GET);
+    assert_eq!(meta.base_url.as_deref(), Some("https://example.com"));
+    assert_eq!(meta.path, Some("/orders/{id}"));
+    assert_eq!(meta.ok, &[StatusCode::OK]);
+    assert_eq!(
+      meta.err,
+      &[
+        (StatusCode::NOT_FOUND, "NotFound"),
+        (StatusCode::GONE, "Gone")
+      ]
+    );
+    assert_eq!(
+      meta.docs,
+      " Retrieve an order.\n\n   Orders are identified by their ID.\n"
+    );
+    assert_eq!(
+      meta.description(),
+      "Retrieve an order.\n\n  Orders are identified by their ID."
+    );
+    // The metadata is only created once.
+    assert!(ptr::eq(meta, GetOrder::meta()));
+  }
+}
@@ -0,0 +1,469 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! A mock server answering requests to endpoints with canned replies.
+//!
+//! The server validates incoming requests against the definitions of
+//! the endpoints it knows about and records all requests not
+//! conforming to any of them as [`Mismatch`]es. It can be used
+//! in-process as a [`Transport`] or listen for connections on a local
+//! port.
+
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::io::Error as IoError;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread::spawn;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use http::header::CONTENT_LENGTH;
+use http::header::TRANSFER_ENCODING;
+use http::Method;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use crate::server::decode;
+use crate::server::Error as ServerError;
+use crate::server::Serve;
+use crate::BoxFuture;
+use crate::Bytes;
+use crate::Transport;
+
+
+/// The maximum size of request bodies accepted over the network.
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+/// The time after which reading from an idle connection fails.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+
+/// An error indicating that a canned reply could not be configured.
+#[derive(Debug)]
+pub enum Error {
+  /// The status is not declared for the endpoint.
+  Status(&'static str, StatusCode),
+  /// The output could not be encoded.
+  Encode(&'static str, String),
+}
+
+impl Display for Error {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::Status(endpoint, status) => {
+        write!(
+          fmt,
+          "status {} is not declared for endpoint {}",
+          status, endpoint
+        )
+      },
+      Self::Encode(endpoint, err) => {
+        write!(
+          fmt,
+          "failed to encode output of endpoint {}: {}",
+          endpoint, err
+        )
+      },
+    }
+  }
+}
+
+impl StdError for Error {}
+
+
+/// A request that did not conform to any of the known endpoints.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+  /// The method of the request.
+  pub method: Method,
+  /// The URI of the request.
+  pub uri: String,
+  /// A description of why the request did not match.
+  pub reason: String,
+}
+
+impl Display for Mismatch {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    write!(fmt, "{} {}: {}", self.method, self.uri, self.reason)
+  }
+}
+
+
+/// The outcome of matching a request against a route.
+type Outcome = Result<Option<Response<Vec<u8>>>, ServerError<String>>;
+
+/// A function validating a request and creating the reply, if any.
+type Handler = Box<dyn Fn(&Request<Vec<u8>>) -> Outcome + Send + Sync>;
+
+/// A route for a single endpoint.
+struct Route {
+  /// The name of the endpoint.
+  name: &'static str,
+  /// The handler for requests.
+  handle: Handler,
+}
+
+
+#[derive(Default)]
+struct Inner {
+  routes: Mutex<Vec<Route>>,
+  served: Mutex<Vec<&'static str>>,
+  mismatches: Mutex<Vec<Mismatch>>,
+}
+
+impl Inner {
+  fn mismatch(
+    &self,
+    request: &Request<Vec<u8>>,
+    status: StatusCode,
+    reason: String,
+  ) -> Response<Vec<u8>> {
+    let mismatch = Mismatch {
+      method: request.method().clone(),
+      uri: request.uri().to_string(),
+      reason,
+    };
+    let body = mismatch.to_string().into_bytes();
+    self.mismatches.lock().unwrap().push(mismatch);
+    response(status, body)
+  }
+
+  fn dispatch(&self, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let mut rejection = None::<(&'static str, ServerError<String>)>;
+
+    for route in self.routes.lock().unwrap().iter() {
+      match (route.handle)(request) {
+        Ok(Some(response)) => {
+          self.served.lock().unwrap().push(route.name);
+          return response;
+        },
+        Ok(None) => {
+          let reason = format!("no reply configured for endpoint {}", route.name);
+          return self.mismatch(request, StatusCode::NOT_IMPLEMENTED, reason);
+        },
+        Err(err) => {
+          // Report the most specific rejection: a failure to decode the
+          // input beats a method mismatch, which beats a path mismatch.
+          let rank = |err: &ServerError<String>| match err {
+            ServerError::Path(..) => 0,
+            ServerError::Method(..) => 1,
+            ServerError::Conversion(..) => 2,
+          };
+          if rejection
+            .as_ref()
+            .map(|(_, other)| rank(&err) > rank(other))
+            .unwrap_or(true)
+          {
+            rejection = Some((route.name, err));
+          }
+        },
+      }
+    }
+
+    match rejection {
+      Some((name, err)) if !matches!(err, ServerError::Path(..)) => {
+        let reason = format!("endpoint {}: {}", name, err);
+        self.mismatch(request, err.status(), reason)
+      },
+      _ => {
+        let reason = "no matching endpoint".to_string();
+        self.mismatch(request, StatusCode::NOT_FOUND, reason)
+      },
+    }
+  }
+}
+
+
+fn response(status: StatusCode, body: Vec<u8>) -> Response<Vec<u8>> {
+  let mut response = Response::new(body);
+  *response.status_mut() = status;
+  response
+}
+
+
+/// A mock server answering requests to a set of endpoints with canned
+/// replies.
+///
+/// Requests are matched against the endpoints' methods and path
+/// templates and have to decode into their inputs successfully, as per
+/// [`Serve::decode`]. Conforming requests are answered with the reply
+/// configured for the endpoint. All other requests are rejected with an
+/// appropriate status and recorded as [`Mismatch`]es.
+#[derive(Default)]
+pub struct MockServer {
+  inner: Arc<Inner>,
+}
+
+impl MockServer {
+  /// Create a new `MockServer` not knowing about any endpoints.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn with_route<E>(self, reply: Option<Response<Vec<u8>>>) -> Self
+  where
+    E: Serve + 'static,
+  {
+    let handle = move |request: &Request<Vec<u8>>| -> Outcome {
+      let _input = decode::<E, _>(request).map_err(|err| match err {
+        ServerError::Method(method) => ServerError::Method(method),
+        ServerError::Path(path) => ServerError::Path(path),
+        ServerError::Conversion(err) => ServerError::Conversion(err.to_string()),
+      })?;
+      Ok(reply.clone())
+    };
+
+    let mut routes = self.inner.routes.lock().unwrap();
+    // A later reply for the same endpoint replaces an earlier one.
+    let () = routes.retain(|route| route.name != E::NAME);
+    let () = routes.push(Route {
+      name: E::NAME,
+      handle: Box::new(handle),
+    });
+    drop(routes);
+    self
+  }
+
+  /// Register endpoint `E` without configuring a reply.
+  ///
+  /// Conforming requests are answered with status 501 but still
+  /// recorded as mismatches.
+  pub fn with_endpoint<E>(self) -> Self
+  where
+    E: Serve + 'static,
+  {
+    self.with_route::<E>(None)
+  }
+
+  /// Configure endpoint `E` to reply with the given output, using the
+  /// status provided by [`Serve::status`].
+  pub fn with_output<E>(self, output: &E::Output) -> Result<Self, Error>
+  where
+    E: Serve + 'static,
+  {
+    let body = E::encode(output).map_err(|err| Error::Encode(E::NAME, err.to_string()))?;
+    Ok(self.with_route::<E>(Some(response(E::status(output), body))))
+  }
+
+  /// Configure endpoint `E` to reply with the given API error, using
+  /// one of the error statuses declared for the endpoint.
+  pub fn with_error<E>(self, status: StatusCode, error: &E::ApiError) -> Result<Self, Error>
+  where
+    E: Serve + 'static,
+  {
+    if !E::meta()
+      .err
+      .iter()
+      .any(|(declared, _)| *declared == status)
+    {
+      return Err(Error::Status(E::NAME, status));
+    }
+    Ok(self.with_route::<E>(Some(response(status, E::encode_err(error)))))
+  }
+
+  /// Retrieve the names of the endpoints of all requests served so
+  /// far, in order.
+  pub fn served(&self) -> Vec<&'static str> {
+    self.inner.served.lock().unwrap().clone()
+  }
+
+  /// Retrieve all requests that did not conform to any endpoint so
+  /// far.
+  pub fn mismatches(&self) -> Vec<Mismatch> {
+    self.inner.mismatches.lock().unwrap().clone()
+  }
+
+  /// Listen for HTTP/1.1 connections on an ephemeral port on the
+  /// loopback interface.
+  ///
+  /// Each connection is served on a separate thread. The server stops
+  /// listening once the returned [`Listening`] object is dropped,
+  /// without waiting for connections still being served. Request
+  /// bodies have to be delimited by `Content-Length`; requests using a
+  /// transfer coding are answered with 501 (Not Implemented).
+  pub fn listen(&self) -> Result<Listening, IoError> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let inner = self.inner.clone();
+    let stopped = stop.clone();
+    let thread = spawn(move || {
+      for stream in listener.incoming() {
+        if stopped.load(Ordering::Relaxed) {
+          break;
+        }
+        if let Ok(stream) = stream {
+          let inner = inner.clone();
+          let _handle = spawn(move || {
+            // Failures are specific to a connection and there is nobody
+            // to report them to.
+            let _result = serve_connection(&inner, stream);
+          });
+        }
+      }
+    });
+
+    Ok(Listening {
+      addr,
+      stop,
+      thread: Some(thread),
+    })
+  }
+}
+
+impl Debug for MockServer {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    let endpoints = self
+      .inner
+      .routes
+      .lock()
+      .unwrap()
+      .iter()
+      .map(|route| route.name)
+      .collect::<Vec<_>>();
+    fmt
+      .debug_struct("MockServer")
+      .field("endpoints", &endpoints)
+      .finish_non_exhaustive()
+  }
+}
+
+impl Transport for MockServer {
+  type Error = IoError;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let request = request.map(Bytes::into_owned);
+    let response = self.inner.dispatch(&request);
+    Box::pin(async move { Ok(response) })
+  }
+}
+
+
+/// Serve a single request on a connection.
+fn serve_connection(inner: &Inner, stream: TcpStream) -> Result<(), IoError> {
+  let () = stream.set_read_timeout(Some(READ_TIMEOUT))?;
+  let mut reader = BufReader::new(&stream);
+  let mut line = String::new();
+  let _count = reader.read_line(&mut line)?;
+  let mut parts = line.split_whitespace();
+  let (method, uri) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+  let mut builder = Request::builder().method(method).uri(uri);
+  let mut length = 0;
+  // An error preventing us from reading the request body, if any.
+  let mut error = None;
+  loop {
+    line.clear();
+    let _count = reader.read_line(&mut line)?;
+    let header = line.trim_end();
+    if header.is_empty() {
+      break;
+    }
+    match header.split_once(':') {
+      Some((name, value)) => {
+        let value = value.trim();
+        if name.eq_ignore_ascii_case(CONTENT_LENGTH.as_str()) {
+          match value.parse() {
+            Ok(value) => length = value,
+            Err(_) => {
+              let message = format!("invalid content length: {}", value);
+              error = Some((StatusCode::BAD_REQUEST, message))
+            },
+          }
+        } else if name.eq_ignore_ascii_case(TRANSFER_ENCODING.as_str()) {
+          // We don't decode any transfer codings, including
+          // `chunked`, and so we couldn't determine where the body ends.
+          let message = format!("transfer encoding {} is not supported", value);
+          error = Some((StatusCode::NOT_IMPLEMENTED, message))
+        }
+        builder = builder.header(name, value);
+      },
+      None => {
+        let message = format!("malformed header line: {}", header);
+        error = Some((StatusCode::BAD_REQUEST, message))
+      },
+    }
+  }
+
+  let response = if let Some((status, message)) = error {
+    response(status, message.into_bytes())
+  } else if length > MAX_BODY_SIZE {
+    let message = format!("request body exceeds {} bytes", MAX_BODY_SIZE);
+    response(StatusCode::PAYLOAD_TOO_LARGE, message.into_bytes())
+  } else {
+    let mut body = vec![0; length];
+    let () = reader.read_exact(&mut body)?;
+    match builder.body(body) {
+      Ok(request) => inner.dispatch(&request),
+      Err(err) => response(StatusCode::BAD_REQUEST, err.to_string().into_bytes()),
+    }
+  };
+
+  let mut stream = &stream;
+  let status = response.status();
+  write!(
+    stream,
+    "HTTP/1.1 {} {}\r\n",
+    status.as_u16(),
+    status.canonical_reason().unwrap_or("")
+  )?;
+  for (name, value) in response.headers() {
+    write!(stream, "{}: ", name)?;
+    let () = stream.write_all(value.as_bytes())?;
+    let () = stream.write_all(b"\r\n")?;
+  }
+  write!(
+    stream,
+    "content-length: {}\r\nconnection: close\r\n\r\n",
+    response.body().len()
+  )?;
+  let () = stream.write_all(response.body())?;
+  stream.flush()
+}
+
+
+/// A [`MockServer`] listening for connections.
+#[derive(Debug)]
+pub struct Listening {
+  addr: SocketAddr,
+  stop: Arc<AtomicBool>,
+  thread: Option<JoinHandle<()>>,
+}
+
+impl Listening {
+  /// Retrieve the address the server is listening on.
+  pub fn addr(&self) -> SocketAddr {
+    self.addr
+  }
+
+  /// Retrieve the base URL to issue requests to.
+  pub fn base_url(&self) -> String {
+    format!("http://{}", self.addr)
+  }
+}
+
+impl Drop for Listening {
+  fn drop(&mut self) {
+    let () = self.stop.store(true, Ordering::Relaxed);
+    // Wake up the listening thread, which is blocked accepting
+    // connections.
+    let _result = TcpStream::connect(self.addr);
+    if let Some(thread) = self.thread.take() {
+      let _result = thread.join();
+    }
+  }
+}
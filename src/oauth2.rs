@@ -0,0 +1,450 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Support for OAuth2 protected APIs.
+//!
+//! [`OAuth2`] is a [`Transport`] wrapping another one, obtaining access
+//! tokens from a token endpoint using the client credentials or
+//! refresh token grant and injecting them into the `Authorization`
+//! header of every request sent. Tokens are cached until shortly
+//! before they expire. Should a request be rejected with status `401`
+//! nonetheless, a fresh token is obtained and the request retried once.
+
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::time::Duration;
+
+use http::header::InvalidHeaderValue;
+use http::header::ACCEPT;
+use http::header::CONTENT_TYPE;
+use http::uri::InvalidUri;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::Method;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use http::Uri;
+
+use serde::Deserialize;
+
+use serde_json::Error as JsonError;
+
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::encode::percent;
+use crate::request;
+use crate::Auth as _;
+use crate::AuthError;
+use crate::Bearer;
+use crate::BoxFuture;
+use crate::Bytes;
+use crate::Endpoint as _;
+use crate::Str;
+use crate::Transport;
+
+
+/// The default time before expiry at which tokens get refreshed.
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+
+/// A request to a token endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenRequest {
+  /// The path (and query) of the token endpoint.
+  pub path: Str,
+  /// The parameters to send, form encoded, in the request body.
+  pub params: Vec<(&'static str, String)>,
+}
+
+
+/// A successful response from a token endpoint, as per RFC 6749
+/// section 5.1.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct TokenResponse {
+  /// The access token.
+  pub access_token: String,
+  /// The type of the token, typically `Bearer`.
+  pub token_type: String,
+  /// The lifetime of the access token in seconds.
+  #[serde(default)]
+  pub expires_in: Option<u64>,
+  /// A refresh token, if issued.
+  #[serde(default)]
+  pub refresh_token: Option<String>,
+  /// The scope of the access token.
+  #[serde(default)]
+  pub scope: Option<String>,
+}
+
+
+/// An error response from a token endpoint, as per RFC 6749 section
+/// 5.2.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct TokenError {
+  /// The error code, e.g., `invalid_client`.
+  pub error: String,
+  /// A human readable description of the error.
+  #[serde(default)]
+  pub error_description: Option<String>,
+}
+
+impl Display for TokenError {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match &self.error_description {
+      Some(description) => write!(fmt, "{}: {}", self.error, description),
+      None => write!(fmt, "{}", self.error),
+    }
+  }
+}
+
+impl StdError for TokenError {}
+
+
+// The token endpoint is used internally by the `OAuth2` transport and
+// is of no concern to the endpoint registry.
+EndpointDef! {
+  @unregistered
+  /// An OAuth2 token endpoint.
+  pub Token(TokenRequest),
+  Ok => TokenResponse, [
+    /* 200 */ OK,
+  ],
+  Err => TokenEndpointError, [
+    /* 400 */ BAD_REQUEST => BadRequest,
+    /* 401 */ UNAUTHORIZED => Unauthorized,
+  ],
+  ConversionErr => JsonError,
+  ApiErr => TokenError,
+
+  fn method() -> Method {
+    Method::POST
+  }
+
+  fn path(input: &Self::Input) -> Str {
+    input.path.clone()
+  }
+
+  fn headers(_input: &Self::Input) -> Result<Option<HeaderMap>, Self::ConversionError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      CONTENT_TYPE,
+      HeaderValue::from_static("application/x-www-form-urlencoded"),
+    );
+    headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+    Ok(Some(headers))
+  }
+
+  fn body(input: &Self::Input) -> Result<Option<Bytes>, Self::ConversionError> {
+    let body = input
+      .params
+      .iter()
+      .map(|(name, value)| format!("{}={}", name, percent(value)))
+      .collect::<Vec<_>>()
+      .join("&");
+    Ok(Some(body.into_bytes().into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    serde_json::from_slice::<Self::Output>(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    serde_json::from_slice::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+
+/// An error as reported by the [`OAuth2`] transport.
+#[derive(Debug)]
+pub enum Error<T> {
+  /// The wrapped transport reported an error.
+  Transport(T),
+  /// The token endpoint reported an error.
+  Token(TokenEndpointError),
+  /// The token endpoint issued a token that can't be used in a header.
+  InvalidToken(InvalidHeaderValue),
+  /// The access token could not be applied to a request.
+  Auth(AuthError),
+}
+
+impl<T> Display for Error<T>
+where
+  T: Display,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Error::Transport(err) => write!(fmt, "{}", err),
+      Error::Token(err) => write!(fmt, "failed to obtain access token: {}", err),
+      Error::InvalidToken(err) => write!(fmt, "received invalid access token: {}", err),
+      Error::Auth(err) => write!(fmt, "failed to apply access token: {}", err),
+    }
+  }
+}
+
+impl<T> StdError for Error<T>
+where
+  T: StdError + 'static,
+{
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Error::Transport(err) => err.source(),
+      Error::Token(err) => err.source(),
+      Error::InvalidToken(err) => err.source(),
+      Error::Auth(err) => err.source(),
+    }
+  }
+}
+
+
+/// The grant used for obtaining tokens.
+enum Grant {
+  ClientCredentials { scope: Option<String> },
+  RefreshToken(String),
+}
+
+
+/// A cached access token.
+struct CachedToken {
+  access_token: String,
+  auth: Bearer,
+  expires_at: Option<Instant>,
+}
+
+
+/// The state of the token cache.
+#[derive(Default)]
+struct State {
+  token: Option<CachedToken>,
+  refresh_token: Option<String>,
+}
+
+
+/// A [`Transport`] authenticating requests using OAuth2 access tokens.
+pub struct OAuth2<T> {
+  transport: T,
+  token_base_url: String,
+  token_path: String,
+  client_id: String,
+  client_secret: Option<String>,
+  grant: Grant,
+  refresh_margin: Duration,
+  state: Mutex<State>,
+}
+
+impl<T> OAuth2<T> {
+  fn new(
+    transport: T,
+    token_url: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+    grant: Grant,
+  ) -> Result<Self, InvalidUri> {
+    let uri = token_url.parse::<Uri>()?;
+    let token_base_url = match (uri.scheme(), uri.authority()) {
+      (Some(scheme), Some(authority)) => format!("{}://{}", scheme, authority),
+      _ => String::new(),
+    };
+    let token_path = uri
+      .path_and_query()
+      .map(|path| path.as_str().to_string())
+      .unwrap_or_else(|| "/".to_string());
+
+    Ok(Self {
+      transport,
+      token_base_url,
+      token_path,
+      client_id: client_id.to_string(),
+      client_secret: client_secret.map(str::to_string),
+      grant,
+      refresh_margin: DEFAULT_REFRESH_MARGIN,
+      state: Mutex::new(State::default()),
+    })
+  }
+
+  /// Create a new `OAuth2` transport obtaining tokens from the token
+  /// endpoint at `token_url` using the client credentials grant.
+  ///
+  /// Client credentials are conveyed in the request body.
+  pub fn client_credentials(
+    transport: T,
+    token_url: &str,
+    client_id: &str,
+    client_secret: &str,
+  ) -> Result<Self, InvalidUri> {
+    let grant = Grant::ClientCredentials { scope: None };
+    Self::new(transport, token_url, client_id, Some(client_secret), grant)
+  }
+
+  /// Create a new `OAuth2` transport obtaining tokens from the token
+  /// endpoint at `token_url` using the refresh token grant.
+  pub fn refresh_token(
+    transport: T,
+    token_url: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+    refresh_token: &str,
+  ) -> Result<Self, InvalidUri> {
+    let grant = Grant::RefreshToken(refresh_token.to_string());
+    Self::new(transport, token_url, client_id, client_secret, grant)
+  }
+
+  /// Set the scope to request when using the client credentials grant.
+  pub fn with_scope(mut self, scope: &str) -> Self {
+    if let Grant::ClientCredentials { scope: grant_scope } = &mut self.grant {
+      *grant_scope = Some(scope.to_string());
+    }
+    self
+  }
+
+  /// Set the time before expiry at which tokens get refreshed.
+  pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+    self.refresh_margin = margin;
+    self
+  }
+
+  /// Retrieve the wrapped transport.
+  pub fn transport(&self) -> &T {
+    &self.transport
+  }
+
+  /// Create the request to the token endpoint.
+  fn token_request(&self, refresh_token: Option<&str>) -> TokenRequest {
+    let mut params = Vec::new();
+    let refresh_token = refresh_token.or(match &self.grant {
+      Grant::RefreshToken(refresh_token) => Some(refresh_token),
+      Grant::ClientCredentials { .. } => None,
+    });
+
+    match (refresh_token, &self.grant) {
+      (Some(refresh_token), _) => {
+        params.push(("grant_type", "refresh_token".to_string()));
+        params.push(("refresh_token", refresh_token.to_string()));
+      },
+      (None, grant) => {
+        params.push(("grant_type", "client_credentials".to_string()));
+        if let Grant::ClientCredentials { scope: Some(scope) } = grant {
+          params.push(("scope", scope.clone()));
+        }
+      },
+    }
+    params.push(("client_id", self.client_id.clone()));
+    if let Some(client_secret) = &self.client_secret {
+      params.push(("client_secret", client_secret.clone()));
+    }
+
+    TokenRequest {
+      path: self.token_path.clone().into(),
+      params,
+    }
+  }
+}
+
+impl<T> OAuth2<T>
+where
+  T: Transport,
+{
+  /// Issue a request to the token endpoint.
+  async fn fetch(&self, refresh_token: Option<&str>) -> Result<TokenResponse, Error<T::Error>> {
+    let input = self.token_request(refresh_token);
+    let request = request::<Token>(Some(&self.token_base_url), &input).map_err(Error::Token)?;
+    let response = self
+      .transport
+      .send(request)
+      .await
+      .map_err(Error::Transport)?;
+    Token::evaluate(response.status(), response.body()).map_err(Error::Token)
+  }
+
+  /// Retrieve an access token, obtaining a new one if none is cached,
+  /// it is about to expire, or it equals `rejected`.
+  async fn token(&self, rejected: Option<&str>) -> Result<(String, Bearer), Error<T::Error>> {
+    let mut state = self.state.lock().await;
+
+    if let Some(token) = &state.token {
+      let expired = token
+        .expires_at
+        .is_some_and(|expires_at| Instant::now() + self.refresh_margin >= expires_at);
+      let rejected = rejected.is_some_and(|rejected| rejected == token.access_token);
+      if !expired && !rejected {
+        return Ok((token.access_token.clone(), token.auth.clone()));
+      }
+    }
+
+    // Prefer a refresh token we got issued, but fall back to the
+    // original grant if it does not work out.
+    let refreshed = match state.refresh_token.take() {
+      Some(refresh_token) => self.fetch(Some(&refresh_token)).await.ok(),
+      None => None,
+    };
+    let response = match refreshed {
+      Some(response) => response,
+      None => self.fetch(None).await?,
+    };
+
+    let auth = Bearer::new(&response.access_token).map_err(Error::InvalidToken)?;
+    state.token = Some(CachedToken {
+      access_token: response.access_token.clone(),
+      auth: auth.clone(),
+      expires_at: response
+        .expires_in
+        .map(|expires_in| Instant::now() + Duration::from_secs(expires_in)),
+    });
+    state.refresh_token = response.refresh_token;
+    Ok((response.access_token, auth))
+  }
+}
+
+impl<T> Debug for OAuth2<T>
+where
+  T: Debug,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("OAuth2")
+      .field("transport", &self.transport)
+      .field("token_base_url", &self.token_base_url)
+      .field("token_path", &self.token_path)
+      .field("client_id", &self.client_id)
+      .field("refresh_margin", &self.refresh_margin)
+      .finish_non_exhaustive()
+  }
+}
+
+impl<T> Transport for OAuth2<T>
+where
+  T: Transport + Sync,
+{
+  type Error = Error<T::Error>;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    Box::pin(async move {
+      let mut retry = Some(request.clone());
+      let mut request = request;
+      let mut rejected = None;
+
+      loop {
+        let (token, auth) = self.token(rejected.as_deref()).await?;
+        let () = auth.authenticate(&mut request).map_err(Error::Auth)?;
+
+        let response = self
+          .transport
+          .send(request)
+          .await
+          .map_err(Error::Transport)?;
+
+        match retry.take() {
+          Some(next) if response.status() == StatusCode::UNAUTHORIZED => {
+            rejected = Some(token);
+            request = next;
+          },
+          _ => break Ok(response),
+        }
+      }
+    })
+  }
+}
@@ -0,0 +1,74 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::time::Duration;
+
+use http::StatusCode;
+
+
+/// A trait for observing the issuing of requests to endpoints, e.g.,
+/// for the purpose of collecting metrics.
+///
+/// All methods are provided and do nothing by default.
+#[allow(unused)]
+pub trait Observer {
+  /// A request to the given endpoint is about to be issued.
+  fn started(&self, endpoint: &'static str) {}
+
+  /// A response with the given status was received after `latency`.
+  fn responded(&self, endpoint: &'static str, status: StatusCode, latency: Duration) {}
+
+  /// Issuing a request finished after `latency`, either successfully,
+  /// or with an error whose variant name is provided.
+  fn finished(&self, endpoint: &'static str, error: Option<&'static str>, latency: Duration) {}
+}
+
+
+#[cfg(feature = "metrics")]
+mod metrics {
+  use super::*;
+
+  use ::metrics::counter;
+  use ::metrics::histogram;
+
+
+  /// An [`Observer`] recording metrics using the `metrics` crate.
+  ///
+  /// The following metrics are emitted, each labeled with the name of
+  /// the endpoint:
+  /// - `http_endpoint_requests_total`: a counter of issued requests
+  /// - `http_endpoint_responses_total`: a counter of received
+  ///   responses, additionally labeled by status
+  /// - `http_endpoint_errors_total`: a counter of failed requests,
+  ///   additionally labeled by error variant
+  /// - `http_endpoint_request_duration_seconds`: a histogram of the
+  ///   time it took to issue requests, successful or not
+  #[derive(Clone, Copy, Debug, Default)]
+  pub struct MetricsObserver;
+
+  impl Observer for MetricsObserver {
+    fn started(&self, endpoint: &'static str) {
+      counter!("http_endpoint_requests_total", "endpoint" => endpoint).increment(1);
+    }
+
+    fn responded(&self, endpoint: &'static str, status: StatusCode, _latency: Duration) {
+      counter!(
+        "http_endpoint_responses_total",
+        "endpoint" => endpoint,
+        "status" => status.as_str().to_string(),
+      )
+      .increment(1);
+    }
+
+    fn finished(&self, endpoint: &'static str, error: Option<&'static str>, latency: Duration) {
+      if let Some(error) = error {
+        counter!("http_endpoint_errors_total", "endpoint" => endpoint, "error" => error)
+          .increment(1);
+      }
+      histogram!("http_endpoint_request_duration_seconds", "endpoint" => endpoint).record(latency);
+    }
+  }
+}
+
+#[cfg(feature = "metrics")]
+pub use self::metrics::MetricsObserver;
@@ -0,0 +1,397 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Generation of OpenAPI documents from endpoint definitions.
+//!
+//! Operations are derived from [`EndpointMeta`] objects, typically as
+//! retrieved from the [`registry`][crate::registry]. Only endpoints
+//! providing a [path template][crate::Endpoint::path_template] can be
+//! described. Path parameters are inferred from placeholders in the
+//! template's path, query parameters from placeholders in its query.
+
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::registry::endpoints;
+use crate::Endpoint;
+use crate::EndpointMeta;
+
+
+/// The OpenAPI version of generated documents.
+const OPENAPI_VERSION: &str = "3.1.0";
+
+
+/// An error reported when generating an OpenAPI document.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+  /// Two endpoints use the same method and path and can not be
+  /// described as separate operations.
+  Conflict(&'static str, &'static str),
+}
+
+impl Display for Error {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::Conflict(first, second) => {
+        write!(
+          fmt,
+          "endpoints {} and {} use the same method and path",
+          first, second
+        )
+      },
+    }
+  }
+}
+
+impl StdError for Error {}
+
+
+/// A trait for types that can describe themselves as JSON Schema.
+pub trait Schema {
+  /// Retrieve the JSON Schema describing `Self`.
+  fn schema() -> Value;
+}
+
+
+impl<T> Schema for Vec<T>
+where
+  T: Schema,
+{
+  fn schema() -> Value {
+    json!({"type": "array", "items": T::schema()})
+  }
+}
+
+
+/// Schemas associated with a single endpoint.
+#[derive(Debug, Default)]
+struct Schemas {
+  input: Option<Value>,
+  output: Option<Value>,
+  error: Option<Value>,
+}
+
+
+/// A builder for OpenAPI documents.
+#[derive(Debug)]
+pub struct Document {
+  title: String,
+  version: String,
+  endpoints: Vec<&'static EndpointMeta>,
+  schemas: HashMap<&'static str, Schemas>,
+}
+
+impl Document {
+  /// Create a new document without any endpoints.
+  pub fn new<T, V>(title: T, version: V) -> Self
+  where
+    T: Into<String>,
+    V: Into<String>,
+  {
+    Self {
+      title: title.into(),
+      version: version.into(),
+      endpoints: Vec::new(),
+      schemas: HashMap::new(),
+    }
+  }
+
+  /// Create a new document describing all registered endpoints.
+  pub fn from_registry<T, V>(title: T, version: V) -> Self
+  where
+    T: Into<String>,
+    V: Into<String>,
+  {
+    Self::new(title, version).with_endpoints(endpoints())
+  }
+
+  /// Add the given endpoints to the document.
+  pub fn with_endpoints<I>(mut self, endpoints: I) -> Self
+  where
+    I: IntoIterator<Item = &'static EndpointMeta>,
+  {
+    self.endpoints.extend(endpoints);
+    self
+  }
+
+  /// Describe the request body of endpoint `E` using the schema of its
+  /// input.
+  pub fn with_input_schema<E>(mut self) -> Self
+  where
+    E: Endpoint,
+    E::Input: Schema,
+  {
+    self.schemas.entry(E::NAME).or_default().input = Some(E::Input::schema());
+    self
+  }
+
+  /// Describe the successful responses of endpoint `E` using the
+  /// schema of its output.
+  pub fn with_output_schema<E>(mut self) -> Self
+  where
+    E: Endpoint,
+    E::Output: Schema,
+  {
+    self.schemas.entry(E::NAME).or_default().output = Some(E::Output::schema());
+    self
+  }
+
+  /// Describe the error responses of endpoint `E` using the schema of
+  /// its API error.
+  pub fn with_error_schema<E>(mut self) -> Self
+  where
+    E: Endpoint,
+    E::ApiError: Schema,
+  {
+    self.schemas.entry(E::NAME).or_default().error = Some(E::ApiError::schema());
+    self
+  }
+
+  /// Retrieve the operation ID of the endpoint with the given name.
+  ///
+  /// The ID is the endpoint's unqualified name, unless another endpoint
+  /// in the document shares it, in which case the fully qualified name
+  /// is used.
+  fn operation_id(&self, name: &'static str) -> String {
+    let short = unqualified(name);
+    let ambiguous = self
+      .endpoints
+      .iter()
+      .any(|meta| meta.name != name && unqualified(meta.name) == short);
+
+    if ambiguous {
+      name.replace("::", "_")
+    } else {
+      short.to_string()
+    }
+  }
+
+  /// Generate the OpenAPI document.
+  ///
+  /// The base URL of an endpoint is conveyed as the `servers` of its
+  /// operation, allowing for endpoints with different base URLs to
+  /// share a path. Endpoints using the same method and path are
+  /// reported as conflicting, though.
+  pub fn to_json(&self) -> Result<Value, Error> {
+    let mut paths = Map::new();
+    let mut operations = HashMap::<(&str, &str), &'static str>::new();
+
+    for meta in &self.endpoints {
+      let template = match meta.path {
+        Some(template) => template,
+        None => continue,
+      };
+      let (path, query) = template.split_once('?').unwrap_or((template, ""));
+      let schemas = self.schemas.get(meta.name);
+
+      let mut parameters = placeholders(path)
+        .into_iter()
+        .map(|name| parameter(name, "path"))
+        .collect::<Vec<_>>();
+      parameters.extend(
+        query
+          .split('&')
+          .filter_map(|pair| pair.split_once('='))
+          .filter(|(_, value)| !placeholders(value).is_empty())
+          .map(|(key, _)| parameter(key, "query")),
+      );
+
+      let method = meta.method.as_str();
+      if let Some(other) = operations.insert((path, method), meta.name) {
+        return Err(Error::Conflict(other, meta.name));
+      }
+
+      let mut operation = Map::new();
+      operation.insert("operationId".into(), self.operation_id(meta.name).into());
+      if let Some(base_url) = &meta.base_url {
+        operation.insert("servers".into(), json!([{"url": base_url}]));
+      }
+      let description = meta.description();
+      if let Some(summary) = description.lines().next().filter(|line| !line.is_empty()) {
+        operation.insert("summary".into(), summary.into());
+        operation.insert("description".into(), description.clone().into());
+      }
+      if !parameters.is_empty() {
+        operation.insert("parameters".into(), parameters.into());
+      }
+      if let Some(input) = schemas.and_then(|schemas| schemas.input.as_ref()) {
+        operation.insert("requestBody".into(), json!({"content": content(input)}));
+      }
+
+      let mut responses = Map::new();
+      for status in meta.ok {
+        let mut response = Map::new();
+        let description = status.canonical_reason().unwrap_or("Success");
+        response.insert("description".into(), description.into());
+        if let Some(output) = schemas.and_then(|schemas| schemas.output.as_ref()) {
+          response.insert("content".into(), content(output));
+        }
+        responses.insert(status.as_str().into(), response.into());
+      }
+      for (status, variant) in meta.err {
+        let mut response = Map::new();
+        response.insert("description".into(), (*variant).into());
+        if let Some(error) = schemas.and_then(|schemas| schemas.error.as_ref()) {
+          response.insert("content".into(), content(error));
+        }
+        responses.insert(status.as_str().into(), response.into());
+      }
+      operation.insert("responses".into(), responses.into());
+
+      let item = paths
+        .entry(path)
+        .or_insert_with(|| Value::Object(Map::new()));
+      let item = item.as_object_mut().unwrap();
+      item.insert(method.to_lowercase(), operation.into());
+    }
+
+    let document = json!({
+      "openapi": OPENAPI_VERSION,
+      "info": {
+        "title": self.title,
+        "version": self.version,
+      },
+      "paths": paths,
+    });
+    Ok(document)
+  }
+}
+
+
+/// Strip the module path from an endpoint name.
+fn unqualified(name: &str) -> &str {
+  name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Extract the names of all placeholders in curly braces in a template.
+fn placeholders(template: &str) -> Vec<&str> {
+  template
+    .split('{')
+    .skip(1)
+    .filter_map(|part| part.split_once('}'))
+    .map(|(name, _)| name)
+    .collect()
+}
+
+/// Create a parameter object.
+fn parameter(name: &str, location: &str) -> Value {
+  json!({
+    "name": name,
+    "in": location,
+    "required": location == "path",
+    "schema": {"type": "string"},
+  })
+}
+
+/// Create a content object for the given schema.
+fn content(schema: &Value) -> Value {
+  json!({"application/json": {"schema": schema}})
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http::Method;
+
+
+  fn meta(name: &'static str, base_url: &'static str, path: &'static str) -> &'static EndpointMeta {
+    Box::leak(Box::new(EndpointMeta {
+      name,
+      method: Method::GET,
+      base_url: Some(base_url.into()),
+      path: Some(path),
+      ok: &[],
+      err: &[],
+      docs: "",
+    }))
+  }
+
+
+  /// Check that we can extract placeholders from templates.
+  #[test]
+  fn placeholder_extraction() {
+    assert_eq!(placeholders("/orders"), Vec::<&str>::new());
+    assert_eq!(placeholders("/orders/{id}"), vec!["id"]);
+    assert_eq!(
+      placeholders("/users/{user}/orders/{order}"),
+      vec!["user", "order"]
+    );
+  }
+
+  /// Check that operation IDs are only qualified if endpoint names are
+  /// ambiguous.
+  #[test]
+  fn operation_ids() {
+    let document = Document::new("Orders", "1.0.0").with_endpoints([
+      meta(
+        "api::v1::GetOrder",
+        "https://example.com",
+        "/v1/orders/{id}",
+      ),
+      meta(
+        "api::v2::GetOrder",
+        "https://example.com",
+        "/v2/orders/{id}",
+      ),
+      meta("api::v2::ListOrders", "https://example.com", "/v2/orders"),
+    ]);
+    let json = document.to_json().unwrap();
+    let paths = &json["paths"];
+    assert_eq!(
+      paths["/v1/orders/{id}"]["get"]["operationId"],
+      "api_v1_GetOrder"
+    );
+    assert_eq!(
+      paths["/v2/orders/{id}"]["get"]["operationId"],
+      "api_v2_GetOrder"
+    );
+    assert_eq!(paths["/v2/orders"]["get"]["operationId"], "ListOrders");
+  }
+
+  /// Check that endpoints with different base URLs can share a path,
+  /// but conflicting ones are reported.
+  #[test]
+  fn shared_paths() {
+    let post = EndpointMeta {
+      method: Method::POST,
+      ..meta("b::CreateStatus", "https://b.example.com", "/status").clone()
+    };
+    let post = Box::leak(Box::new(post));
+    let document = Document::new("Status", "1.0.0").with_endpoints([
+      meta("a::GetStatus", "https://a.example.com", "/status"),
+      post,
+    ]);
+    let json = document.to_json().unwrap();
+    let item = &json["paths"]["/status"];
+    assert_eq!(
+      item["get"]["servers"],
+      json!([{"url": "https://a.example.com"}])
+    );
+    assert_eq!(
+      item["post"]["servers"],
+      json!([{"url": "https://b.example.com"}])
+    );
+    assert_eq!(item.get("servers"), None);
+
+    let document = document.with_endpoints([meta(
+      "b::GetStatus",
+      "https://b.example.com",
+      "/status?verbose={verbose}",
+    )]);
+    let err = document.to_json().unwrap_err();
+    assert_eq!(err, Error::Conflict("a::GetStatus", "b::GetStatus"));
+    assert_eq!(
+      err.to_string(),
+      "endpoints a::GetStatus and b::GetStatus use the same method and path"
+    );
+  }
+}
@@ -0,0 +1,499 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Pagination of listing endpoints.
+//!
+//! An endpoint implementing [`Paginated`] describes how to derive the
+//! input for the next page, either from the input and output of the
+//! current page (e.g., for cursor or offset based pagination) or from
+//! the `next` link in the response's `Link` header (as per RFC 8288).
+//! [`Client::paginate`] then provides a [`Pages`] stream yielding the
+//! individual pages, which can be turned into an [`Items`] stream
+//! yielding the items contained in them or into a [`Blocking`]
+//! iterator.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::future::poll_fn;
+use std::io::Error as IoError;
+use std::mem::replace;
+use std::pin::Pin;
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+
+use futures_core::Stream;
+
+use http::header::LINK;
+use http::HeaderMap;
+use http::Uri;
+
+use tokio::runtime::Builder;
+use tokio::runtime::Runtime;
+
+use crate::BoxFuture;
+use crate::Client;
+use crate::ClientError;
+use crate::Endpoint;
+use crate::Transport;
+
+
+/// The error reported when retrieving a page fails.
+type Error<T, E> = ClientError<<E as Endpoint>::Error, <T as Transport>::Error>;
+
+/// A future retrieving a page, handing back the page's input along
+/// with the result.
+type PageFuture<'c, T, E> = BoxFuture<
+  'c,
+  (
+    <E as Endpoint>::Input,
+    Result<(HeaderMap, <E as Endpoint>::Output), Error<T, E>>,
+  ),
+>;
+
+
+/// An extension to [`Endpoint`] for endpoints returning their results
+/// in pages.
+pub trait Paginated: Endpoint {
+  /// The type of the items contained in a page.
+  type Item;
+
+  /// Extract the items contained in a page.
+  fn items(output: Self::Output) -> Vec<Self::Item>;
+
+  /// Derive the input for retrieving the next page from the input and
+  /// output of the current one.
+  ///
+  /// Returning `None` indicates that no next page is known, in which
+  /// case [`Paginated::next_from_link`] is consulted. By default no
+  /// next page is derived.
+  #[allow(unused)]
+  fn next(input: &Self::Input, output: &Self::Output) -> Option<Self::Input> {
+    None
+  }
+
+  /// Derive the input for retrieving the next page from the target of
+  /// the `next` link in the `Link` header of the current page's
+  /// response.
+  ///
+  /// The link target is not resolved and may be a relative reference.
+  /// By default no next page is derived.
+  #[allow(unused)]
+  fn next_from_link(input: &Self::Input, link: &Uri) -> Option<Self::Input> {
+    None
+  }
+}
+
+
+/// Parse the links contained in a `Link` header value, returning the
+/// target along with the relation types of each.
+fn links(value: &str) -> Vec<(&str, &str)> {
+  let mut links = Vec::new();
+  let mut rest = value;
+
+  loop {
+    rest = rest.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+    let (target, after) = match rest.strip_prefix('<').and_then(|rest| rest.split_once('>')) {
+      Some(link) => link,
+      None => break,
+    };
+    rest = after;
+
+    let mut rel = None;
+    while let Some(param) = rest.trim_start().strip_prefix(';') {
+      let end = param.find(['=', ';', ',']).unwrap_or(param.len());
+      let name = param[..end].trim();
+      rest = &param[end..];
+
+      let mut value = "";
+      if let Some(after) = rest.strip_prefix('=') {
+        let after = after.trim_start();
+        if let Some(quoted) = after.strip_prefix('"') {
+          let end = quoted.find('"').unwrap_or(quoted.len());
+          value = &quoted[..end];
+          rest = quoted.get(end + 1..).unwrap_or("");
+        } else {
+          let end = after.find([';', ',']).unwrap_or(after.len());
+          value = after[..end].trim_end();
+          rest = &after[end..];
+        }
+      }
+
+      // Only the first occurrence of the `rel` parameter is
+      // considered.
+      if rel.is_none() && name.eq_ignore_ascii_case("rel") {
+        rel = Some(value);
+      }
+    }
+
+    let () = links.push((target, rel.unwrap_or("")));
+  }
+  links
+}
+
+
+/// Retrieve the target of the `next` link contained in the `Link`
+/// headers of a response, if any.
+pub fn next_link(headers: &HeaderMap) -> Option<Uri> {
+  headers
+    .get_all(LINK)
+    .iter()
+    .filter_map(|value| value.to_str().ok())
+    .flat_map(links)
+    .find(|(_, rel)| {
+      rel
+        .split_ascii_whitespace()
+        .any(|rel| rel.eq_ignore_ascii_case("next"))
+    })
+    .and_then(|(target, _)| target.parse().ok())
+}
+
+
+/// The state of a [`Pages`] stream.
+enum State<'c, T, E>
+where
+  T: Transport,
+  E: Paginated,
+{
+  /// The next page is to be retrieved using the contained input.
+  Next(E::Input),
+  /// A page is currently being retrieved.
+  Pending(PageFuture<'c, T, E>),
+  /// No more pages are to be retrieved.
+  Done,
+}
+
+
+/// A stream of the pages of a [`Paginated`] endpoint, as created by
+/// [`Client::paginate`].
+///
+/// The stream ends after the last page, once the configured maximum
+/// number of pages has been retrieved, or after the first error.
+pub struct Pages<'c, T, E>
+where
+  T: Transport,
+  E: Paginated,
+{
+  client: &'c Client<T>,
+  state: State<'c, T, E>,
+  max_pages: Option<usize>,
+  retrieved: usize,
+}
+
+impl<'c, T, E> Pages<'c, T, E>
+where
+  T: Transport + Sync,
+  E: Paginated,
+  E::Input: Send + Sync + 'c,
+  E::Output: Send + 'c,
+  E::Error: Send,
+  T::Error: Send,
+{
+  /// Set the maximum number of pages to retrieve.
+  pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+    self.max_pages = Some(max_pages);
+    self
+  }
+
+  /// Retrieve the next page.
+  pub async fn next(&mut self) -> Option<Result<E::Output, Error<T, E>>> {
+    poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+  }
+
+  /// Convert the stream of pages into a stream of the items contained
+  /// in them.
+  pub fn items(self) -> Items<'c, T, E> {
+    Items {
+      pages: self,
+      items: VecDeque::new(),
+    }
+  }
+
+  /// Convert the stream into a blocking iterator.
+  ///
+  /// The iterator drives the stream on a dedicated runtime and must not
+  /// be used from within an asynchronous context.
+  pub fn blocking(self) -> Result<Blocking<Self>, IoError> {
+    Blocking::new(self)
+  }
+}
+
+impl<'c, T, E> Stream for Pages<'c, T, E>
+where
+  T: Transport + Sync,
+  E: Paginated,
+  E::Input: Send + Sync + 'c,
+  E::Output: Send + 'c,
+  E::Error: Send,
+  T::Error: Send,
+{
+  type Item = Result<E::Output, Error<T, E>>;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+
+    loop {
+      match replace(&mut this.state, State::Done) {
+        State::Next(input) => {
+          if this
+            .max_pages
+            .is_some_and(|max_pages| this.retrieved >= max_pages)
+          {
+            return Poll::Ready(None);
+          }
+
+          this.retrieved += 1;
+          let client = this.client;
+          this.state = State::Pending(Box::pin(async move {
+            let result = client.issue_with_headers::<E>(&input).await;
+            (input, result)
+          }));
+        },
+        State::Pending(mut future) => {
+          let (input, result) = match future.as_mut().poll(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => {
+              this.state = State::Pending(future);
+              return Poll::Pending;
+            },
+          };
+
+          let (headers, output) = match result {
+            Ok(page) => page,
+            Err(err) => return Poll::Ready(Some(Err(err))),
+          };
+
+          let next = E::next(&input, &output)
+            .or_else(|| next_link(&headers).and_then(|link| E::next_from_link(&input, &link)));
+          if let Some(next) = next {
+            this.state = State::Next(next);
+          }
+          return Poll::Ready(Some(Ok(output)));
+        },
+        State::Done => return Poll::Ready(None),
+      }
+    }
+  }
+}
+
+// The stream never pins any of its contents.
+impl<T, E> Unpin for Pages<'_, T, E>
+where
+  T: Transport,
+  E: Paginated,
+{
+}
+
+impl<T, E> Debug for Pages<'_, T, E>
+where
+  T: Transport,
+  E: Paginated,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("Pages")
+      .field("endpoint", &E::NAME)
+      .field("max_pages", &self.max_pages)
+      .field("retrieved", &self.retrieved)
+      .finish_non_exhaustive()
+  }
+}
+
+
+/// A stream of the items contained in the pages of a [`Paginated`]
+/// endpoint, as created by [`Pages::items`].
+pub struct Items<'c, T, E>
+where
+  T: Transport,
+  E: Paginated,
+{
+  pages: Pages<'c, T, E>,
+  items: VecDeque<E::Item>,
+}
+
+impl<'c, T, E> Items<'c, T, E>
+where
+  T: Transport + Sync,
+  E: Paginated,
+  E::Input: Send + Sync + 'c,
+  E::Output: Send + 'c,
+  E::Error: Send,
+  T::Error: Send,
+{
+  /// Retrieve the next item.
+  pub async fn next(&mut self) -> Option<Result<E::Item, Error<T, E>>> {
+    poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+  }
+
+  /// Convert the stream into a blocking iterator.
+  ///
+  /// The iterator drives the stream on a dedicated runtime and must not
+  /// be used from within an asynchronous context.
+  pub fn blocking(self) -> Result<Blocking<Self>, IoError> {
+    Blocking::new(self)
+  }
+}
+
+impl<'c, T, E> Stream for Items<'c, T, E>
+where
+  T: Transport + Sync,
+  E: Paginated,
+  E::Input: Send + Sync + 'c,
+  E::Output: Send + 'c,
+  E::Error: Send,
+  T::Error: Send,
+{
+  type Item = Result<E::Item, Error<T, E>>;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+
+    loop {
+      if let Some(item) = this.items.pop_front() {
+        return Poll::Ready(Some(Ok(item)));
+      }
+
+      match ready!(Pin::new(&mut this.pages).poll_next(cx)) {
+        Some(Ok(output)) => this.items.extend(E::items(output)),
+        Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+        None => return Poll::Ready(None),
+      }
+    }
+  }
+}
+
+// The stream never pins any of its contents.
+impl<T, E> Unpin for Items<'_, T, E>
+where
+  T: Transport,
+  E: Paginated,
+{
+}
+
+impl<T, E> Debug for Items<'_, T, E>
+where
+  T: Transport,
+  E: Paginated,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("Items")
+      .field("pages", &self.pages)
+      .field("buffered", &self.items.len())
+      .finish()
+  }
+}
+
+
+/// A blocking iterator over a [`Pages`] or [`Items`] stream.
+pub struct Blocking<S> {
+  runtime: Runtime,
+  stream: S,
+}
+
+impl<S> Blocking<S> {
+  fn new(stream: S) -> Result<Self, IoError> {
+    let runtime = Builder::new_current_thread().enable_time().build()?;
+    Ok(Self { runtime, stream })
+  }
+}
+
+impl<S> Iterator for Blocking<S>
+where
+  S: Stream + Unpin,
+{
+  type Item = S::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let stream = &mut self.stream;
+    self
+      .runtime
+      .block_on(poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)))
+  }
+}
+
+impl<S> Debug for Blocking<S>
+where
+  S: Debug,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("Blocking")
+      .field("stream", &self.stream)
+      .finish_non_exhaustive()
+  }
+}
+
+
+impl<T> Client<T>
+where
+  T: Transport + Sync,
+{
+  /// Retrieve the pages of the [`Paginated`] endpoint `E`, starting
+  /// with the page described by the given input.
+  pub fn paginate<E>(&self, input: E::Input) -> Pages<'_, T, E>
+  where
+    E: Paginated,
+  {
+    Pages {
+      client: self,
+      state: State::Next(input),
+      max_pages: None,
+      retrieved: 0,
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http::HeaderValue;
+
+
+  /// Check that we can parse `Link` header values.
+  #[test]
+  fn link_parsing() {
+    let value = r#"<https://api.example.com/items?page=2>; rel="next", <https://api.example.com/items?page=5>; rel=last"#;
+    assert_eq!(
+      links(value),
+      [
+        ("https://api.example.com/items?page=2", "next"),
+        ("https://api.example.com/items?page=5", "last"),
+      ]
+    );
+
+    let value = r#"</items?page=1>; title="a, b; c"; rel="prev first", </items?page=3>;rel="next";rel=ignored"#;
+    assert_eq!(
+      links(value),
+      [("/items?page=1", "prev first"), ("/items?page=3", "next"),]
+    );
+
+    assert_eq!(links(""), []);
+    assert_eq!(links("garbage"), []);
+    assert_eq!(links("<unterminated"), []);
+    assert_eq!(links("</a>, </b>; rel"), [("/a", ""), ("/b", "")]);
+  }
+
+  /// Check that we find the `next` link among multiple `Link` headers.
+  #[test]
+  fn next_link_retrieval() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(next_link(&headers), None);
+
+    let _prev = headers.append(
+      LINK,
+      HeaderValue::from_static(r#"</items?page=1>; rel="prev""#),
+    );
+    assert_eq!(next_link(&headers), None);
+
+    let _prev = headers.append(
+      LINK,
+      HeaderValue::from_static(r#"</items?page=5>; rel=last, </items?page=3>; rel="NEXT""#),
+    );
+    assert_eq!(next_link(&headers), Some(Uri::from_static("/items?page=3")));
+  }
+}
@@ -0,0 +1,435 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::cmp::max;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use http::HeaderMap;
+use http::Uri;
+
+use crate::Endpoint;
+
+
+/// The header reporting the number of requests remaining in the
+/// current rate limit window.
+const REMAINING: &str = "x-ratelimit-remaining";
+/// The header reporting when the current rate limit window resets.
+const RESET: &str = "x-ratelimit-reset";
+/// Values of the reset header beyond this threshold are interpreted
+/// as Unix timestamps, everything below as a number of seconds.
+const RESET_TIMESTAMP_THRESHOLD: u64 = 1_000_000_000;
+
+
+/// A rate limit quota, i.e., a number of requests allowed per period.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Quota {
+  /// The maximum number of requests that can be issued back-to-back.
+  pub burst: u32,
+  /// The period over which `burst` requests are allowed.
+  pub period: Duration,
+}
+
+impl Quota {
+  /// Create a quota allowing `burst` requests per second.
+  pub fn per_second(burst: u32) -> Self {
+    Self {
+      burst,
+      period: Duration::from_secs(1),
+    }
+  }
+
+  /// Create a quota allowing `burst` requests per minute.
+  pub fn per_minute(burst: u32) -> Self {
+    Self {
+      burst,
+      period: Duration::from_secs(60),
+    }
+  }
+}
+
+
+/// The state of a single limit, tracked using the generic cell rate
+/// algorithm (GCRA).
+#[derive(Debug)]
+struct Gcra {
+  /// The time between two requests at the sustained rate.
+  interval: Duration,
+  /// How far ahead of the theoretical arrival time requests may be
+  /// issued, which is what allows for bursts.
+  tolerance: Duration,
+  /// The theoretical arrival time of the next request.
+  tat: Option<Instant>,
+}
+
+impl Gcra {
+  fn new(quota: Quota) -> Self {
+    let burst = max(quota.burst, 1);
+    let interval = quota.period / burst;
+    Self {
+      interval,
+      tolerance: interval * (burst - 1),
+      tat: None,
+    }
+  }
+
+  /// Determine the earliest time no earlier than `at` at which a
+  /// request may be issued, without reserving a slot.
+  fn earliest(&self, at: Instant) -> Instant {
+    let tat = max(self.tat.unwrap_or(at), at);
+    let allowed = tat.checked_sub(self.tolerance).unwrap_or(at);
+    max(allowed, at)
+  }
+
+  /// Reserve the slot for a request issued at `at`, which must not be
+  /// earlier than what [`Gcra::earliest`] reported.
+  fn commit(&mut self, at: Instant) {
+    let tat = max(self.tat.unwrap_or(at), at);
+    self.tat = Some(tat + self.interval);
+  }
+}
+
+
+/// The rate limiting state associated with a single host.
+#[derive(Debug, Default)]
+struct Host {
+  /// The configured limit, if any.
+  limit: Option<Gcra>,
+  /// The time until which the server asked us to back off.
+  blocked_until: Option<Instant>,
+}
+
+
+#[derive(Debug, Default)]
+struct State {
+  hosts: HashMap<String, Host>,
+  endpoints: HashMap<&'static str, Gcra>,
+}
+
+
+/// A client side rate limiter for endpoints.
+///
+/// Limits can be configured per host and per endpoint type. Requests
+/// are subject to both. In addition, the limiter adapts to the
+/// `X-RateLimit-Remaining` and `X-RateLimit-Reset` headers reported by
+/// servers, holding back requests to a host that reported its quota as
+/// exhausted until the reported reset time.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+  state: Mutex<State>,
+}
+
+impl RateLimiter {
+  /// Create a new rate limiter without any limits configured.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Limit requests to the host of the given base URL.
+  ///
+  /// Hosts are matched including their effective port, i.e., a limit
+  /// for `https://example.com` applies to requests to
+  /// `https://example.com:443/` but not to `http://example.com/`. Any
+  /// path component of `base_url` is ignored: servers enforce quotas
+  /// (and report them via rate limit headers) per host, so base URLs
+  /// sharing a host share a limit as well.
+  pub fn with_host_limit(self, base_url: &str, quota: Quota) -> Self {
+    let host = base_url
+      .parse::<Uri>()
+      .ok()
+      .and_then(|uri| host(&uri))
+      .unwrap_or_else(|| base_url.to_ascii_lowercase());
+    self
+      .state
+      .lock()
+      .unwrap()
+      .hosts
+      .entry(host)
+      .or_default()
+      .limit = Some(Gcra::new(quota));
+    self
+  }
+
+  /// Limit requests to the endpoint `E`.
+  pub fn with_endpoint_limit<E>(self, quota: Quota) -> Self
+  where
+    E: Endpoint,
+  {
+    self
+      .state
+      .lock()
+      .unwrap()
+      .endpoints
+      .insert(E::NAME, Gcra::new(quota));
+    self
+  }
+
+  /// Reserve a slot for issuing a request for endpoint `E` to the
+  /// given URI, returning the time to wait before doing so.
+  pub fn delay<E>(&self, uri: &Uri) -> Duration
+  where
+    E: Endpoint,
+  {
+    let now = Instant::now();
+    self.reserve::<E>(uri, now).saturating_duration_since(now)
+  }
+
+  fn reserve<E>(&self, uri: &Uri, now: Instant) -> Instant
+  where
+    E: Endpoint,
+  {
+    let mut state = self.state.lock().unwrap();
+    let State { hosts, endpoints } = &mut *state;
+    let mut endpoint = endpoints.get_mut(E::NAME);
+    let mut host = host(uri).and_then(|host| hosts.get_mut(&host));
+
+    // Determine the final dispatch time first and only then reserve
+    // slots at it. Otherwise requests held back by the server would
+    // all be released at once, disregarding the endpoint's spacing.
+    let mut at = now;
+    if let Some(limit) = &endpoint {
+      at = limit.earliest(at);
+    }
+    if let Some(host) = &mut host {
+      if let Some(blocked_until) = host.blocked_until {
+        if blocked_until > at {
+          at = blocked_until
+        } else {
+          host.blocked_until = None;
+        }
+      }
+      if let Some(limit) = &host.limit {
+        at = limit.earliest(at);
+      }
+    }
+
+    if let Some(limit) = &mut endpoint {
+      let () = limit.commit(at);
+    }
+    if let Some(limit) = host.and_then(|host| host.limit.as_mut()) {
+      let () = limit.commit(at);
+    }
+    at
+  }
+
+  /// Inform the limiter about the headers of a response received from
+  /// the given URI.
+  pub fn observe(&self, uri: &Uri, headers: &HeaderMap) {
+    self.observe_at(uri, headers, Instant::now(), SystemTime::now())
+  }
+
+  fn observe_at(&self, uri: &Uri, headers: &HeaderMap, now: Instant, system_now: SystemTime) {
+    let header = |name| {
+      headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+
+    let host = match host(uri) {
+      Some(host) => host,
+      None => return,
+    };
+
+    if let (Some(0), Some(reset)) = (header(REMAINING), header(RESET)) {
+      let wait = if reset >= RESET_TIMESTAMP_THRESHOLD {
+        let reset = UNIX_EPOCH + Duration::from_secs(reset);
+        reset.duration_since(system_now).unwrap_or_default()
+      } else {
+        Duration::from_secs(reset)
+      };
+
+      let mut state = self.state.lock().unwrap();
+      let host = state.hosts.entry(host).or_default();
+      host.blocked_until = Some(now + wait);
+    }
+  }
+}
+
+
+/// Extract the host from a URI, normalized to lower case host name
+/// and effective port, e.g., `example.com:443`.
+///
+/// URIs without scheme are assumed to use HTTPS.
+pub(crate) fn host(uri: &Uri) -> Option<String> {
+  let host = uri.host()?.to_ascii_lowercase();
+  let port = uri.port_u16().or(match uri.scheme_str() {
+    Some("http") => Some(80),
+    Some("https") | None => Some(443),
+    Some(_) => None,
+  });
+
+  match port {
+    Some(port) => Some(format!("{host}:{port}")),
+    None => Some(host),
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http::HeaderValue;
+
+  use crate::Str;
+
+
+  EndpointDef! {
+    Get(()),
+    Ok => (), [],
+    Err => GetError, [],
+    ConversionErr => ::std::fmt::Error,
+    ApiErr => ::std::fmt::Error,
+
+    fn path(_: &Self::Input) -> Str {
+      "/".into()
+    }
+
+    fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+      Ok(())
+    }
+
+    fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+      Err(body.to_vec())
+    }
+  }
+
+
+  /// Check that we can extract the normalized host from a URI.
+  #[test]
+  fn host_extraction() {
+    let host = |uri| host(&Uri::from_static(uri));
+    assert_eq!(host("https://example.com/v2/").unwrap(), "example.com:443");
+    assert_eq!(host("https://Example.COM:443/").unwrap(), "example.com:443");
+    assert_eq!(
+      host("https://user:pw@example.com/").unwrap(),
+      "example.com:443"
+    );
+    assert_eq!(host("http://localhost:8080").unwrap(), "localhost:8080");
+    assert_eq!(host("http://localhost").unwrap(), "localhost:80");
+    assert_eq!(host("example.com").unwrap(), "example.com:443");
+    assert_eq!(host("/relative"), None);
+  }
+
+  /// Check that host limits apply irrespective of how the host is
+  /// spelled.
+  #[test]
+  fn host_normalization() {
+    let limiter = RateLimiter::new().with_host_limit("https://example.com", Quota::per_second(1));
+    let now = Instant::now();
+
+    let uri = Uri::from_static("https://example.com/");
+    assert_eq!(limiter.reserve::<Get>(&uri, now), now);
+    let uri = Uri::from_static("https://user@example.com:443/");
+    assert_eq!(
+      limiter.reserve::<Get>(&uri, now),
+      now + Duration::from_secs(1)
+    );
+
+    // A different port denotes a different host.
+    let uri = Uri::from_static("http://example.com/");
+    assert_eq!(limiter.reserve::<Get>(&uri, now), now);
+  }
+
+  /// Make sure that bursts are allowed and excess requests get spaced
+  /// out at the configured rate.
+  #[test]
+  fn burst_and_spacing() {
+    let uri = Uri::from_static("https://example.com/");
+    let limiter = RateLimiter::new().with_host_limit("https://example.com", Quota::per_second(2));
+    let now = Instant::now();
+
+    assert_eq!(limiter.reserve::<Get>(&uri, now), now);
+    assert_eq!(limiter.reserve::<Get>(&uri, now), now);
+    assert_eq!(
+      limiter.reserve::<Get>(&uri, now),
+      now + Duration::from_millis(500)
+    );
+    assert_eq!(
+      limiter.reserve::<Get>(&uri, now),
+      now + Duration::from_millis(1000)
+    );
+
+    // Other hosts are not affected.
+    let other = Uri::from_static("https://example.org/");
+    assert_eq!(limiter.reserve::<Get>(&other, now), now);
+  }
+
+  /// Check that endpoint and host limits are both honored.
+  #[test]
+  fn endpoint_and_host_limit() {
+    let uri = Uri::from_static("https://example.com/");
+    let limiter = RateLimiter::new()
+      .with_host_limit("https://example.com", Quota::per_second(10))
+      .with_endpoint_limit::<Get>(Quota::per_second(1));
+    let now = Instant::now();
+
+    assert_eq!(limiter.reserve::<Get>(&uri, now), now);
+    assert_eq!(
+      limiter.reserve::<Get>(&uri, now),
+      now + Duration::from_secs(1)
+    );
+  }
+
+  /// Check that requests held back by the server still honor the
+  /// endpoint limit once released.
+  #[test]
+  fn endpoint_limit_after_back_off() {
+    let uri = Uri::from_static("https://example.com/");
+    let limiter = RateLimiter::new()
+      .with_host_limit("https://example.com", Quota::per_second(10))
+      .with_endpoint_limit::<Get>(Quota::per_second(1));
+    let now = Instant::now();
+    let system_now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(REMAINING, HeaderValue::from_static("0"));
+    headers.insert(RESET, HeaderValue::from_static("30"));
+    limiter.observe_at(&uri, &headers, now, system_now);
+
+    for i in 0..3 {
+      assert_eq!(
+        limiter.reserve::<Get>(&uri, now),
+        now + Duration::from_secs(30 + i)
+      );
+    }
+  }
+
+  /// Verify that we back off after a server reported an exhausted
+  /// quota.
+  #[test]
+  fn adapt_to_headers() {
+    let uri = Uri::from_static("https://example.com/");
+    let limiter = RateLimiter::new();
+    let now = Instant::now();
+    let system_now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(REMAINING, HeaderValue::from_static("1"));
+    headers.insert(RESET, HeaderValue::from_static("30"));
+    limiter.observe_at(&uri, &headers, now, system_now);
+    assert_eq!(limiter.reserve::<Get>(&uri, now), now);
+
+    headers.insert(REMAINING, HeaderValue::from_static("0"));
+    limiter.observe_at(&uri, &headers, now, system_now);
+    assert_eq!(
+      limiter.reserve::<Get>(&uri, now),
+      now + Duration::from_secs(30)
+    );
+
+    headers.insert(RESET, HeaderValue::from_static("1700000005"));
+    limiter.observe_at(&uri, &headers, now, system_now);
+    assert_eq!(
+      limiter.reserve::<Get>(&uri, now),
+      now + Duration::from_secs(5)
+    );
+
+    let later = now + Duration::from_secs(6);
+    assert_eq!(limiter.reserve::<Get>(&uri, later), later);
+  }
+}
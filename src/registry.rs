@@ -0,0 +1,59 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! A registry of all endpoints defined using [`EndpointDef`].
+//!
+//! With the `registry` feature enabled, every endpoint defined using
+//! the macro is registered automatically, irrespective of the crate it
+//! is defined in.
+
+use std::collections::BTreeMap;
+
+use crate::EndpointMeta;
+use crate::Str;
+
+
+/// The registration of a single endpoint.
+///
+/// Registrations are emitted by the `EndpointDef` macro and not meant
+/// to be created manually.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Registration {
+  meta: fn() -> &'static EndpointMeta,
+}
+
+impl Registration {
+  pub const fn new(meta: fn() -> &'static EndpointMeta) -> Self {
+    Self { meta }
+  }
+}
+
+inventory::collect!(Registration);
+
+
+/// Retrieve the metadata of all registered endpoints, ordered by name.
+pub fn endpoints() -> Vec<&'static EndpointMeta> {
+  let mut endpoints = inventory::iter::<Registration>
+    .into_iter()
+    .map(|registration| (registration.meta)())
+    .collect::<Vec<_>>();
+  endpoints.sort_by(|a, b| a.name.cmp(b.name).then_with(|| a.path.cmp(&b.path)));
+  endpoints
+}
+
+/// Retrieve the metadata of all registered endpoints, grouped by base
+/// URL.
+///
+/// Endpoints not providing a base URL are grouped under `None`.
+pub fn endpoints_by_base_url() -> BTreeMap<Option<Str>, Vec<&'static EndpointMeta>> {
+  endpoints()
+    .into_iter()
+    .fold(BTreeMap::new(), |mut groups, meta| {
+      groups
+        .entry(meta.base_url.clone())
+        .or_insert_with(Vec::new)
+        .push(meta);
+      groups
+    })
+}
@@ -0,0 +1,104 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::time::Duration;
+
+use http::header::AUTHORIZATION;
+use http::header::COOKIE;
+use http::header::PROXY_AUTHORIZATION;
+use http::header::SET_COOKIE;
+use http::request::Builder as RequestBuilder;
+use http::HeaderName;
+use http::HeaderValue;
+use http::Request;
+
+use crate::trace;
+use crate::Bytes;
+use crate::Endpoint;
+
+
+/// Names of headers whose values are considered sensitive, e.g., for
+/// the purpose of logging.
+const SENSITIVE: [HeaderName; 4] = [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE, SET_COOKIE];
+
+
+/// Check whether the value of a header is sensitive and should be
+/// redacted when shown.
+pub(crate) fn is_sensitive(name: &HeaderName, value: &HeaderValue) -> bool {
+  value.is_sensitive() || SENSITIVE.contains(name)
+}
+
+
+/// A request extension conveying the timeout to apply to a request.
+///
+/// Transports should honor it for establishing the connection as well
+/// as for the request as a whole.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Timeout(pub Duration);
+
+
+/// A request extension conveying how caches should treat a request.
+///
+/// The policy of a request is set based on the endpoint's
+/// [`Endpoint::cache_policy`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CachePolicy {
+  /// Serve and store responses as per the usual HTTP caching rules.
+  #[default]
+  Default,
+  /// Serve stored responses irrespective of their freshness, only
+  /// contacting the server if no response is stored.
+  ForceCache,
+  /// Neither serve responses from the cache nor store them in it.
+  Bypass,
+}
+
+
+/// Create the HTTP request for issuing the given endpoint.
+///
+/// The base URL reported by the endpoint itself takes precedence over
+/// the provided one. If neither is available, the request's URI will
+/// just comprise the path and query. An endpoint's timeout, if any, is
+/// conveyed in the form of a [`Timeout`] extension and its cache
+/// policy, if not the default one, as a [`CachePolicy`] extension.
+pub fn request<E>(base_url: Option<&str>, input: &E::Input) -> Result<Request<Bytes>, E::Error>
+where
+  E: Endpoint,
+{
+  let endpoint_url = E::base_url();
+  let base_url = endpoint_url.as_deref().or(base_url).unwrap_or("");
+
+  let path = E::path(input);
+  let mut uri = String::with_capacity(base_url.len() + path.len() + 1);
+  uri.push_str(base_url.trim_end_matches('/'));
+  if !path.starts_with('/') {
+    uri.push('/');
+  }
+  uri.push_str(&path);
+
+  if let Some(query) = E::query(input)? {
+    uri.push('?');
+    uri.push_str(&query);
+  }
+
+  let body = E::body(input)?.unwrap_or(Bytes::Borrowed(&[]));
+  let headers = E::headers(input)?;
+  let mut request = RequestBuilder::new()
+    .method(E::method())
+    .uri(uri)
+    .body(body)?;
+
+  if let Some(headers) = headers {
+    request.headers_mut().extend(headers);
+  }
+  if let Some(timeout) = E::timeout() {
+    request.extensions_mut().insert(Timeout(timeout));
+  }
+  let cache_policy = E::cache_policy();
+  if cache_policy != CachePolicy::Default {
+    request.extensions_mut().insert(cache_policy);
+  }
+
+  trace::created::<E>(&request);
+  Ok(request)
+}
@@ -0,0 +1,305 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Serving of endpoints, i.e., the server side counterpart to issuing
+//! requests to them.
+//!
+//! An endpoint that additionally implements [`Serve`] can decode an
+//! incoming request into its input and encode the result of handling
+//! it into a response, using the status codes declared in its
+//! definition. That way, client and server share a single definition.
+
+use std::error::Error as StdError;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use http::HeaderMap;
+use http::Method;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use http::Uri;
+
+use crate::encode::percent_decode;
+use crate::Endpoint;
+
+
+/// An error indicating why an incoming request could not be decoded.
+#[derive(Debug)]
+pub enum Error<C> {
+  /// The request used a method other than the endpoint's.
+  Method(Method),
+  /// The request's path does not match the endpoint's path template.
+  Path(String),
+  /// The input could not be decoded from the request.
+  Conversion(C),
+}
+
+impl<C> Error<C>
+where
+  C: Display,
+{
+  /// Retrieve the HTTP status to reject the request with.
+  pub fn status(&self) -> StatusCode {
+    match self {
+      Self::Method(..) => StatusCode::METHOD_NOT_ALLOWED,
+      Self::Path(..) => StatusCode::NOT_FOUND,
+      Self::Conversion(..) => StatusCode::BAD_REQUEST,
+    }
+  }
+
+  /// Create the response rejecting the request.
+  pub fn into_response(self) -> Response<Vec<u8>> {
+    response(self.status(), self.to_string().into_bytes())
+  }
+}
+
+impl<C> Display for Error<C>
+where
+  C: Display,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::Method(method) => write!(fmt, "unexpected request method {}", method),
+      Self::Path(path) => write!(fmt, "no endpoint at path {}", path),
+      Self::Conversion(err) => write!(fmt, "{}", err),
+    }
+  }
+}
+
+impl<C> StdError for Error<C>
+where
+  C: StdError,
+{
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Self::Method(..) | Self::Path(..) => None,
+      Self::Conversion(err) => err.source(),
+    }
+  }
+}
+
+
+/// An incoming request matched against an endpoint's path template.
+#[derive(Debug)]
+pub struct Incoming<'r> {
+  method: &'r Method,
+  uri: &'r Uri,
+  headers: &'r HeaderMap,
+  body: &'r [u8],
+  params: Vec<(&'static str, String)>,
+}
+
+impl<'r> Incoming<'r> {
+  /// Retrieve the request's method.
+  pub fn method(&self) -> &Method {
+    self.method
+  }
+
+  /// Retrieve the request's URI.
+  pub fn uri(&self) -> &Uri {
+    self.uri
+  }
+
+  /// Retrieve the request's headers.
+  pub fn headers(&self) -> &HeaderMap {
+    self.headers
+  }
+
+  /// Retrieve the request's body.
+  pub fn body(&self) -> &[u8] {
+    self.body
+  }
+
+  /// Retrieve the percent-decoded value of the path parameter with the
+  /// given name, as named in the endpoint's path template.
+  pub fn param(&self, name: &str) -> Option<&str> {
+    self
+      .params
+      .iter()
+      .find(|(param, _)| *param == name)
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Retrieve the request's query, if any.
+  pub fn query(&self) -> Option<&str> {
+    self.uri.query()
+  }
+
+  /// Retrieve the percent-decoded value of the first query parameter
+  /// with the given name.
+  pub fn query_param(&self, name: &str) -> Option<String> {
+    self
+      .query()?
+      .split('&')
+      .filter_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (percent_decode(key) == name.as_bytes()).then_some(value)
+      })
+      .map(|value| String::from_utf8_lossy(&percent_decode(value)).into_owned())
+      .next()
+  }
+}
+
+
+/// A trait for endpoints that can be served, as the inverse of
+/// [`Endpoint`].
+///
+/// Incoming requests are matched against the endpoint's method and
+/// [path template][Endpoint::path_template], which hence has to be
+/// provided. Placeholders in the template match a single path segment.
+pub trait Serve: Endpoint {
+  /// Decode the endpoint's input from an incoming request, as the
+  /// inverse of [`Endpoint::path`], [`Endpoint::query`],
+  /// [`Endpoint::headers`], and [`Endpoint::body`].
+  fn decode(request: &Incoming<'_>) -> Result<Self::Input, Self::ConversionError>;
+
+  /// Encode the endpoint's output into a response body, as the inverse
+  /// of [`Endpoint::parse`].
+  fn encode(output: &Self::Output) -> Result<Vec<u8>, Self::ConversionError>;
+
+  /// Encode an API error into a response body, as the inverse of
+  /// [`Endpoint::parse_err`].
+  fn encode_err(error: &Self::ApiError) -> Vec<u8>;
+
+  /// Retrieve the status to report the given output with.
+  ///
+  /// By default, the first success status declared for the endpoint is
+  /// used.
+  fn status(output: &Self::Output) -> StatusCode {
+    let _ = output;
+    Self::meta().ok.first().copied().unwrap_or(StatusCode::OK)
+  }
+}
+
+
+fn response(status: StatusCode, body: Vec<u8>) -> Response<Vec<u8>> {
+  let mut response = Response::new(body);
+  *response.status_mut() = status;
+  response
+}
+
+
+/// Match a path against a path template, returning the values of the
+/// template's placeholders on success.
+fn match_template(template: &'static str, path: &str) -> Option<Vec<(&'static str, String)>> {
+  // Any templated query is not relevant for routing.
+  let template = template.split('?').next().unwrap_or("");
+  let template = template.trim_start_matches('/').split('/');
+  let mut path = path.trim_start_matches('/').split('/');
+  let mut params = Vec::new();
+
+  for pattern in template {
+    let segment = path.next()?;
+    match (pattern.find('{'), pattern.rfind('}')) {
+      (Some(start), Some(end)) if start < end => {
+        let (prefix, suffix) = (&pattern[..start], &pattern[end + 1..]);
+        let value = segment
+          .strip_prefix(prefix)?
+          .strip_suffix(suffix)
+          .filter(|value| !value.is_empty())?;
+        let value = String::from_utf8(percent_decode(value)).ok()?;
+        let () = params.push((&pattern[start + 1..end], value));
+      },
+      _ if pattern == segment => (),
+      _ => return None,
+    }
+  }
+
+  path.next().is_none().then_some(params)
+}
+
+
+/// Check whether an incoming request targets the given endpoint.
+pub fn matches<E, B>(request: &Request<B>) -> bool
+where
+  E: Endpoint,
+{
+  request.method() == E::method()
+    && E::path_template()
+      .and_then(|template| match_template(template, request.uri().path()))
+      .is_some()
+}
+
+
+/// Decode an incoming request into the endpoint's input.
+pub fn decode<E, B>(request: &Request<B>) -> Result<E::Input, Error<E::ConversionError>>
+where
+  E: Serve,
+  B: AsRef<[u8]>,
+{
+  let path = request.uri().path();
+  let params = E::path_template()
+    .and_then(|template| match_template(template, path))
+    .ok_or_else(|| Error::Path(path.to_string()))?;
+  if request.method() != E::method() {
+    return Err(Error::Method(request.method().clone()));
+  }
+
+  let incoming = Incoming {
+    method: request.method(),
+    uri: request.uri(),
+    headers: request.headers(),
+    body: request.body().as_ref(),
+    params,
+  };
+  E::decode(&incoming).map_err(Error::Conversion)
+}
+
+
+/// Encode the result of handling a request to an endpoint into a
+/// response.
+///
+/// Outputs are reported with the status provided by [`Serve::status`],
+/// while errors use the status declared for the respective variant.
+/// Errors not corresponding to a status, such as conversion errors, are
+/// reported as internal server errors.
+pub fn respond<E>(result: Result<E::Output, E::Error>) -> Response<Vec<u8>>
+where
+  E: Serve,
+{
+  match result {
+    Ok(output) => match E::encode(&output) {
+      Ok(body) => response(E::status(&output), body),
+      Err(err) => response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        err.to_string().into_bytes(),
+      ),
+    },
+    Err(err) => match E::error_status(&err) {
+      Some((status, Ok(api_err))) => response(status, E::encode_err(api_err)),
+      Some((status, Err(body))) => response(status, body.to_vec()),
+      None => response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        err.to_string().into_bytes(),
+      ),
+    },
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  /// Check that we match paths against templates correctly.
+  #[test]
+  fn template_matching() {
+    let params = match_template("/orders/{id}", "/orders/42").unwrap();
+    assert_eq!(params, [("id", "42".to_string())]);
+
+    let params = match_template("/files/{name}.json?v={v}", "/files/a%20b.json").unwrap();
+    assert_eq!(params, [("name", "a b".to_string())]);
+
+    let params = match_template("/orders", "/orders").unwrap();
+    assert!(params.is_empty());
+
+    assert_eq!(match_template("/orders/{id}", "/orders"), None);
+    assert_eq!(match_template("/orders/{id}", "/orders/"), None);
+    assert_eq!(match_template("/orders/{id}", "/orders/42/items"), None);
+    assert_eq!(match_template("/orders/{id}", "/order/42"), None);
+    assert_eq!(match_template("/files/{name}.json", "/files/a.yaml"), None);
+  }
+}
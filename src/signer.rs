@@ -0,0 +1,387 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Signing of requests using HMAC-SHA256, as required by many
+//! exchange and brokerage APIs.
+
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use http::header::InvalidHeaderValue;
+use http::HeaderName;
+use http::HeaderValue;
+use http::Request;
+
+use crate::crypto::hmac_sha256;
+use crate::crypto::sha256;
+use crate::encode::base64;
+use crate::encode::hex;
+use crate::encode::DateTime;
+use crate::Auth;
+use crate::AuthError;
+use crate::Bytes;
+
+
+/// A function generating nonces.
+type NonceFn = Arc<dyn Fn() -> String + Send + Sync>;
+
+
+/// An error indicating that a signature template is malformed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateError(String);
+
+impl Display for TemplateError {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    write!(fmt, "invalid signature template: {}", self.0)
+  }
+}
+
+impl StdError for TemplateError {}
+
+
+/// A piece of a signature template.
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+  Literal(String),
+  Timestamp,
+  Nonce,
+  Method,
+  Path,
+  Query,
+  PathAndQuery,
+  Body,
+  BodySha256,
+}
+
+/// Parse a signature template into its segments.
+fn parse_template(template: &str) -> Result<Vec<Segment>, TemplateError> {
+  let mut segments = Vec::new();
+  let mut rest = template;
+
+  while let Some(start) = rest.find('{') {
+    if start > 0 {
+      let () = segments.push(Segment::Literal(rest[..start].to_string()));
+    }
+    let end = rest[start..]
+      .find('}')
+      .ok_or_else(|| TemplateError(format!("unterminated placeholder at `{}`", &rest[start..])))?;
+    let placeholder = &rest[start + 1..start + end];
+    let segment = match placeholder {
+      "timestamp" => Segment::Timestamp,
+      "nonce" => Segment::Nonce,
+      "method" => Segment::Method,
+      "path" => Segment::Path,
+      "query" => Segment::Query,
+      "path_and_query" => Segment::PathAndQuery,
+      "body" => Segment::Body,
+      "body_sha256" => Segment::BodySha256,
+      _ => {
+        return Err(TemplateError(format!(
+          "unknown placeholder `{{{placeholder}}}`"
+        )))
+      },
+    };
+    let () = segments.push(segment);
+    rest = &rest[start + end + 1..];
+  }
+
+  if !rest.is_empty() {
+    let () = segments.push(Segment::Literal(rest.to_string()));
+  }
+  Ok(segments)
+}
+
+
+/// The format of timestamps included in signatures.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Timestamp {
+  /// Seconds since the Unix epoch.
+  Seconds,
+  /// Milliseconds since the Unix epoch.
+  #[default]
+  Millis,
+  /// An ISO 8601 timestamp with millisecond precision in UTC, e.g.,
+  /// `2026-10-18T13:10:45.000Z`.
+  Iso8601,
+}
+
+impl Timestamp {
+  fn format(&self, time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    match self {
+      Self::Seconds => duration.as_secs().to_string(),
+      Self::Millis => duration.as_millis().to_string(),
+      Self::Iso8601 => DateTime::new(time).iso8601(),
+    }
+  }
+}
+
+
+/// The encoding of the signature.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Encoding {
+  /// Lower case hexadecimal encoding.
+  #[default]
+  Hex,
+  /// Standard base64 encoding.
+  Base64,
+}
+
+
+/// An [`Auth`] provider signing requests using HMAC-SHA256.
+///
+/// The signed message is described by a template, in which the
+/// following placeholders are replaced with the corresponding part of
+/// the request:
+/// - `{timestamp}`: the current time, formatted as per
+///   [`HmacSigner::with_timestamp`]
+/// - `{nonce}`: a nonce, as produced by the configured generator
+/// - `{method}`: the request method, e.g., `POST`
+/// - `{path}`: the request path, e.g., `/api/v3/order`
+/// - `{query}`: the query string without leading `?`, if any
+/// - `{path_and_query}`: the path followed by `?` and the query string,
+///   if one is present
+/// - `{body}`: the request body
+/// - `{body_sha256}`: the hex encoded SHA-256 digest of the request body
+///
+/// For example, an API expecting the signature over the concatenation
+/// of timestamp, method, path, and body would use the template
+/// `{timestamp}{method}{path_and_query}{body}`.
+#[derive(Clone)]
+pub struct HmacSigner {
+  secret: Vec<u8>,
+  template: Vec<Segment>,
+  signature_header: HeaderName,
+  timestamp_header: Option<HeaderName>,
+  timestamp: Timestamp,
+  nonce_header: Option<HeaderName>,
+  nonce: NonceFn,
+  encoding: Encoding,
+  headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl HmacSigner {
+  /// Create a new `HmacSigner` signing messages described by
+  /// `template` using `secret` and setting the signature in the header
+  /// with the given name.
+  pub fn new(
+    secret: &[u8],
+    template: &str,
+    signature_header: HeaderName,
+  ) -> Result<Self, TemplateError> {
+    // By default, nonces are microseconds since the epoch, strictly
+    // increasing even when requested faster than the clock advances.
+    let last = Arc::new(AtomicU64::new(0));
+    let nonce = move || {
+      let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64;
+      let prev = last
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+          Some(now.max(last + 1))
+        })
+        // The closure never fails.
+        .unwrap_or_default();
+      now.max(prev + 1).to_string()
+    };
+
+    Ok(Self {
+      secret: secret.to_vec(),
+      template: parse_template(template)?,
+      signature_header,
+      timestamp_header: None,
+      timestamp: Timestamp::default(),
+      nonce_header: None,
+      nonce: Arc::new(nonce),
+      encoding: Encoding::default(),
+      headers: Vec::new(),
+    })
+  }
+
+  /// Set the format of timestamps.
+  pub fn with_timestamp(mut self, timestamp: Timestamp) -> Self {
+    self.timestamp = timestamp;
+    self
+  }
+
+  /// Set the name of the header conveying the timestamp included in
+  /// the signature.
+  pub fn with_timestamp_header(mut self, name: HeaderName) -> Self {
+    self.timestamp_header = Some(name);
+    self
+  }
+
+  /// Set the name of the header conveying the nonce included in the
+  /// signature.
+  pub fn with_nonce_header(mut self, name: HeaderName) -> Self {
+    self.nonce_header = Some(name);
+    self
+  }
+
+  /// Set the function used for generating nonces.
+  pub fn with_nonce<F>(mut self, nonce: F) -> Self
+  where
+    F: Fn() -> String + Send + Sync + 'static,
+  {
+    self.nonce = Arc::new(nonce);
+    self
+  }
+
+  /// Set the encoding of the signature.
+  pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+    self.encoding = encoding;
+    self
+  }
+
+  /// Set an additional header on each signed request, typically the
+  /// API key identifying the secret. The value is marked as sensitive.
+  pub fn with_header(mut self, name: HeaderName, value: &str) -> Result<Self, InvalidHeaderValue> {
+    let mut value = HeaderValue::from_str(value)?;
+    value.set_sensitive(true);
+    let () = self.headers.push((name, value));
+    Ok(self)
+  }
+
+  /// Create the message to sign for a request.
+  fn message(&self, request: &Request<Bytes>, timestamp: &str, nonce: &str) -> Vec<u8> {
+    let uri = request.uri();
+    let query = uri.query().unwrap_or("");
+
+    let mut message = Vec::new();
+    for segment in &self.template {
+      match segment {
+        Segment::Literal(literal) => message.extend_from_slice(literal.as_bytes()),
+        Segment::Timestamp => message.extend_from_slice(timestamp.as_bytes()),
+        Segment::Nonce => message.extend_from_slice(nonce.as_bytes()),
+        Segment::Method => message.extend_from_slice(request.method().as_str().as_bytes()),
+        Segment::Path => message.extend_from_slice(uri.path().as_bytes()),
+        Segment::Query => message.extend_from_slice(query.as_bytes()),
+        Segment::PathAndQuery => {
+          message.extend_from_slice(uri.path().as_bytes());
+          if !query.is_empty() {
+            message.push(b'?');
+            message.extend_from_slice(query.as_bytes());
+          }
+        },
+        Segment::Body => message.extend_from_slice(request.body()),
+        Segment::BodySha256 => message.extend_from_slice(hex(&sha256(request.body())).as_bytes()),
+      }
+    }
+    message
+  }
+
+  /// Sign a request as of the given point in time.
+  ///
+  /// An error is reported if the nonce is to be conveyed in a header
+  /// but the configured generator produced a value not valid as such.
+  /// The request is left untouched in this case.
+  pub fn sign_at(
+    &self,
+    request: &mut Request<Bytes>,
+    time: SystemTime,
+  ) -> Result<(), InvalidHeaderValue> {
+    let timestamp = self.timestamp.format(time);
+    let nonce = if self.template.contains(&Segment::Nonce) || self.nonce_header.is_some() {
+      (self.nonce)()
+    } else {
+      String::new()
+    };
+    let nonce_header = match &self.nonce_header {
+      Some(name) => Some((name.clone(), HeaderValue::from_str(&nonce)?)),
+      None => None,
+    };
+
+    let mac = hmac_sha256(&self.secret, &self.message(request, &timestamp, &nonce));
+    let signature = match self.encoding {
+      Encoding::Hex => hex(&mac),
+      Encoding::Base64 => base64(&mac),
+    };
+
+    let headers = request.headers_mut();
+    for (name, value) in &self.headers {
+      let _prev = headers.insert(name.clone(), value.clone());
+    }
+    // Timestamps and signatures are known to consist of valid
+    // characters only.
+    if let Some(name) = &self.timestamp_header {
+      let _prev = headers.insert(name.clone(), HeaderValue::try_from(timestamp).unwrap());
+    }
+    if let Some((name, value)) = nonce_header {
+      let _prev = headers.insert(name, value);
+    }
+    let _prev = headers.insert(
+      self.signature_header.clone(),
+      HeaderValue::try_from(signature).unwrap(),
+    );
+    Ok(())
+  }
+}
+
+impl Debug for HmacSigner {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("HmacSigner")
+      .field("signature_header", &self.signature_header)
+      .field("timestamp", &self.timestamp)
+      .field("encoding", &self.encoding)
+      .finish_non_exhaustive()
+  }
+}
+
+impl Auth for HmacSigner {
+  fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), AuthError> {
+    self
+      .sign_at(request, SystemTime::now())
+      .map_err(AuthError::new)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+
+  /// Check that we parse signature templates correctly.
+  #[test]
+  fn template_parsing() {
+    let segments = parse_template("{timestamp}:{method} {path_and_query}|").unwrap();
+    assert_eq!(
+      segments,
+      [
+        Segment::Timestamp,
+        Segment::Literal(":".to_string()),
+        Segment::Method,
+        Segment::Literal(" ".to_string()),
+        Segment::PathAndQuery,
+        Segment::Literal("|".to_string()),
+      ]
+    );
+
+    let err = parse_template("{timestamp}{verb}").unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "invalid signature template: unknown placeholder `{verb}`"
+    );
+    assert!(parse_template("{body").is_err());
+  }
+
+  /// Check that default nonces are strictly increasing.
+  #[test]
+  fn increasing_nonces() {
+    let signer = HmacSigner::new(b"", "{nonce}", HeaderName::from_static("x-sign")).unwrap();
+    let nonces = (0..100)
+      .map(|_| (signer.nonce)().parse::<u64>().unwrap())
+      .collect::<Vec<_>>();
+    assert!(nonces.windows(2).all(|pair| pair[0] < pair[1]));
+  }
+}
@@ -0,0 +1,451 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Signing of requests using AWS Signature Version 4, as understood by
+//! S3 and S3-compatible services such as MinIO, as well as most other
+//! AWS services.
+
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use http::header::InvalidHeaderValue;
+use http::header::AUTHORIZATION;
+use http::header::CONNECTION;
+use http::header::HOST;
+use http::header::PROXY_AUTHENTICATE;
+use http::header::PROXY_AUTHORIZATION;
+use http::header::TE;
+use http::header::TRAILER;
+use http::header::TRANSFER_ENCODING;
+use http::header::UPGRADE;
+use http::header::USER_AGENT;
+use http::uri::InvalidUri;
+use http::HeaderName;
+use http::HeaderValue;
+use http::Request;
+use http::Uri;
+
+use crate::crypto::hmac_sha256;
+use crate::crypto::sha256;
+use crate::encode::hex;
+use crate::encode::percent;
+use crate::encode::percent_decode;
+use crate::encode::DateTime;
+use crate::Auth;
+use crate::AuthError;
+use crate::Bytes;
+
+
+/// The signing algorithm identifier.
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+/// The payload hash used for requests with an unsigned body.
+pub const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+const X_AMZ_CONTENT_SHA256: HeaderName = HeaderName::from_static("x-amz-content-sha256");
+const X_AMZ_DATE: HeaderName = HeaderName::from_static("x-amz-date");
+const X_AMZ_SECURITY_TOKEN: HeaderName = HeaderName::from_static("x-amz-security-token");
+
+/// The maximum validity of a presigned URL, seven days.
+const MAX_EXPIRES: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Headers excluded from signatures, because proxies and transports
+/// may add, change, or remove them on the way to the service.
+const UNSIGNED_HEADERS: [HeaderName; 9] = [
+  AUTHORIZATION,
+  CONNECTION,
+  PROXY_AUTHENTICATE,
+  PROXY_AUTHORIZATION,
+  TE,
+  TRAILER,
+  TRANSFER_ENCODING,
+  UPGRADE,
+  USER_AGENT,
+];
+
+
+/// An error as reported when creating a presigned URL.
+#[derive(Debug)]
+pub enum PresignError {
+  /// The requested validity exceeds the maximum of seven days.
+  Expires(Duration),
+  /// The presigned URL is invalid.
+  Uri(InvalidUri),
+}
+
+impl Display for PresignError {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::Expires(expires) => write!(
+        fmt,
+        "validity of {}s exceeds maximum of {}s",
+        expires.as_secs(),
+        MAX_EXPIRES.as_secs()
+      ),
+      Self::Uri(err) => write!(fmt, "failed to create presigned URL: {}", err),
+    }
+  }
+}
+
+impl StdError for PresignError {
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Self::Expires(..) => None,
+      Self::Uri(err) => err.source(),
+    }
+  }
+}
+
+
+/// Format a point in time in the basic ISO 8601 format used by AWS,
+/// e.g., `20130524T000000Z`.
+fn amz_date(time: SystemTime) -> String {
+  let DateTime {
+    year,
+    month,
+    day,
+    hour,
+    minute,
+    second,
+    ..
+  } = DateTime::new(time);
+  format!("{year:04}{month:02}{day:02}T{hour:02}{minute:02}{second:02}Z")
+}
+
+/// Canonicalize a header value by trimming it and collapsing
+/// sequential spaces.
+fn canonical_value(value: &HeaderValue) -> String {
+  String::from_utf8_lossy(value.as_bytes())
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Canonicalize the query of a URI by sorting its parameters and
+/// normalizing their encoding.
+fn canonical_query(query: &str) -> String {
+  let mut params = query
+    .split('&')
+    .filter(|pair| !pair.is_empty())
+    .map(|pair| {
+      let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+      (
+        percent(percent_decode(name)),
+        percent(percent_decode(value)),
+      )
+    })
+    .collect::<Vec<_>>();
+  let () = params.sort();
+
+  params
+    .into_iter()
+    .map(|(name, value)| format!("{name}={value}"))
+    .collect::<Vec<_>>()
+    .join("&")
+}
+
+
+/// The pieces of a request that go into its signature.
+struct Canonical {
+  /// The names of the signed headers, separated by `;`.
+  signed_headers: String,
+  /// The canonical request.
+  request: String,
+}
+
+
+/// An [`Auth`] provider signing requests using AWS Signature Version
+/// 4.
+///
+/// The signature covers the request's method, path, query, headers,
+/// and, unless disabled via [`SigV4::with_unsigned_payload`], its body.
+/// Because it includes all headers present at the time of signing, the
+/// provider should be applied after all headers have been set. Excluded
+/// are hop-by-hop headers such as `Connection` as well as `User-Agent`,
+/// which transports and proxies may rewrite.
+///
+/// Signed requests carry the signing time. Requests should be signed
+/// right before they are sent, as services reject signatures that are
+/// more than a few minutes old.
+///
+/// A [`Client`][crate::Client] takes care of both: it applies
+/// authentication providers as the last step before handing a request
+/// to its transport, i.e., after setting all headers and waiting for
+/// the rate limiter.
+#[derive(Clone)]
+pub struct SigV4 {
+  access_key: String,
+  secret_key: String,
+  region: String,
+  service: String,
+  session_token: Option<HeaderValue>,
+  unsigned_payload: bool,
+}
+
+impl SigV4 {
+  /// Create a new `SigV4` provider for the given credentials, region
+  /// (e.g., `us-east-1`), and service (e.g., `s3`).
+  pub fn new(
+    access_key: &str,
+    secret_key: &str,
+    region: &str,
+    service: &str,
+  ) -> Result<Self, InvalidHeaderValue> {
+    // All of these end up in the `Authorization` header. Make sure that
+    // they form a valid header value.
+    let _value = HeaderValue::from_str(&format!("{access_key}/{region}/{service}"))?;
+
+    Ok(Self {
+      access_key: access_key.to_string(),
+      secret_key: secret_key.to_string(),
+      region: region.to_string(),
+      service: service.to_string(),
+      session_token: None,
+      unsigned_payload: false,
+    })
+  }
+
+  /// Set the session token to use, as required for temporary
+  /// credentials.
+  pub fn with_session_token(mut self, token: &str) -> Result<Self, InvalidHeaderValue> {
+    let mut value = HeaderValue::from_str(token)?;
+    value.set_sensitive(true);
+    self.session_token = Some(value);
+    Ok(self)
+  }
+
+  /// Set whether to exclude request bodies from the signature.
+  ///
+  /// Unsigned payloads are supported by S3 and allow for signing a
+  /// request before its body is known in its entirety, e.g., when it is
+  /// streamed by the transport.
+  pub fn with_unsigned_payload(mut self, unsigned: bool) -> Self {
+    self.unsigned_payload = unsigned;
+    self
+  }
+
+  /// The credential scope for signatures created on the given date.
+  fn scope(&self, date: &str) -> String {
+    format!(
+      "{date}/{region}/{service}/aws4_request",
+      region = self.region,
+      service = self.service,
+    )
+  }
+
+  /// Canonicalize the path of a URI.
+  fn canonical_path(&self, uri: &Uri) -> String {
+    let path = uri.path();
+    if path.is_empty() {
+      return "/".to_string();
+    }
+
+    path
+      .split('/')
+      .map(|segment| {
+        // S3 expects each segment to be encoded once, while all other
+        // services want the already encoded path to be encoded again.
+        if self.service == "s3" {
+          percent(percent_decode(segment))
+        } else {
+          percent(segment)
+        }
+      })
+      .collect::<Vec<_>>()
+      .join("/")
+  }
+
+  /// Create the canonical form of a request.
+  fn canonicalize(&self, request: &Request<Bytes>, query: &str, payload_hash: &str) -> Canonical {
+    let mut headers = request
+      .headers()
+      .iter()
+      .filter(|(name, _)| !UNSIGNED_HEADERS.contains(name))
+      .map(|(name, value)| (name.as_str(), canonical_value(value)))
+      .collect::<Vec<_>>();
+
+    if !request.headers().contains_key(HOST) {
+      if let Some(authority) = request.uri().authority() {
+        let () = headers.push((HOST.as_str(), authority.as_str().to_string()));
+      }
+    }
+    // Sort headers by name only, retaining the order of values of
+    // headers that are present multiple times.
+    let () = headers.sort_by_key(|(name, _)| *name);
+
+    let mut canonical_headers = String::new();
+    let mut signed_headers = Vec::<&str>::new();
+    for (name, value) in headers {
+      if signed_headers.last() == Some(&name) {
+        canonical_headers.pop();
+        canonical_headers.push(',');
+      } else {
+        let () = signed_headers.push(name);
+        canonical_headers.push_str(name);
+        canonical_headers.push(':');
+      }
+      canonical_headers.push_str(&value);
+      canonical_headers.push('\n');
+    }
+    let signed_headers = signed_headers.join(";");
+
+    let request = format!(
+      "{method}\n{path}\n{query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}",
+      method = request.method(),
+      path = self.canonical_path(request.uri()),
+    );
+
+    Canonical {
+      signed_headers,
+      request,
+    }
+  }
+
+  /// Calculate the signature of a canonical request.
+  fn signature(&self, canonical_request: &str, amz_date: &str) -> String {
+    let date = &amz_date[..8];
+    let string_to_sign = format!(
+      "{ALGORITHM}\n{amz_date}\n{scope}\n{hash}",
+      scope = self.scope(date),
+      hash = hex(&sha256(canonical_request.as_bytes())),
+    );
+
+    let key = format!("AWS4{}", self.secret_key);
+    let key = hmac_sha256(key.as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, self.region.as_bytes());
+    let key = hmac_sha256(&key, self.service.as_bytes());
+    let key = hmac_sha256(&key, b"aws4_request");
+    hex(&hmac_sha256(&key, string_to_sign.as_bytes()))
+  }
+
+  /// Sign a request as of the given point in time, setting the
+  /// `Authorization` header along with the required `X-Amz-*` headers.
+  pub fn sign_at(&self, request: &mut Request<Bytes>, time: SystemTime) {
+    let amz_date = amz_date(time);
+    let payload_hash = if self.unsigned_payload {
+      UNSIGNED_PAYLOAD.to_string()
+    } else {
+      hex(&sha256(request.body()))
+    };
+
+    let headers = request.headers_mut();
+    // The values are known to consist of valid characters only.
+    let _prev = headers.insert(X_AMZ_DATE, HeaderValue::try_from(&amz_date).unwrap());
+    let _prev = headers.insert(
+      X_AMZ_CONTENT_SHA256,
+      HeaderValue::try_from(&payload_hash).unwrap(),
+    );
+    if let Some(token) = &self.session_token {
+      let _prev = headers.insert(X_AMZ_SECURITY_TOKEN, token.clone());
+    }
+
+    let query = canonical_query(request.uri().query().unwrap_or(""));
+    let canonical = self.canonicalize(request, &query, &payload_hash);
+    let signature = self.signature(&canonical.request, &amz_date);
+    let authorization = format!(
+      "{ALGORITHM} Credential={access_key}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+      access_key = self.access_key,
+      scope = self.scope(&amz_date[..8]),
+      signed_headers = canonical.signed_headers,
+    );
+
+    // The access key, region, and service got validated on
+    // construction.
+    let mut value = HeaderValue::try_from(authorization).unwrap();
+    value.set_sensitive(true);
+    let _prev = request.headers_mut().insert(AUTHORIZATION, value);
+  }
+
+  /// Create a presigned URL for the given request, valid for `expires`
+  /// from now.
+  ///
+  /// Presigned URLs carry the signature in their query and can be
+  /// handed to third parties, which can then issue the request without
+  /// access to any credentials. Headers present on the request have to
+  /// be sent along unmodified. The body is not signed.
+  ///
+  /// `expires` may not exceed seven days, the maximum supported by AWS.
+  pub fn presign(&self, request: &Request<Bytes>, expires: Duration) -> Result<Uri, PresignError> {
+    self.presign_at(request, expires, SystemTime::now())
+  }
+
+  /// Create a presigned URL for the given request as of the provided
+  /// point in time, valid for `expires` from then.
+  pub fn presign_at(
+    &self,
+    request: &Request<Bytes>,
+    expires: Duration,
+    time: SystemTime,
+  ) -> Result<Uri, PresignError> {
+    if expires > MAX_EXPIRES {
+      return Err(PresignError::Expires(expires));
+    }
+
+    let amz_date = amz_date(time);
+    let credential = format!("{}/{}", self.access_key, self.scope(&amz_date[..8]));
+    // The signed headers are only known after canonicalization, which
+    // in turn needs the query. Luckily, they only depend on the request.
+    let signed_headers = self
+      .canonicalize(request, "", UNSIGNED_PAYLOAD)
+      .signed_headers;
+
+    let mut params = vec![
+      ("X-Amz-Algorithm", ALGORITHM.to_string()),
+      ("X-Amz-Credential", credential),
+      ("X-Amz-Date", amz_date.clone()),
+      ("X-Amz-Expires", expires.as_secs().to_string()),
+      ("X-Amz-SignedHeaders", signed_headers),
+    ];
+    if let Some(token) = &self.session_token {
+      let token = String::from_utf8_lossy(token.as_bytes()).into_owned();
+      let () = params.push(("X-Amz-Security-Token", token));
+    }
+
+    let mut query = request
+      .uri()
+      .query()
+      .filter(|query| !query.is_empty())
+      .map(|query| format!("{query}&"))
+      .unwrap_or_default();
+    query.push_str(
+      &params
+        .iter()
+        .map(|(name, value)| format!("{name}={}", percent(value)))
+        .collect::<Vec<_>>()
+        .join("&"),
+    );
+
+    let canonical = self.canonicalize(request, &canonical_query(&query), UNSIGNED_PAYLOAD);
+    let signature = self.signature(&canonical.request, &amz_date);
+
+    let mut uri = request.uri().to_string();
+    if let Some(pos) = uri.find('?') {
+      let () = uri.truncate(pos);
+    }
+    format!("{uri}?{query}&X-Amz-Signature={signature}")
+      .parse()
+      .map_err(PresignError::Uri)
+  }
+}
+
+impl Debug for SigV4 {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("SigV4")
+      .field("region", &self.region)
+      .field("service", &self.service)
+      .field("unsigned_payload", &self.unsigned_payload)
+      .finish_non_exhaustive()
+  }
+}
+
+impl Auth for SigV4 {
+  fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), AuthError> {
+    let () = self.sign_at(request, SystemTime::now());
+    Ok(())
+  }
+}
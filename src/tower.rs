@@ -0,0 +1,202 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Integration with the `tower` ecosystem.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+use http::Request;
+use http::Response;
+
+use pin_project_lite::pin_project;
+
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::request;
+use crate::Bytes;
+use crate::ClientError;
+use crate::Endpoint;
+use crate::Str;
+
+
+/// A [`Layer`] wrapping an HTTP service into an [`EndpointService`].
+pub struct EndpointLayer<E> {
+  base_url: Option<Str>,
+  _endpoint: PhantomData<fn() -> E>,
+}
+
+impl<E> EndpointLayer<E> {
+  /// Create a new layer for endpoint `E`, optionally using the given
+  /// base URL if the endpoint does not provide one.
+  pub fn new(base_url: Option<Str>) -> Self {
+    Self {
+      base_url,
+      _endpoint: PhantomData,
+    }
+  }
+}
+
+impl<E> Clone for EndpointLayer<E> {
+  fn clone(&self) -> Self {
+    Self::new(self.base_url.clone())
+  }
+}
+
+impl<E> Debug for EndpointLayer<E> {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("EndpointLayer")
+      .field("base_url", &self.base_url)
+      .finish()
+  }
+}
+
+impl<S, E> Layer<S> for EndpointLayer<E> {
+  type Service = EndpointService<S, E>;
+
+  fn layer(&self, service: S) -> Self::Service {
+    EndpointService {
+      service,
+      base_url: self.base_url.clone(),
+      _endpoint: PhantomData,
+    }
+  }
+}
+
+
+/// A [`Service`] issuing requests to endpoint `E` over an HTTP
+/// service.
+///
+/// The service accepts the endpoint's input, creates the request using
+/// [`request`], hands it to the wrapped HTTP service, and evaluates the
+/// response using [`Endpoint::evaluate`].
+pub struct EndpointService<S, E> {
+  service: S,
+  base_url: Option<Str>,
+  _endpoint: PhantomData<fn() -> E>,
+}
+
+impl<S, E> EndpointService<S, E> {
+  /// Create a new service for endpoint `E` wrapping the given HTTP
+  /// service.
+  pub fn new(service: S, base_url: Option<Str>) -> Self {
+    EndpointLayer::new(base_url).layer(service)
+  }
+
+  /// Retrieve a reference to the wrapped HTTP service.
+  pub fn get_ref(&self) -> &S {
+    &self.service
+  }
+
+  /// Unwrap the wrapped HTTP service.
+  pub fn into_inner(self) -> S {
+    self.service
+  }
+}
+
+impl<S, E> Clone for EndpointService<S, E>
+where
+  S: Clone,
+{
+  fn clone(&self) -> Self {
+    Self {
+      service: self.service.clone(),
+      base_url: self.base_url.clone(),
+      _endpoint: PhantomData,
+    }
+  }
+}
+
+impl<S, E> Debug for EndpointService<S, E>
+where
+  S: Debug,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("EndpointService")
+      .field("service", &self.service)
+      .field("base_url", &self.base_url)
+      .finish()
+  }
+}
+
+impl<S, E, B> Service<E::Input> for EndpointService<S, E>
+where
+  S: Service<Request<Bytes>, Response = Response<B>>,
+  E: Endpoint,
+  B: AsRef<[u8]>,
+{
+  type Response = E::Output;
+  type Error = ClientError<E::Error, S::Error>;
+  type Future = ResponseFuture<S::Future, E>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self.service.poll_ready(cx).map_err(ClientError::Transport)
+  }
+
+  fn call(&mut self, input: E::Input) -> Self::Future {
+    match request::<E>(self.base_url.as_deref(), &input) {
+      Ok(request) => ResponseFuture::Pending {
+        future: self.service.call(request),
+        _endpoint: PhantomData,
+      },
+      Err(err) => ResponseFuture::Failed { error: Some(err) },
+    }
+  }
+}
+
+
+pin_project! {
+  /// The future returned by [`EndpointService`].
+  #[project = ResponseFutureProj]
+  pub enum ResponseFuture<F, E>
+  where
+    E: Endpoint,
+  {
+    /// The request has been sent and we wait for the response.
+    Pending {
+      #[pin]
+      future: F,
+      _endpoint: PhantomData<fn() -> E>,
+    },
+    /// The request could not be created.
+    Failed {
+      error: Option<E::Error>,
+    },
+  }
+}
+
+impl<F, E, B, T> Future for ResponseFuture<F, E>
+where
+  F: Future<Output = Result<Response<B>, T>>,
+  E: Endpoint,
+  B: AsRef<[u8]>,
+{
+  type Output = Result<E::Output, ClientError<E::Error, T>>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    match self.project() {
+      ResponseFutureProj::Pending { future, .. } => {
+        let response = match future.poll(cx) {
+          Poll::Ready(Ok(response)) => response,
+          Poll::Ready(Err(err)) => return Poll::Ready(Err(ClientError::Transport(err))),
+          Poll::Pending => return Poll::Pending,
+        };
+        let (parts, body) = response.into_parts();
+        Poll::Ready(E::evaluate(parts.status, body.as_ref()).map_err(ClientError::Endpoint))
+      },
+      ResponseFutureProj::Failed { error } => {
+        let error = error.take().expect("future polled after completion");
+        Poll::Ready(Err(ClientError::Endpoint(error)))
+      },
+    }
+  }
+}
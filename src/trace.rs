@@ -0,0 +1,137 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Tracing instrumentation of request creation and response evaluation.
+//!
+//! Without the `tracing` feature enabled all functionality in here
+//! boils down to no-ops.
+
+use std::future::Future;
+
+use http::Request;
+use http::StatusCode;
+
+use crate::Bytes;
+use crate::Endpoint;
+
+#[cfg(feature = "tracing")]
+mod imp {
+  use std::fmt::Debug;
+  use std::fmt::Formatter;
+  use std::fmt::Result as FmtResult;
+
+  use http::HeaderMap;
+
+  use tracing::debug;
+  use tracing::debug_span;
+  use tracing::Span;
+
+  use super::*;
+
+  use crate::request::is_sensitive;
+
+
+  /// A wrapper around a `HeaderMap` redacting sensitive values when
+  /// formatted.
+  pub(crate) struct Redacted<'h>(pub(crate) &'h HeaderMap);
+
+  impl Debug for Redacted<'_> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+      let mut map = fmt.debug_map();
+      for (name, value) in self.0 {
+        if is_sensitive(name, value) {
+          map.entry(&name.as_str(), &"<redacted>");
+        } else {
+          map.entry(&name.as_str(), value);
+        }
+      }
+      map.finish()
+    }
+  }
+
+
+  /// Create a span covering the issuing of a request to endpoint `E`.
+  ///
+  /// Here and in the events below the `path` field is only recorded
+  /// for endpoints providing a path template.
+  pub(super) fn span<E>() -> Span
+  where
+    E: Endpoint,
+  {
+    debug_span!(
+      "endpoint",
+      endpoint = E::NAME,
+      method = %E::method(),
+      path = E::path_template(),
+    )
+  }
+
+  pub(super) fn created<E>(request: &Request<Bytes>)
+  where
+    E: Endpoint,
+  {
+    debug!(
+      endpoint = E::NAME,
+      method = %request.method(),
+      path = E::path_template(),
+      body_size = request.body().len(),
+      headers = ?Redacted(request.headers()),
+      "created request",
+    );
+  }
+
+  pub(super) fn evaluated<E>(status: StatusCode, body: &[u8], result: &Result<E::Output, E::Error>)
+  where
+    E: Endpoint,
+  {
+    let error = result.as_ref().err().map(E::error_variant);
+    debug!(
+      endpoint = E::NAME,
+      method = %E::method(),
+      path = E::path_template(),
+      status = status.as_u16(),
+      body_size = body.len(),
+      error,
+      "evaluated response",
+    );
+  }
+}
+
+
+/// Instrument the future issuing a request to endpoint `E`.
+#[allow(unused)]
+pub(crate) fn instrument<E, F>(future: F) -> impl Future<Output = F::Output>
+where
+  E: Endpoint,
+  F: Future,
+{
+  #[cfg(feature = "tracing")]
+  {
+    use tracing::Instrument as _;
+    future.instrument(imp::span::<E>())
+  }
+  #[cfg(not(feature = "tracing"))]
+  {
+    future
+  }
+}
+
+/// Trace the creation of a request for endpoint `E`.
+#[allow(unused)]
+pub(crate) fn created<E>(request: &Request<Bytes>)
+where
+  E: Endpoint,
+{
+  #[cfg(feature = "tracing")]
+  imp::created::<E>(request)
+}
+
+/// Trace the evaluation of a response by endpoint `E`.
+#[allow(unused)]
+pub fn evaluated<E>(status: StatusCode, body: &[u8], result: &Result<E::Output, E::Error>)
+where
+  E: Endpoint,
+{
+  #[cfg(feature = "tracing")]
+  imp::evaluated::<E>(status, body, result)
+}
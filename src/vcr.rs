@@ -0,0 +1,548 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Recording and replaying of HTTP interactions.
+//!
+//! A [`Recorder`] wraps a [`Transport`] and captures each request it
+//! sends along with the received response in a [`Cassette`]. Cassettes
+//! can be saved to and loaded from JSON or YAML files. A [`Replayer`]
+//! is a [`Transport`] serving responses from a cassette, without
+//! issuing any actual requests. Requests are matched against recorded
+//! ones as configured by a [`Matcher`].
+
+use std::error::Error as StdError;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::fs::read_to_string;
+use std::fs::write;
+use std::io::Error as IoError;
+use std::path::Path;
+use std::sync::Mutex;
+
+use http::HeaderMap;
+use http::HeaderName;
+use http::Request;
+use http::Response;
+use http::Uri;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use serde_json::Error as JsonError;
+
+use serde_yaml::Error as YamlError;
+
+use crate::request::is_sensitive;
+use crate::BoxFuture;
+use crate::Bytes;
+use crate::Transport;
+
+
+/// An error as reported by functionality in this module.
+#[derive(Debug)]
+pub enum Error {
+  /// An I/O error.
+  Io(IoError),
+  /// A cassette could not be (de)serialized as JSON.
+  Json(JsonError),
+  /// A cassette could not be (de)serialized as YAML.
+  Yaml(YamlError),
+  /// A recorded interaction is invalid.
+  Invalid(String),
+  /// No recorded interaction matched a request.
+  Unmatched {
+    /// The method of the request.
+    method: String,
+    /// The URI of the request.
+    uri: String,
+  },
+}
+
+impl Display for Error {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Error::Io(err) => write!(fmt, "{}", err),
+      Error::Json(err) => write!(fmt, "failed to (de)serialize JSON cassette: {}", err),
+      Error::Yaml(err) => write!(fmt, "failed to (de)serialize YAML cassette: {}", err),
+      Error::Invalid(err) => write!(fmt, "invalid interaction: {}", err),
+      Error::Unmatched { method, uri } => {
+        write!(fmt, "no recorded interaction matches {} {}", method, uri)
+      },
+    }
+  }
+}
+
+impl StdError for Error {
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Error::Io(err) => err.source(),
+      Error::Json(err) => err.source(),
+      Error::Yaml(err) => err.source(),
+      Error::Invalid(..) | Error::Unmatched { .. } => None,
+    }
+  }
+}
+
+impl From<IoError> for Error {
+  fn from(e: IoError) -> Self {
+    Error::Io(e)
+  }
+}
+
+impl From<JsonError> for Error {
+  fn from(e: JsonError) -> Self {
+    Error::Json(e)
+  }
+}
+
+impl From<YamlError> for Error {
+  fn from(e: YamlError) -> Self {
+    Error::Yaml(e)
+  }
+}
+
+
+/// A recorded HTTP header.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Header {
+  /// The header's name.
+  pub name: String,
+  /// The header's value.
+  pub value: String,
+}
+
+
+/// A recorded request or response body.
+///
+/// Bodies that are valid UTF-8 are recorded as text, all others as a
+/// sequence of bytes.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Body {
+  /// A textual body.
+  Text(String),
+  /// A binary body.
+  Binary(Vec<u8>),
+}
+
+impl Body {
+  fn new(bytes: &[u8]) -> Self {
+    match String::from_utf8(bytes.to_vec()) {
+      Ok(text) => Body::Text(text),
+      Err(err) => Body::Binary(err.into_bytes()),
+    }
+  }
+
+  /// Retrieve the body's bytes.
+  pub fn as_bytes(&self) -> &[u8] {
+    match self {
+      Body::Text(text) => text.as_bytes(),
+      Body::Binary(bytes) => bytes,
+    }
+  }
+}
+
+impl Default for Body {
+  fn default() -> Self {
+    Body::Text(String::new())
+  }
+}
+
+
+/// A recorded request.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RecordedRequest {
+  /// The request's method.
+  pub method: String,
+  /// The request's URI.
+  pub uri: String,
+  /// The request's headers.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub headers: Vec<Header>,
+  /// The request's body.
+  #[serde(default)]
+  pub body: Body,
+}
+
+
+/// A recorded response.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RecordedResponse {
+  /// The response's status code.
+  pub status: u16,
+  /// The response's headers.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub headers: Vec<Header>,
+  /// The response's body.
+  #[serde(default)]
+  pub body: Body,
+}
+
+
+/// A recorded request along with the response received for it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Interaction {
+  /// The request.
+  pub request: RecordedRequest,
+  /// The response.
+  pub response: RecordedResponse,
+}
+
+
+/// A collection of recorded interactions.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Cassette {
+  /// The recorded interactions, in the order they happened.
+  pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+  /// Create a new, empty cassette.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Load a cassette from a file.
+  ///
+  /// Files with a `.yaml` or `.yml` extension are parsed as YAML, all
+  /// others as JSON.
+  pub fn load<P>(path: P) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref();
+    let data = read_to_string(path)?;
+    let cassette = if is_yaml(path) {
+      serde_yaml::from_str(&data)?
+    } else {
+      serde_json::from_str(&data)?
+    };
+    Ok(cassette)
+  }
+
+  /// Save the cassette to a file, using the same format selection
+  /// rules as [`Cassette::load`].
+  pub fn save<P>(&self, path: P) -> Result<(), Error>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref();
+    let data = if is_yaml(path) {
+      serde_yaml::to_string(self)?
+    } else {
+      let mut data = serde_json::to_string_pretty(self)?;
+      data.push('\n');
+      data
+    };
+    write(path, data)?;
+    Ok(())
+  }
+}
+
+fn is_yaml(path: &Path) -> bool {
+  matches!(
+    path.extension().and_then(|ext| ext.to_str()),
+    Some("yaml") | Some("yml")
+  )
+}
+
+
+fn record_headers(headers: &HeaderMap, redact: bool) -> Vec<Header> {
+  headers
+    .iter()
+    .map(|(name, value)| Header {
+      name: name.as_str().to_string(),
+      value: if redact && is_sensitive(name, value) {
+        "<redacted>".to_string()
+      } else {
+        String::from_utf8_lossy(value.as_bytes()).into_owned()
+      },
+    })
+    .collect()
+}
+
+
+/// A [`Transport`] recording all interactions in a [`Cassette`].
+///
+/// By default, the values of authorization headers, cookies, and other
+/// sensitive headers are redacted in recorded interactions.
+#[derive(Debug)]
+pub struct Recorder<T> {
+  transport: T,
+  cassette: Mutex<Cassette>,
+  redact: bool,
+}
+
+impl<T> Recorder<T> {
+  /// Create a new `Recorder` recording into an empty cassette.
+  pub fn new(transport: T) -> Self {
+    Self::with_cassette(transport, Cassette::new())
+  }
+
+  /// Create a new `Recorder` appending to the provided cassette.
+  pub fn with_cassette(transport: T, cassette: Cassette) -> Self {
+    Self {
+      transport,
+      cassette: Mutex::new(cassette),
+      redact: true,
+    }
+  }
+
+  /// Set whether to redact sensitive header values.
+  pub fn with_redaction(mut self, redact: bool) -> Self {
+    self.redact = redact;
+    self
+  }
+
+  /// Retrieve a copy of the cassette as recorded so far.
+  pub fn cassette(&self) -> Cassette {
+    self.cassette.lock().unwrap().clone()
+  }
+
+  /// Destroy the `Recorder`, returning the recorded cassette.
+  pub fn into_cassette(self) -> Cassette {
+    self.cassette.into_inner().unwrap()
+  }
+
+  /// Save the cassette as recorded so far to a file.
+  ///
+  /// Please refer to [`Cassette::save`] for details.
+  pub fn save<P>(&self, path: P) -> Result<(), Error>
+  where
+    P: AsRef<Path>,
+  {
+    self.cassette.lock().unwrap().save(path)
+  }
+}
+
+impl<T> Transport for Recorder<T>
+where
+  T: Transport + Sync,
+{
+  type Error = T::Error;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let recorded = RecordedRequest {
+      method: request.method().to_string(),
+      uri: request.uri().to_string(),
+      headers: record_headers(request.headers(), self.redact),
+      body: Body::new(request.body()),
+    };
+
+    Box::pin(async move {
+      let response = self.transport.send(request).await?;
+      let interaction = Interaction {
+        request: recorded,
+        response: RecordedResponse {
+          status: response.status().as_u16(),
+          headers: record_headers(response.headers(), self.redact),
+          body: Body::new(response.body()),
+        },
+      };
+      self.cassette.lock().unwrap().interactions.push(interaction);
+      Ok(response)
+    })
+  }
+}
+
+
+/// The criteria used for matching requests against recorded ones.
+///
+/// By default, the method, path, query, and body are compared, but no
+/// headers are. Query parameters are compared irrespective of their
+/// order.
+#[derive(Clone, Debug)]
+pub struct Matcher {
+  method: bool,
+  path: bool,
+  query: bool,
+  headers: Vec<HeaderName>,
+  body: bool,
+}
+
+impl Matcher {
+  /// Create a new `Matcher` with the default criteria.
+  pub fn new() -> Self {
+    Self {
+      method: true,
+      path: true,
+      query: true,
+      headers: Vec::new(),
+      body: true,
+    }
+  }
+
+  /// Set whether to compare methods.
+  pub fn with_method(mut self, method: bool) -> Self {
+    self.method = method;
+    self
+  }
+
+  /// Set whether to compare the scheme, authority, and path of URIs.
+  pub fn with_path(mut self, path: bool) -> Self {
+    self.path = path;
+    self
+  }
+
+  /// Set whether to compare queries.
+  pub fn with_query(mut self, query: bool) -> Self {
+    self.query = query;
+    self
+  }
+
+  /// Additionally compare the values of the header with the given
+  /// name.
+  pub fn with_header(mut self, name: HeaderName) -> Self {
+    self.headers.push(name);
+    self
+  }
+
+  /// Set whether to compare bodies.
+  pub fn with_body(mut self, body: bool) -> Self {
+    self.body = body;
+    self
+  }
+
+  /// Check whether the given request matches a recorded one.
+  fn matches(&self, request: &Request<Bytes>, recorded: &RecordedRequest) -> bool {
+    if self.method && request.method().as_str() != recorded.method {
+      return false;
+    }
+
+    if self.path || self.query {
+      let uri = match recorded.uri.parse::<Uri>() {
+        Ok(uri) => uri,
+        Err(..) => return false,
+      };
+
+      if self.path
+        && (request.uri().scheme() != uri.scheme()
+          || request.uri().authority() != uri.authority()
+          || request.uri().path() != uri.path())
+      {
+        return false;
+      }
+
+      if self.query && query_pairs(request.uri()) != query_pairs(&uri) {
+        return false;
+      }
+    }
+
+    for name in &self.headers {
+      let values = request
+        .headers()
+        .get_all(name)
+        .iter()
+        .map(|value| String::from_utf8_lossy(value.as_bytes()))
+        .collect::<Vec<_>>();
+      let recorded = recorded
+        .headers
+        .iter()
+        .filter(|header| header.name.eq_ignore_ascii_case(name.as_str()))
+        .map(|header| header.value.as_str())
+        .collect::<Vec<_>>();
+      if values != recorded {
+        return false;
+      }
+    }
+
+    !self.body || request.body().as_ref() == recorded.body.as_bytes()
+  }
+}
+
+impl Default for Matcher {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+fn query_pairs(uri: &Uri) -> Vec<&str> {
+  let mut pairs = uri
+    .query()
+    .unwrap_or("")
+    .split('&')
+    .filter(|pair| !pair.is_empty())
+    .collect::<Vec<_>>();
+  pairs.sort_unstable();
+  pairs
+}
+
+
+/// A [`Transport`] serving responses from a [`Cassette`].
+///
+/// Each recorded interaction is served at most once, in the order
+/// recorded. A request not matching any unused interaction results in
+/// an [`Error::Unmatched`] error.
+#[derive(Debug)]
+pub struct Replayer {
+  cassette: Cassette,
+  matcher: Matcher,
+  used: Mutex<Vec<bool>>,
+}
+
+impl Replayer {
+  /// Create a new `Replayer` for the given cassette, matching requests
+  /// using the default [`Matcher`].
+  pub fn new(cassette: Cassette) -> Self {
+    let used = vec![false; cassette.interactions.len()];
+    Self {
+      cassette,
+      matcher: Matcher::new(),
+      used: Mutex::new(used),
+    }
+  }
+
+  /// Create a new `Replayer` for the cassette stored in the given
+  /// file.
+  ///
+  /// Please refer to [`Cassette::load`] for details.
+  pub fn load<P>(path: P) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    Ok(Self::new(Cassette::load(path)?))
+  }
+
+  /// Set the matcher to use for matching requests against recorded
+  /// ones.
+  pub fn with_matcher(mut self, matcher: Matcher) -> Self {
+    self.matcher = matcher;
+    self
+  }
+
+  fn replay(&self, request: &Request<Bytes>) -> Result<Response<Vec<u8>>, Error> {
+    let mut used = self.used.lock().unwrap();
+    let index = self
+      .cassette
+      .interactions
+      .iter()
+      .enumerate()
+      .position(|(index, interaction)| {
+        !used[index] && self.matcher.matches(request, &interaction.request)
+      })
+      .ok_or_else(|| Error::Unmatched {
+        method: request.method().to_string(),
+        uri: request.uri().to_string(),
+      })?;
+    used[index] = true;
+
+    let recorded = &self.cassette.interactions[index].response;
+    let mut response = Response::builder().status(recorded.status);
+    for header in &recorded.headers {
+      response = response.header(&header.name, &header.value);
+    }
+    response
+      .body(recorded.body.as_bytes().to_vec())
+      .map_err(|err| Error::Invalid(err.to_string()))
+  }
+}
+
+impl Transport for Replayer {
+  type Error = Error;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let result = self.replay(&request);
+    Box::pin(async move { result })
+  }
+}
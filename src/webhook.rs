@@ -0,0 +1,272 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Verification of incoming webhook requests.
+//!
+//! Webhooks are the inverse of endpoints: instead of issuing a request
+//! and evaluating the response, we receive a request and have to
+//! validate it before acting on its payload. Webhooks are best defined
+//! using the [`WebhookDef`][crate::WebhookDef] macro.
+
+use std::error::Error as StdError;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use http::HeaderName;
+use http::Method;
+use http::Request;
+use http::StatusCode;
+
+use crate::crypto::constant_time_eq;
+use crate::crypto::hmac_sha256;
+use crate::encode::base64;
+use crate::encode::hex;
+use crate::signer::Encoding;
+use crate::Str;
+
+
+/// An error indicating why a webhook request got rejected.
+#[derive(Debug)]
+pub enum Error<C> {
+  /// The request used an unexpected method.
+  Method(Method),
+  /// The request was sent to an unexpected path.
+  Path(String),
+  /// A required header is missing or malformed.
+  Header(HeaderName),
+  /// The request's timestamp is outside of the accepted tolerance.
+  Timestamp(u64),
+  /// The request's signature does not match.
+  Signature,
+  /// The payload could not be parsed.
+  Conversion(C),
+}
+
+impl<C> Error<C> {
+  /// Retrieve the HTTP status to reject the request with.
+  pub fn status(&self) -> StatusCode {
+    match self {
+      Self::Method(..) => StatusCode::METHOD_NOT_ALLOWED,
+      Self::Path(..) => StatusCode::NOT_FOUND,
+      Self::Header(..) | Self::Timestamp(..) | Self::Signature => StatusCode::UNAUTHORIZED,
+      Self::Conversion(..) => StatusCode::BAD_REQUEST,
+    }
+  }
+}
+
+impl<C> Display for Error<C>
+where
+  C: Display,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::Method(method) => write!(fmt, "unexpected request method {}", method),
+      Self::Path(path) => write!(fmt, "unexpected request path {}", path),
+      Self::Header(name) => write!(fmt, "header {} is missing or invalid", name),
+      Self::Timestamp(timestamp) => {
+        write!(
+          fmt,
+          "timestamp {} is outside of accepted tolerance",
+          timestamp
+        )
+      },
+      Self::Signature => fmt.write_str("signature mismatch"),
+      Self::Conversion(err) => write!(fmt, "{}", err),
+    }
+  }
+}
+
+impl<C> StdError for Error<C>
+where
+  C: StdError,
+{
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Self::Method(..)
+      | Self::Path(..)
+      | Self::Header(..)
+      | Self::Timestamp(..)
+      | Self::Signature => None,
+      Self::Conversion(err) => err.source(),
+    }
+  }
+}
+
+
+/// A trait describing an incoming webhook.
+///
+/// A webhook is a request with a given method and path, carrying a
+/// payload in its body along with an HMAC-SHA256 signature over (by
+/// default) the body in a header. Optionally, a timestamp header is
+/// included in the signature, guarding against replay of old requests.
+pub trait Webhook {
+  /// The type of the payload conveyed by the webhook.
+  type Payload;
+  /// An error emitted when converting the body into the payload.
+  type ConversionError: StdError;
+
+  /// The name of the webhook.
+  ///
+  /// When defined using [`WebhookDef`][crate::WebhookDef], this is the
+  /// identifier the webhook was defined with, qualified with the path
+  /// of the module it was defined in, e.g., `hooks::github::Push`.
+  const NAME: &'static str;
+
+  /// Retrieve the HTTP method of webhook requests.
+  ///
+  /// The default method is POST.
+  fn method() -> Method {
+    Method::POST
+  }
+
+  /// Retrieve the path webhook requests are sent to.
+  fn path() -> Str;
+
+  /// Retrieve the name of the header carrying the signature.
+  fn signature_header() -> HeaderName;
+
+  /// Retrieve the prefix preceding the signature in its header, if
+  /// any, such as `sha256=`.
+  fn signature_prefix() -> &'static str {
+    ""
+  }
+
+  /// Retrieve the encoding of the signature.
+  ///
+  /// Hex encoded signatures are compared case-insensitively.
+  fn signature_encoding() -> Encoding {
+    Encoding::Hex
+  }
+
+  /// Retrieve the name of the header carrying the request's timestamp,
+  /// in seconds since the Unix epoch.
+  ///
+  /// By default no timestamp is expected.
+  fn timestamp_header() -> Option<HeaderName> {
+    None
+  }
+
+  /// Retrieve the maximum accepted difference between a request's
+  /// timestamp and the current time.
+  fn tolerance() -> Duration {
+    Duration::from_secs(300)
+  }
+
+  /// Create the message the signature was calculated over.
+  ///
+  /// By default, this is the body, preceded by the timestamp and a
+  /// `.` if a timestamp header is in use.
+  fn signed_message(timestamp: Option<&str>, body: &[u8]) -> Vec<u8> {
+    match timestamp {
+      Some(timestamp) => [timestamp.as_bytes(), b".", body].concat(),
+      None => body.to_vec(),
+    }
+  }
+
+  /// Parse the body into the payload.
+  fn parse(body: &[u8]) -> Result<Self::Payload, Self::ConversionError>;
+
+  /// Verify an incoming request using `secret`, parsing its payload.
+  fn verify<B>(
+    request: &Request<B>,
+    secret: &[u8],
+  ) -> Result<Self::Payload, Error<Self::ConversionError>>
+  where
+    B: AsRef<[u8]>,
+  {
+    Self::verify_at(request, secret, SystemTime::now())
+  }
+
+  /// Verify an incoming request using `secret` as of the given point in
+  /// time, parsing its payload.
+  fn verify_at<B>(
+    request: &Request<B>,
+    secret: &[u8],
+    now: SystemTime,
+  ) -> Result<Self::Payload, Error<Self::ConversionError>>
+  where
+    B: AsRef<[u8]>,
+  {
+    if request.method() != Self::method() {
+      return Err(Error::Method(request.method().clone()));
+    }
+    if request.uri().path() != Self::path() {
+      return Err(Error::Path(request.uri().path().to_string()));
+    }
+
+    let header = |name: HeaderName| {
+      request
+        .headers()
+        .get(&name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(Error::Header(name))
+    };
+
+    let timestamp = Self::timestamp_header().map(header).transpose()?;
+    let body = request.body().as_ref();
+    let name = Self::signature_header();
+    let signature = header(name.clone())?
+      .trim()
+      .strip_prefix(Self::signature_prefix())
+      .ok_or(Error::Header(name))?;
+
+    let mac = hmac_sha256(secret, &Self::signed_message(timestamp, body));
+    let valid = match Self::signature_encoding() {
+      Encoding::Hex => constant_time_eq(
+        hex(&mac).as_bytes(),
+        signature.to_ascii_lowercase().as_bytes(),
+      ),
+      Encoding::Base64 => constant_time_eq(base64(&mac).as_bytes(), signature.as_bytes()),
+    };
+    if !valid {
+      return Err(Error::Signature);
+    }
+
+    // Only check the timestamp once we know it is authentic.
+    if let Some(timestamp) = timestamp {
+      let name = Self::timestamp_header().unwrap();
+      let timestamp = timestamp
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| Error::Header(name))?;
+      let now = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+      if now.abs_diff(timestamp) > Self::tolerance().as_secs() {
+        return Err(Error::Timestamp(timestamp));
+      }
+    }
+
+    Self::parse(body).map_err(Error::Conversion)
+  }
+}
+
+
+/// A macro used for defining an incoming webhook.
+///
+/// The macro defines a type implementing [`Webhook`] for the given
+/// payload type. All other properties are provided by means of the
+/// trait's methods.
+#[macro_export]
+macro_rules! WebhookDef {
+  ( $(#[$docs:meta])* $pub:vis $name:ident($payload:ty),
+    ConversionErr => $conv_err:ty,
+    $($defs:tt)* ) => {
+
+    $(#[$docs])*
+    #[derive(Clone, Copy, Debug)]
+    $pub struct $name;
+
+    #[allow(unused_qualifications)]
+    impl ::http_endpoint::webhook::Webhook for $name {
+      type Payload = $payload;
+      type ConversionError = $conv_err;
+
+      const NAME: &'static str = concat!(module_path!(), "::", stringify!($name));
+
+      $($defs)*
+    }
+  };
+}
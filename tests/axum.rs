@@ -0,0 +1,247 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::str::Utf8Error;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use axum::body::to_bytes;
+use axum::body::Body;
+use axum::Router;
+
+use http::Method;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use http_endpoint::axum::RouterExt as _;
+use http_endpoint::server::Incoming;
+use http_endpoint::server::Serve;
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::Client;
+use http_endpoint::ClientError;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+use http_endpoint::Transport;
+
+use test_log::test;
+
+use thiserror::Error;
+
+use tower::ServiceExt as _;
+
+
+#[derive(Debug, Error)]
+enum ConversionError {
+  #[error("invalid key")]
+  Key,
+  #[error(transparent)]
+  Utf8(#[from] Utf8Error),
+}
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("{0}")]
+struct ApiError(String);
+
+
+EndpointDef! {
+  GetValue(String),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetValueError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => ConversionError,
+  ApiErr => ApiError,
+
+  fn path(key: &Self::Input) -> Str {
+    format!("/values/{key}").into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/values/{key}")
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(std::str::from_utf8(body)?.to_string())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(ApiError(String::from_utf8_lossy(body).into_owned()))
+  }
+}
+
+impl Serve for GetValue {
+  fn decode(request: &Incoming<'_>) -> Result<Self::Input, Self::ConversionError> {
+    request
+      .param("key")
+      .map(str::to_string)
+      .ok_or(ConversionError::Key)
+  }
+
+  fn encode(output: &Self::Output) -> Result<Vec<u8>, Self::ConversionError> {
+    Ok(output.clone().into_bytes())
+  }
+
+  fn encode_err(error: &Self::ApiError) -> Vec<u8> {
+    error.0.clone().into_bytes()
+  }
+}
+
+
+EndpointDef! {
+  PutValue((String, String)),
+  Ok => (), [
+    /* 204 */ NO_CONTENT,
+  ],
+  Err => PutValueError, [
+    /* 409 */ CONFLICT => Conflict,
+  ],
+  ConversionErr => ConversionError,
+  ApiErr => ApiError,
+
+  fn method() -> Method {
+    Method::PUT
+  }
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/values/{}", input.0).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/values/{key}")
+  }
+
+  fn body(input: &Self::Input) -> Result<Option<Bytes>, Self::ConversionError> {
+    Ok(Some(Bytes::Owned(input.1.clone().into_bytes())))
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(ApiError(String::from_utf8_lossy(body).into_owned()))
+  }
+}
+
+impl Serve for PutValue {
+  fn decode(request: &Incoming<'_>) -> Result<Self::Input, Self::ConversionError> {
+    let key = request.param("key").ok_or(ConversionError::Key)?;
+    let value = std::str::from_utf8(request.body())?;
+    Ok((key.to_string(), value.to_string()))
+  }
+
+  fn encode(_: &Self::Output) -> Result<Vec<u8>, Self::ConversionError> {
+    Ok(Vec::new())
+  }
+
+  fn encode_err(error: &Self::ApiError) -> Vec<u8> {
+    error.0.clone().into_bytes()
+  }
+}
+
+
+/// A transport dispatching requests to a router in-process.
+struct Local(Router);
+
+impl Transport for Local {
+  type Error = Infallible;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let request = request.map(|body| Body::from(body.into_owned()));
+    let router = self.0.clone();
+
+    Box::pin(async move {
+      let response = router.oneshot(request).await?;
+      let (parts, body) = response.into_parts();
+      let body = to_bytes(body, usize::MAX).await.unwrap();
+      Ok(Response::from_parts(parts, body.to_vec()))
+    })
+  }
+}
+
+
+/// Create a router serving a key-value store.
+fn router() -> Router {
+  let store = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+  let get_store = store.clone();
+
+  Router::new()
+    .endpoint::<GetValue, _, _>(move |key| {
+      let value = get_store.lock().unwrap().get(&key).cloned();
+      async move {
+        value.ok_or_else(|| GetValueError::NotFound(Ok(ApiError(format!("{key} not found")))))
+      }
+    })
+    .endpoint::<PutValue, _, _>(move |(key, value)| {
+      let result = match store.lock().unwrap().entry(key) {
+        Entry::Occupied(entry) => Err(PutValueError::Conflict(Ok(ApiError(format!(
+          "{} already exists",
+          entry.key()
+        ))))),
+        Entry::Vacant(entry) => {
+          let _value = entry.insert(value);
+          Ok(())
+        },
+      };
+      async move { result }
+    })
+}
+
+
+/// Check that handlers mounted for endpoints serve requests issued
+/// using the same definitions.
+#[test(tokio::test)]
+async fn serve_endpoints() {
+  let client = Client::new(Local(router()));
+
+  let err = client
+    .issue::<GetValue>(&"foo".to_string())
+    .await
+    .unwrap_err();
+  match err {
+    ClientError::Endpoint(GetValueError::NotFound(Ok(err))) => assert_eq!(err.0, "foo not found"),
+    _ => panic!("unexpected error: {err:?}"),
+  }
+
+  let input = ("foo".to_string(), "bar".to_string());
+  let () = client.issue::<PutValue>(&input).await.unwrap();
+  let value = client.issue::<GetValue>(&"foo".to_string()).await.unwrap();
+  assert_eq!(value, "bar");
+
+  let err = client.issue::<PutValue>(&input).await.unwrap_err();
+  assert!(
+    matches!(err, ClientError::Endpoint(PutValueError::Conflict(..))),
+    "{err:?}"
+  );
+}
+
+/// Check that requests not matching any endpoint or not decodable are
+/// rejected.
+#[test(tokio::test)]
+async fn reject_requests() {
+  let router = router();
+
+  let request = Request::delete("/values/foo").body(Body::empty()).unwrap();
+  let response = router.clone().oneshot(request).await.unwrap();
+  assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+  let request = Request::get("/keys").body(Body::empty()).unwrap();
+  let response = router.clone().oneshot(request).await.unwrap();
+  assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+  let request = Request::put("/values/foo")
+    .body(Body::from(vec![0xff, 0xfe]))
+    .unwrap();
+  let response = router.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+  assert!(body.starts_with(b"invalid utf-8"), "{body:?}");
+}
@@ -0,0 +1,497 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+use std::env::temp_dir;
+use std::fs::remove_dir_all;
+use std::process::id;
+use std::str::from_utf8;
+use std::str::Utf8Error;
+use std::sync::Mutex;
+
+use http::header::ACCEPT_LANGUAGE;
+use http::header::AGE;
+use http::header::CACHE_CONTROL;
+use http::header::ETAG;
+use http::header::IF_NONE_MATCH;
+use http::header::LAST_MODIFIED;
+use http::header::VARY;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use http_endpoint::cache::Cache;
+use http_endpoint::cache::DirStorage;
+use http_endpoint::cache::MemoryStorage;
+use http_endpoint::cache::Storage;
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::CachePolicy;
+use http_endpoint::Client;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+use http_endpoint::Transport;
+
+use test_log::test;
+
+use thiserror::Error;
+
+
+#[derive(Debug, Error)]
+#[error("no error")]
+struct NoError;
+
+
+EndpointDef! {
+  GetResource(&'static str),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetResourceError, [],
+  ConversionErr => Utf8Error,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(name: &Self::Input) -> Str {
+    format!("/{name}").into()
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(from_utf8(body)?.to_string())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+
+EndpointDef! {
+  GetLocalized(&'static str),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetLocalizedError, [],
+  ConversionErr => Utf8Error,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/localized".into()
+  }
+
+  fn headers(language: &Self::Input) -> Result<Option<HeaderMap>, Self::ConversionError> {
+    let mut headers = HeaderMap::new();
+    let _prev = headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_static(language));
+    Ok(Some(headers))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(from_utf8(body)?.to_string())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+
+EndpointDef! {
+  GetForced(&'static str),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetForcedError, [],
+  ConversionErr => Utf8Error,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(name: &Self::Input) -> Str {
+    format!("/{name}").into()
+  }
+
+  fn cache_policy() -> CachePolicy {
+    CachePolicy::ForceCache
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(from_utf8(body)?.to_string())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+
+EndpointDef! {
+  GetBypassed(&'static str),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetBypassedError, [],
+  ConversionErr => Utf8Error,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(name: &Self::Input) -> Str {
+    format!("/{name}").into()
+  }
+
+  fn cache_policy() -> CachePolicy {
+    CachePolicy::Bypass
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(from_utf8(body)?.to_string())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+
+/// A transport serving versioned resources with different caching
+/// characteristics, honoring `If-None-Match` headers.
+#[derive(Default)]
+struct Server {
+  /// The current version of the resources.
+  version: Mutex<u64>,
+  /// The `If-None-Match` header values of all requests along with the
+  /// statuses responded with.
+  requests: Mutex<Vec<(Option<String>, StatusCode)>>,
+}
+
+impl Transport for Server {
+  type Error = Infallible;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let version = *self.version.lock().unwrap();
+    let etag = format!("\"v{version}\"");
+    let if_none_match = request
+      .headers()
+      .get(IF_NONE_MATCH)
+      .map(|value| value.to_str().unwrap().to_string());
+
+    let mut body = format!("version {version}");
+    let mut response = Response::new(Vec::new());
+    let headers = response.headers_mut();
+    match request.uri().path() {
+      "/versioned" => {
+        let _prev = headers.insert(ETAG, etag.parse().unwrap());
+        let _prev = headers.insert(
+          LAST_MODIFIED,
+          "Sun, 18 Oct 2026 12:00:00 GMT".parse().unwrap(),
+        );
+      },
+      "/fresh" => {
+        let _prev = headers.insert(CACHE_CONTROL, "max-age=3600".parse().unwrap());
+      },
+      "/no-store" => {
+        let _prev = headers.insert(ETAG, etag.parse().unwrap());
+        let _prev = headers.insert(CACHE_CONTROL, "no-store, max-age=3600".parse().unwrap());
+      },
+      "/private" => {
+        let _prev = headers.insert(CACHE_CONTROL, "private, max-age=3600".parse().unwrap());
+      },
+      "/localized" => {
+        let language = request.headers().get(ACCEPT_LANGUAGE).unwrap();
+        let () = body.push_str(&format!(" ({})", language.to_str().unwrap()));
+        let _prev = headers.insert(CACHE_CONTROL, "max-age=3600".parse().unwrap());
+        let _prev = headers.insert(VARY, "Accept-Language".parse().unwrap());
+      },
+      "/stale" => {
+        let _prev = headers.insert(ETAG, etag.parse().unwrap());
+        let _prev = headers.insert(AGE, "10".parse().unwrap());
+        let _prev = headers.insert(
+          CACHE_CONTROL,
+          "max-age=1, stale-while-revalidate=60".parse().unwrap(),
+        );
+      },
+      _ => (),
+    }
+
+    if if_none_match.as_deref() == Some(etag.as_str()) {
+      *response.status_mut() = StatusCode::NOT_MODIFIED;
+    } else {
+      *response.body_mut() = body.into_bytes();
+    }
+
+    let () = self
+      .requests
+      .lock()
+      .unwrap()
+      .push((if_none_match, response.status()));
+    Box::pin(async move { Ok(response) })
+  }
+}
+
+
+/// Issue requests for a versioned resource through a cache using the
+/// given storage and check that they are revalidated.
+async fn revalidate<S>(storage: S)
+where
+  S: Storage + 'static,
+{
+  let client = Client::new(Cache::with_storage(Server::default(), storage));
+
+  let output = client.issue::<GetResource>(&"versioned").await.unwrap();
+  assert_eq!(output, "version 0");
+  let output = client.issue::<GetResource>(&"versioned").await.unwrap();
+  assert_eq!(output, "version 0");
+
+  *client.transport().transport().version.lock().unwrap() += 1;
+  let output = client.issue::<GetResource>(&"versioned").await.unwrap();
+  assert_eq!(output, "version 1");
+  let output = client.issue::<GetResource>(&"versioned").await.unwrap();
+  assert_eq!(output, "version 1");
+
+  let requests = client.transport().transport().requests.lock().unwrap();
+  assert_eq!(
+    *requests,
+    [
+      (None, StatusCode::OK),
+      (Some("\"v0\"".to_string()), StatusCode::NOT_MODIFIED),
+      (Some("\"v0\"".to_string()), StatusCode::OK),
+      (Some("\"v1\"".to_string()), StatusCode::NOT_MODIFIED),
+    ]
+  );
+}
+
+
+/// Check that responses kept in memory are revalidated.
+#[test(tokio::test)]
+async fn memory_revalidation() {
+  let () = revalidate(MemoryStorage::default()).await;
+}
+
+/// Check that responses kept on disk are revalidated, also across
+/// storage instances.
+#[test(tokio::test)]
+async fn dir_revalidation() {
+  let dir = temp_dir().join(format!("http-endpoint-cache-{}", id()));
+  let () = revalidate(DirStorage::new(&dir).unwrap()).await;
+
+  let storage = DirStorage::new(&dir).unwrap();
+  let entry = storage.get("http://localhost/versioned").unwrap();
+  assert_eq!(entry.status, StatusCode::OK);
+  assert_eq!(entry.etag().unwrap(), "\"v1\"");
+  assert_eq!(entry.body, b"version 1");
+
+  let () = remove_dir_all(&dir).unwrap();
+}
+
+/// Check that responses without validators are not stored.
+#[test(tokio::test)]
+async fn no_validators() {
+  let client = Client::new(Cache::new(Server::default()));
+
+  let output = client.issue::<GetResource>(&"plain").await.unwrap();
+  assert_eq!(output, "version 0");
+  let output = client.issue::<GetResource>(&"plain").await.unwrap();
+  assert_eq!(output, "version 0");
+
+  assert!(client.transport().storage().is_empty());
+  let requests = client.transport().transport().requests.lock().unwrap();
+  assert_eq!(*requests, [(None, StatusCode::OK), (None, StatusCode::OK)]);
+}
+
+/// Check that fresh responses are served without contacting the
+/// server.
+#[test(tokio::test)]
+async fn fresh_responses() {
+  let client = Client::new(Cache::new(Server::default()));
+
+  let output = client.issue::<GetResource>(&"fresh").await.unwrap();
+  assert_eq!(output, "version 0");
+
+  *client.transport().transport().version.lock().unwrap() += 1;
+  let output = client.issue::<GetResource>(&"fresh").await.unwrap();
+  assert_eq!(output, "version 0");
+
+  let requests = client.transport().transport().requests.lock().unwrap();
+  assert_eq!(*requests, [(None, StatusCode::OK)]);
+}
+
+/// Check that responses marked as `no-store` are never stored.
+#[test(tokio::test)]
+async fn no_store() {
+  let client = Client::new(Cache::new(Server::default()));
+
+  let output = client.issue::<GetResource>(&"no-store").await.unwrap();
+  assert_eq!(output, "version 0");
+  let output = client.issue::<GetResource>(&"no-store").await.unwrap();
+  assert_eq!(output, "version 0");
+
+  assert!(client.transport().storage().is_empty());
+  let requests = client.transport().transport().requests.lock().unwrap();
+  assert_eq!(*requests, [(None, StatusCode::OK), (None, StatusCode::OK)]);
+}
+
+/// Check that responses marked as `private` are only stored by private
+/// caches.
+#[test(tokio::test)]
+async fn private_responses() {
+  let client = Client::new(Cache::new(Server::default()));
+  let _output = client.issue::<GetResource>(&"private").await.unwrap();
+  let _output = client.issue::<GetResource>(&"private").await.unwrap();
+  assert_eq!(client.transport().storage().len(), 1);
+  assert_eq!(
+    client
+      .transport()
+      .transport()
+      .requests
+      .lock()
+      .unwrap()
+      .len(),
+    1
+  );
+
+  let client = Client::new(Cache::new(Server::default()).with_shared(true));
+  let _output = client.issue::<GetResource>(&"private").await.unwrap();
+  let _output = client.issue::<GetResource>(&"private").await.unwrap();
+  assert!(client.transport().storage().is_empty());
+  assert_eq!(
+    client
+      .transport()
+      .transport()
+      .requests
+      .lock()
+      .unwrap()
+      .len(),
+    2
+  );
+}
+
+/// Check that stored responses are only served for requests matching
+/// the headers nominated by their `Vary` header.
+#[test(tokio::test)]
+async fn vary() {
+  let client = Client::new(Cache::new(Server::default()));
+
+  let output = client.issue::<GetLocalized>(&"en").await.unwrap();
+  assert_eq!(output, "version 0 (en)");
+  let output = client.issue::<GetLocalized>(&"en").await.unwrap();
+  assert_eq!(output, "version 0 (en)");
+  assert_eq!(
+    client
+      .transport()
+      .transport()
+      .requests
+      .lock()
+      .unwrap()
+      .len(),
+    1
+  );
+
+  let output = client.issue::<GetLocalized>(&"de").await.unwrap();
+  assert_eq!(output, "version 0 (de)");
+  let output = client.issue::<GetLocalized>(&"de").await.unwrap();
+  assert_eq!(output, "version 0 (de)");
+  assert_eq!(
+    client
+      .transport()
+      .transport()
+      .requests
+      .lock()
+      .unwrap()
+      .len(),
+    2
+  );
+}
+
+/// Check that responses allowed to be served stale are revalidated in
+/// the background.
+#[test(tokio::test)]
+async fn stale_while_revalidate() {
+  let client = Client::new(Cache::new(Server::default()));
+
+  let output = client.issue::<GetResource>(&"stale").await.unwrap();
+  assert_eq!(output, "version 0");
+
+  *client.transport().transport().version.lock().unwrap() += 1;
+  let output = client.issue::<GetResource>(&"stale").await.unwrap();
+  assert_eq!(output, "version 0");
+
+  while client
+    .transport()
+    .transport()
+    .requests
+    .lock()
+    .unwrap()
+    .len()
+    < 2
+  {
+    let () = tokio::task::yield_now().await;
+  }
+
+  let output = client.issue::<GetResource>(&"stale").await.unwrap();
+  assert_eq!(output, "version 1");
+
+  let requests = client.transport().transport().requests.lock().unwrap();
+  assert_eq!(
+    requests[..2],
+    [
+      (None, StatusCode::OK),
+      (Some("\"v0\"".to_string()), StatusCode::OK),
+    ]
+  );
+}
+
+/// Check that endpoints can force the use of stored responses or
+/// bypass the cache altogether.
+#[test(tokio::test)]
+async fn cache_policies() {
+  let client = Client::new(Cache::new(Server::default()));
+
+  let output = client.issue::<GetForced>(&"versioned").await.unwrap();
+  assert_eq!(output, "version 0");
+  *client.transport().transport().version.lock().unwrap() += 1;
+  let output = client.issue::<GetForced>(&"versioned").await.unwrap();
+  assert_eq!(output, "version 0");
+  assert_eq!(
+    client
+      .transport()
+      .transport()
+      .requests
+      .lock()
+      .unwrap()
+      .len(),
+    1
+  );
+
+  let output = client.issue::<GetBypassed>(&"fresh").await.unwrap();
+  assert_eq!(output, "version 1");
+  let output = client.issue::<GetBypassed>(&"fresh").await.unwrap();
+  assert_eq!(output, "version 1");
+  assert_eq!(
+    client
+      .transport()
+      .transport()
+      .requests
+      .lock()
+      .unwrap()
+      .len(),
+    3
+  );
+  assert!(client
+    .transport()
+    .storage()
+    .get("http://localhost/fresh")
+    .is_none());
+}
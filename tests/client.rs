@@ -0,0 +1,482 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use http::header::AUTHORIZATION;
+use http::HeaderValue;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use http_endpoint::ApiKey;
+use http_endpoint::Auth;
+use http_endpoint::AuthError;
+use http_endpoint::Basic;
+use http_endpoint::Bearer;
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::Client;
+use http_endpoint::ClientError;
+use http_endpoint::EndpointDef;
+use http_endpoint::MetricsObserver;
+use http_endpoint::Observer;
+use http_endpoint::Quota;
+use http_endpoint::RateLimiter;
+use http_endpoint::Str;
+use http_endpoint::Transport;
+
+use metrics::with_local_recorder;
+
+use metrics_util::debugging::DebugValue;
+use metrics_util::debugging::DebuggingRecorder;
+
+use tokio::runtime::Builder as RuntimeBuilder;
+use tokio::time::sleep;
+use tokio::time::Instant;
+
+use test_log::test;
+
+use thiserror::Error;
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("an unspecified error was encountered")]
+struct NoError;
+
+
+EndpointDef! {
+  GetEcho(String),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/echo/{}", input).into()
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    String::from_utf8(body.to_vec()).map_err(|_| NoError)
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+EndpointDef! {
+  GetSlowEcho(String),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetSlowError, [],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+  Timeout => Duration::from_secs(5),
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/echo/{}", input).into()
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    String::from_utf8(body.to_vec()).map_err(|_| NoError)
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+/// A transport answering requests in-process, echoing back the last
+/// path component. Requests to a path ending in a number are answered
+/// after that many seconds.
+#[derive(Debug, Default)]
+struct Echo {
+  /// The URIs of all requests sent so far along with the time they
+  /// were sent at.
+  requests: Mutex<Vec<(String, Instant)>>,
+  /// The values of the `Authorization` header of all requests sent so
+  /// far.
+  authorizations: Mutex<Vec<Option<String>>>,
+  /// Headers to add to each response.
+  headers: Vec<(&'static str, &'static str)>,
+}
+
+impl Transport for Echo {
+  type Error = Infallible;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let uri = request.uri().to_string();
+    self
+      .requests
+      .lock()
+      .unwrap()
+      .push((uri.clone(), Instant::now()));
+    let authorization = request
+      .headers()
+      .get(AUTHORIZATION)
+      .map(|value| value.to_str().unwrap().to_string());
+    self.authorizations.lock().unwrap().push(authorization);
+
+    let body = uri.rsplit('/').next().unwrap().to_string();
+    let status = if body == "missing" {
+      StatusCode::NOT_FOUND
+    } else {
+      StatusCode::OK
+    };
+
+    let mut response = Response::builder().status(status);
+    for (key, value) in &self.headers {
+      response = response.header(*key, *value);
+    }
+    let delay = body.parse::<u64>().unwrap_or(0);
+    let response = response.body(body.into_bytes()).unwrap();
+    Box::pin(async move {
+      sleep(Duration::from_secs(delay)).await;
+      Ok(response)
+    })
+  }
+}
+
+
+/// Check that we can issue a request through a client.
+#[test(tokio::test)]
+async fn issue() {
+  let client = Client::new(Echo::default()).with_base_url("https://example.com/api/");
+  let output = client.issue::<GetEcho>(&"hello".to_string()).await.unwrap();
+  assert_eq!(output, "hello");
+
+  let requests = client.transport().requests.lock().unwrap();
+  assert_eq!(requests[0].0, "https://example.com/api/echo/hello");
+}
+
+/// Check that endpoint errors are reported properly.
+#[test(tokio::test)]
+async fn issue_error() {
+  let client = Client::new(Echo::default()).with_base_url("https://example.com");
+  let err = client
+    .issue::<GetEcho>(&"missing".to_string())
+    .await
+    .unwrap_err();
+  match err {
+    ClientError::Endpoint(GetError::NotFound(Ok(NoError))) => (),
+    _ => panic!("unexpected error: {:?}", err),
+  }
+}
+
+/// Check that configured authentication providers are applied to
+/// requests.
+#[test(tokio::test)]
+async fn auth() {
+  let client = Client::new(Echo::default())
+    .with_base_url("https://example.com")
+    .with_auth(Bearer::new("token").unwrap())
+    .with_base_url_auth(
+      "HTTPS://Example.COM:443/echo/basic/",
+      Basic::new("user", None).unwrap(),
+    )
+    .with_base_url_auth(
+      "https://example.com/echo/basic/key",
+      ApiKey::query("key", "secret"),
+    );
+
+  for input in ["hello", "basic/hello", "basic/key/hello", "basics/hello"] {
+    let _output = client.issue::<GetEcho>(&input.to_string()).await.unwrap();
+  }
+
+  let requests = client.transport().requests.lock().unwrap();
+  let requests = requests.iter().map(|(uri, _)| uri).collect::<Vec<_>>();
+  assert_eq!(
+    requests,
+    [
+      "https://example.com/echo/hello",
+      "https://example.com/echo/basic/hello",
+      "https://example.com/echo/basic/key/hello?key=secret",
+      "https://example.com/echo/basics/hello",
+    ]
+  );
+
+  let authorizations = client.transport().authorizations.lock().unwrap();
+  assert_eq!(
+    *authorizations,
+    [
+      Some("Bearer token".to_string()),
+      Some("Basic dXNlcjo=".to_string()),
+      None,
+      Some("Bearer token".to_string()),
+    ]
+  );
+}
+
+/// Check that failures to authenticate requests are reported and the
+/// requests not sent.
+#[test(tokio::test)]
+async fn auth_failure() {
+  struct Failing;
+
+  impl Auth for Failing {
+    fn authenticate(&self, _request: &mut Request<Bytes>) -> Result<(), AuthError> {
+      Err(AuthError::new("credentials expired"))
+    }
+  }
+
+  let client = Client::new(Echo::default())
+    .with_base_url("https://example.com")
+    .with_auth(Failing);
+
+  let err = client
+    .issue::<GetEcho>(&"hello".to_string())
+    .await
+    .unwrap_err();
+  assert!(matches!(err, ClientError::Auth(..)), "{err:?}");
+  assert_eq!(
+    err.to_string(),
+    "failed to authenticate request: credentials expired"
+  );
+  assert!(client.transport().requests.lock().unwrap().is_empty());
+}
+
+/// Check that authentication happens after waiting for the rate
+/// limiter, so that time sensitive credentials are not stale.
+#[test(tokio::test(start_paused = true))]
+async fn auth_after_rate_limit() {
+  /// An authentication provider stamping requests with the number of
+  /// milliseconds elapsed since its creation.
+  struct Stamp(Instant);
+
+  impl Auth for Stamp {
+    fn authenticate(&self, request: &mut Request<Bytes>) -> Result<(), AuthError> {
+      let elapsed = self.0.elapsed().as_millis();
+      let value = HeaderValue::from_str(&format!("Stamp {elapsed}")).unwrap();
+      let _prev = request.headers_mut().insert(AUTHORIZATION, value);
+      Ok(())
+    }
+  }
+
+  let start = Instant::now();
+  let limiter = RateLimiter::new().with_endpoint_limit::<GetEcho>(Quota::per_second(2));
+  let client = Client::new(Echo::default())
+    .with_base_url("https://example.com")
+    .with_rate_limiter(limiter)
+    .with_auth(Stamp(start));
+
+  for _ in 0..4 {
+    let _output = client.issue::<GetEcho>(&"x".to_string()).await.unwrap();
+  }
+
+  let requests = client.transport().requests.lock().unwrap();
+  let authorizations = client.transport().authorizations.lock().unwrap();
+  assert!(requests[3].1.duration_since(start) >= Duration::from_secs(1));
+  for ((_, sent), authorization) in requests.iter().zip(authorizations.iter()) {
+    let expected = format!("Stamp {}", sent.duration_since(start).as_millis());
+    assert_eq!(authorization.as_deref(), Some(expected.as_str()));
+  }
+}
+
+/// Check that requests get delayed as per the configured rate limits.
+#[test(tokio::test(start_paused = true))]
+async fn rate_limit() {
+  let limiter = RateLimiter::new().with_endpoint_limit::<GetEcho>(Quota::per_second(2));
+  let client = Client::new(Echo::default())
+    .with_base_url("https://example.com")
+    .with_rate_limiter(limiter);
+
+  let start = Instant::now();
+  for _ in 0..4 {
+    let _output = client.issue::<GetEcho>(&"x".to_string()).await.unwrap();
+  }
+
+  let requests = client.transport().requests.lock().unwrap();
+  let times = requests
+    .iter()
+    .map(|(_, time)| time.duration_since(start))
+    .collect::<Vec<_>>();
+  assert_eq!(times[0], Duration::ZERO);
+  assert_eq!(times[1], Duration::ZERO);
+  assert!(times[2] >= Duration::from_millis(500), "{times:?}");
+  assert!(times[3] >= Duration::from_millis(1000), "{times:?}");
+}
+
+/// Check that the client backs off when the server reports an
+/// exhausted quota.
+#[test(tokio::test(start_paused = true))]
+async fn rate_limit_headers() {
+  let echo = Echo {
+    headers: vec![("X-RateLimit-Remaining", "0"), ("X-RateLimit-Reset", "10")],
+    ..Default::default()
+  };
+  let client = Client::new(echo)
+    .with_base_url("https://example.com")
+    .with_rate_limiter(RateLimiter::new());
+
+  let start = Instant::now();
+  let _output = client.issue::<GetEcho>(&"x".to_string()).await.unwrap();
+  let _output = client.issue::<GetEcho>(&"x".to_string()).await.unwrap();
+
+  let requests = client.transport().requests.lock().unwrap();
+  assert_eq!(requests[0].1.duration_since(start), Duration::ZERO);
+  assert!(requests[1].1.duration_since(start) >= Duration::from_secs(10));
+}
+
+/// Check that the client's default timeout and endpoint specific ones
+/// are honored.
+#[test(tokio::test(start_paused = true))]
+async fn timeout() {
+  let client = Client::new(Echo::default())
+    .with_base_url("https://example.com")
+    .with_timeout(Duration::from_secs(2));
+
+  let output = client.issue::<GetEcho>(&"1".to_string()).await.unwrap();
+  assert_eq!(output, "1");
+
+  let err = client.issue::<GetEcho>(&"3".to_string()).await.unwrap_err();
+  match err {
+    ClientError::Timeout(timeout) => assert_eq!(timeout, Duration::from_secs(2)),
+    _ => panic!("unexpected error: {:?}", err),
+  }
+
+  let output = client.issue::<GetSlowEcho>(&"3".to_string()).await.unwrap();
+  assert_eq!(output, "3");
+
+  let err = client
+    .issue::<GetSlowEcho>(&"6".to_string())
+    .await
+    .unwrap_err();
+  match err {
+    ClientError::Timeout(timeout) => assert_eq!(timeout, Duration::from_secs(5)),
+    _ => panic!("unexpected error: {:?}", err),
+  }
+}
+
+/// An observer recording all invocations.
+#[derive(Clone, Debug, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Observer for Recorder {
+  fn started(&self, endpoint: &'static str) {
+    self.0.lock().unwrap().push(format!("started {endpoint}"));
+  }
+
+  fn responded(&self, endpoint: &'static str, status: StatusCode, _latency: Duration) {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .push(format!("responded {endpoint} {}", status.as_u16()));
+  }
+
+  fn finished(&self, endpoint: &'static str, error: Option<&'static str>, latency: Duration) {
+    self.0.lock().unwrap().push(format!(
+      "finished {endpoint} {error:?} {}",
+      latency.as_secs()
+    ));
+  }
+}
+
+
+/// Check that the configured observer is informed about issued
+/// requests.
+#[test(tokio::test(start_paused = true))]
+async fn observe() {
+  let recorder = Recorder::default();
+  let client = Client::new(Echo::default())
+    .with_base_url("https://example.com")
+    .with_timeout(Duration::from_secs(2))
+    .with_observer(recorder.clone());
+
+  let _output = client.issue::<GetEcho>(&"1".to_string()).await.unwrap();
+  let _err = client
+    .issue::<GetEcho>(&"missing".to_string())
+    .await
+    .unwrap_err();
+  let _err = client.issue::<GetEcho>(&"3".to_string()).await.unwrap_err();
+
+  let events = recorder.0.lock().unwrap();
+  let expected = [
+    "started client::GetEcho",
+    "responded client::GetEcho 200",
+    "finished client::GetEcho None 1",
+    "started client::GetEcho",
+    "responded client::GetEcho 404",
+    "finished client::GetEcho Some(\"NotFound\") 0",
+    "started client::GetEcho",
+    "finished client::GetEcho Some(\"Timeout\") 2",
+  ];
+  assert_eq!(*events, expected);
+}
+
+/// Check that the `MetricsObserver` records the expected metrics.
+#[test]
+fn metrics() {
+  let recorder = DebuggingRecorder::new();
+  let snapshotter = recorder.snapshotter();
+
+  with_local_recorder(&recorder, || {
+    let runtime = RuntimeBuilder::new_current_thread()
+      .enable_time()
+      .build()
+      .unwrap();
+    runtime.block_on(async {
+      let client = Client::new(Echo::default())
+        .with_base_url("https://example.com")
+        .with_observer(MetricsObserver);
+      let _output = client.issue::<GetEcho>(&"x".to_string()).await.unwrap();
+      let _err = client
+        .issue::<GetEcho>(&"missing".to_string())
+        .await
+        .unwrap_err();
+    })
+  });
+
+  let metrics = snapshotter
+    .snapshot()
+    .into_vec()
+    .into_iter()
+    .map(|(key, _, _, value)| {
+      let key = key.key();
+      let mut labels = key
+        .labels()
+        .map(|label| format!("{}={}", label.key(), label.value()))
+        .collect::<Vec<_>>();
+      labels.sort();
+      (format!("{}{{{}}}", key.name(), labels.join(",")), value)
+    })
+    .collect::<Vec<_>>();
+
+  let counter = |name: &str| {
+    metrics
+      .iter()
+      .find(|(key, _)| key == name)
+      .map(|(_, value)| value)
+  };
+  assert_eq!(
+    counter("http_endpoint_requests_total{endpoint=client::GetEcho}"),
+    Some(&DebugValue::Counter(2))
+  );
+  assert_eq!(
+    counter("http_endpoint_responses_total{endpoint=client::GetEcho,status=200}"),
+    Some(&DebugValue::Counter(1))
+  );
+  assert_eq!(
+    counter("http_endpoint_responses_total{endpoint=client::GetEcho,status=404}"),
+    Some(&DebugValue::Counter(1))
+  );
+  assert_eq!(
+    counter("http_endpoint_errors_total{endpoint=client::GetEcho,error=NotFound}"),
+    Some(&DebugValue::Counter(1))
+  );
+  match counter("http_endpoint_request_duration_seconds{endpoint=client::GetEcho}") {
+    Some(DebugValue::Histogram(values)) => assert_eq!(values.len(), 2),
+    value => panic!("unexpected value: {value:?}"),
+  }
+}
@@ -0,0 +1,265 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::env::temp_dir;
+use std::fs::read_to_string;
+use std::fs::remove_file;
+use std::process::id;
+
+use http::Method;
+use http::StatusCode;
+
+use http_endpoint::codegen::generate;
+use http_endpoint::codegen::generate_file;
+use http_endpoint::codegen::Error;
+use http_endpoint::request;
+use http_endpoint::Endpoint;
+
+use test_log::test;
+
+
+/// The code generated for the pet store specification.
+mod petstore {
+  include!("data/petstore.rs");
+}
+
+use petstore::Category;
+use petstore::CreatePet;
+use petstore::CreatePetError;
+use petstore::CreatePetInput;
+use petstore::DeletePetsPetId;
+use petstore::DeletePetsPetIdInput;
+use petstore::GetCategories;
+use petstore::GetCategoriesInput;
+use petstore::ListPets;
+use petstore::ListPetsInput;
+use petstore::NewPet;
+use petstore::Pet;
+use petstore::ShowPetById;
+use petstore::ShowPetByIdInput;
+use petstore::Status;
+
+
+const YAML_SPEC: &str = include_str!("data/petstore.yaml");
+const JSON_SPEC: &str = include_str!("data/petstore.json");
+const GENERATED: &str = include_str!("data/petstore.rs");
+
+
+/// Check that the code generated for the sample specifications matches
+/// the checked in version.
+#[test]
+fn generated_code() {
+  assert_eq!(generate(YAML_SPEC).unwrap(), GENERATED);
+  assert_eq!(generate(JSON_SPEC).unwrap(), GENERATED);
+}
+
+/// Check that we can generate code from one file into another, as
+/// would be done from a build script.
+#[test]
+fn generate_from_file() {
+  let spec = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/petstore.yaml");
+  let output = temp_dir().join(format!("http-endpoint-codegen-{}.rs", id()));
+  let () = generate_file(spec, &output).unwrap();
+  let code = read_to_string(&output).unwrap();
+  let () = remove_file(&output).unwrap();
+  assert_eq!(code, GENERATED);
+}
+
+/// Check that generated endpoints create the expected requests.
+#[test]
+fn generated_requests() {
+  let input = ListPetsInput {
+    limit: Some(10),
+    tag: Some(vec!["cat".into(), "small dog".into()]),
+  };
+  let req = request::<ListPets>(Some("https://pets.example.com"), &input).unwrap();
+  assert_eq!(req.method(), Method::GET);
+  assert_eq!(
+    req.uri(),
+    "https://pets.example.com/pets?limit=10&tag=cat&tag=small%20dog"
+  );
+
+  let input = ListPetsInput {
+    limit: None,
+    tag: None,
+  };
+  let req = request::<ListPets>(Some("https://pets.example.com"), &input).unwrap();
+  assert_eq!(req.uri(), "https://pets.example.com/pets");
+
+  let input = ShowPetByIdInput { pet_id: 42 };
+  let req = request::<ShowPetById>(Some("https://pets.example.com"), &input).unwrap();
+  assert_eq!(req.uri(), "https://pets.example.com/pets/42");
+  assert_eq!(ShowPetById::path_template(), Some("/pets/{petId}"));
+
+  let input = GetCategoriesInput { ids: vec![1, 2] };
+  let req = request::<GetCategories>(Some("https://pets.example.com"), &input).unwrap();
+  assert_eq!(req.uri(), "https://pets.example.com/categories/1,2");
+
+  let input = DeletePetsPetIdInput { pet_id: 42 };
+  let req = request::<DeletePetsPetId>(None, &input).unwrap();
+  assert_eq!(req.method(), Method::DELETE);
+
+  let input = CreatePetInput {
+    body: NewPet {
+      name: "Garfield".into(),
+      status: Some(Status::Available),
+      r#type: None,
+    },
+  };
+  let req = request::<CreatePet>(None, &input).unwrap();
+  assert_eq!(req.method(), Method::POST);
+  assert_eq!(
+    req.body().as_ref(),
+    br#"{"name":"Garfield","status":"available"}"#
+  );
+}
+
+/// Check that generated endpoints evaluate responses as expected.
+#[test]
+fn generated_responses() {
+  let body = br#"{"id":1,"name":"Garfield","photoUrls":["a"],"status":"sold","type":"cat"}"#;
+  let pet = CreatePet::evaluate(StatusCode::CREATED, body).unwrap();
+  assert_eq!(
+    pet,
+    Pet {
+      id: 1,
+      name: "Garfield".into(),
+      photo_urls: Some(vec!["a".into()]),
+      status: Status::Sold,
+      r#type: Some("cat".into()),
+    }
+  );
+
+  let body = br#"{"message":"exists"}"#;
+  let err = CreatePet::evaluate(StatusCode::CONFLICT, body).unwrap_err();
+  match err {
+    CreatePetError::Conflict(Ok(err)) => assert_eq!(err.0["message"], "exists"),
+    _ => panic!("unexpected error: {err:?}"),
+  }
+
+  let body = br#"[{"id":2,"parent":{"id":1},"children":[{"id":3}]}]"#;
+  let categories = GetCategories::evaluate(StatusCode::OK, body).unwrap();
+  assert_eq!(categories[0].parent.as_ref().unwrap().id, 1);
+  assert_eq!(
+    categories[0].children,
+    Some(vec![Category {
+      children: None,
+      id: 3,
+      parent: None,
+    }])
+  );
+
+  let () = DeletePetsPetId::evaluate(StatusCode::NO_CONTENT, b"").unwrap();
+  assert_eq!(Status::Pending.to_string(), "pending");
+}
+
+/// Check that we reject specifications that would result in clashing
+/// names in the generated code.
+#[test]
+fn name_clashes() {
+  let spec = r#"
+openapi: 3.0.3
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        fooBar:
+          type: string
+        foo_bar:
+          type: string
+"#;
+  let err = generate(spec).unwrap_err();
+  assert!(matches!(err, Error::Spec(..)), "{err}");
+  assert_eq!(
+    err.to_string(),
+    "invalid specification: multiple properties of Pet map to field foo_bar"
+  );
+
+  let spec = r#"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: pageSize
+          in: query
+          schema:
+            type: integer
+        - name: page_size
+          in: query
+          schema:
+            type: integer
+"#;
+  let err = generate(spec).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "invalid specification: multiple parameters of ListPets map to field page_size"
+  );
+
+  let spec = r#"
+openapi: 3.0.3
+paths:
+  /pets:
+    post:
+      operationId: createPet
+      parameters:
+        - name: body
+          in: query
+          schema:
+            type: string
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+"#;
+  let err = generate(spec).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "invalid specification: parameter of CreatePet clashes with request body field"
+  );
+
+  let spec = r#"
+openapi: 3.0.3
+paths:
+  /pets:
+    post:
+      operationId: createPet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/CreatePetInput'
+components:
+  schemas:
+    CreatePetInput:
+      type: object
+      properties:
+        name:
+          type: string
+"#;
+  let err = generate(spec).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "invalid specification: multiple types named CreatePetInput"
+  );
+
+  let spec = r#"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      operationId: listPets
+components:
+  schemas:
+    ListPetsError:
+      type: string
+"#;
+  let err = generate(spec).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "invalid specification: multiple types named ListPetsError"
+  );
+}
@@ -1,84 +1,143 @@
 // Copyright (C) 2020-2024 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::env::var_os;
 use std::fmt::Debug;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use http::Request;
+use http::Response;
 
 use http_body_util::BodyExt;
 use http_body_util::Full;
 use hyper::body::Bytes as HyperBytes;
-use hyper::http::request::Builder as HttpRequestBuilder;
 use hyper::Error as HyperError;
-use hyper::Request;
 use hyper_tls::HttpsConnector;
 use hyper_util::client::legacy::Client as HttpClient;
 use hyper_util::client::legacy::Error as HyperUtilError;
 use hyper_util::rt::TokioExecutor;
 
+use http_endpoint::request;
+use http_endpoint::vcr::Cassette;
+use http_endpoint::vcr::Error as VcrError;
+use http_endpoint::vcr::Recorder;
+use http_endpoint::vcr::Replayer;
+use http_endpoint::BoxFuture;
 use http_endpoint::Bytes;
 use http_endpoint::Endpoint;
+use http_endpoint::Transport;
 
 use serde::Deserialize;
 
 use thiserror::Error;
 
-use url::Url;
-
 const HTTP_BIN_BASE_URL: &str = "https://httpbin.org/";
 
+/// The environment variable instructing tests to issue actual requests
+/// and record them, instead of replaying previously recorded ones.
+const RECORD_VAR: &str = "HTTP_ENDPOINT_RECORD";
+
+/// A lock serializing updates to the cassette of the test binary.
+static CASSETTE_LOCK: Mutex<()> = Mutex::new(());
+
 
 #[derive(Debug, Deserialize, Error, PartialEq)]
 #[error("an unspecified error was encountered")]
 pub struct NoError;
 
 
+#[derive(Debug, Error)]
+pub enum HttpError {
+  #[error(transparent)]
+  Hyper(#[from] HyperError),
+  #[error(transparent)]
+  HyperUtil(#[from] HyperUtilError),
+}
+
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error<E> {
   Endpoint(E),
-  Hyper(HyperError),
-  HyperUtil(HyperUtilError),
+  Http(HttpError),
+  Vcr(VcrError),
 }
 
 
-fn request<E>(input: &E::Input) -> Result<Request<Full<HyperBytes>>, E::Error>
-where
-  E: Endpoint,
-{
-  let mut url = Url::parse(HTTP_BIN_BASE_URL).unwrap();
-  url.set_path(&E::path(input));
-  url.set_query(E::query(input)?.as_ref().map(AsRef::as_ref));
-
-  let body = match E::body(input)? {
-    None => HyperBytes::new(),
-    Some(Bytes::Borrowed(slice)) => HyperBytes::from(slice),
-    Some(Bytes::Owned(vec)) => HyperBytes::from(vec),
-  };
+/// A `Transport` issuing requests over the network using `hyper`.
+struct HyperTransport;
+
+impl Transport for HyperTransport {
+  type Error = HttpError;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    Box::pin(async move {
+      let client = HttpClient::builder(TokioExecutor::new()).build(HttpsConnector::new());
+      let request = request.map(|body| match body {
+        Bytes::Borrowed(slice) => Full::new(HyperBytes::from(slice)),
+        Bytes::Owned(vec) => Full::new(HyperBytes::from(vec)),
+      });
+      let response = client.request(request).await?;
+      let (parts, body) = response.into_parts();
+      let body = BodyExt::collect(body).await?.to_bytes();
+      Ok(Response::from_parts(parts, body.to_vec()))
+    })
+  }
+}
+
+
+/// Retrieve the path to the cassette of the current test binary.
+fn cassette_path() -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("data")
+    .join("cassettes")
+    .join(concat!(env!("CARGO_CRATE_NAME"), ".yaml"))
+}
 
-  let headers = E::headers(input)?;
-  let mut request = HttpRequestBuilder::new()
-    .method(E::method())
-    .uri(url.as_str())
-    .body(Full::new(body))?;
 
-  if let Some(headers) = headers {
-    request.headers_mut().extend(headers);
+/// Issue a request to httpbin and record the interaction.
+async fn record<E>(request: Request<Bytes>) -> Result<Response<Vec<u8>>, Error<E>> {
+  let recorder = Recorder::new(HyperTransport);
+  let response = recorder.send(request).await.map_err(Error::Http)?;
+
+  let path = cassette_path();
+  let _guard = CASSETTE_LOCK.lock().unwrap();
+  let mut cassette = if path.exists() {
+    Cassette::load(&path).map_err(Error::Vcr)?
+  } else {
+    Cassette::new()
+  };
+  for interaction in recorder.into_cassette().interactions {
+    let () = cassette
+      .interactions
+      .retain(|other| other.request != interaction.request);
+    let () = cassette.interactions.push(interaction);
   }
-  Ok(request)
+  let () = cassette.save(&path).map_err(Error::Vcr)?;
+  Ok(response)
 }
 
+
+/// Issue a request to the given endpoint.
+///
+/// By default, responses are replayed from the cassette of the current
+/// test binary. If the `HTTP_ENDPOINT_RECORD` environment variable is
+/// set, requests are sent to httpbin instead and recorded.
 pub async fn issue<E>(input: &E::Input) -> Result<E::Output, Error<E::Error>>
 where
   E: Endpoint,
 {
-  let client = HttpClient::builder(TokioExecutor::new()).build(HttpsConnector::new());
-  let request = request::<E>(input).map_err(Error::Endpoint)?;
-  let result = client.request(request).await.map_err(Error::HyperUtil)?;
-  let status = result.status();
-  let bytes = BodyExt::collect(result)
-    .await
-    .map_err(Error::Hyper)?
-    .to_bytes();
-  let body = bytes.as_ref();
-  let output = E::evaluate(status, body).map_err(Error::Endpoint)?;
+  let request = request::<E>(Some(HTTP_BIN_BASE_URL), input).map_err(Error::Endpoint)?;
+  let response = if var_os(RECORD_VAR).is_some() {
+    record(request).await?
+  } else {
+    let replayer = Replayer::load(cassette_path()).map_err(Error::Vcr)?;
+    replayer.send(request).await.map_err(Error::Vcr)?
+  };
+
+  let output = E::evaluate(response.status(), response.body()).map_err(Error::Endpoint)?;
   Ok(output)
 }
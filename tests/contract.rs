@@ -0,0 +1,375 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::path::Path;
+use std::ptr;
+
+use http::Error as HttpError;
+use http::Method;
+use http::StatusCode;
+
+use http_endpoint::contract::Verifier;
+use http_endpoint::contract::Violation;
+use http_endpoint::vcr::Body;
+use http_endpoint::vcr::Cassette;
+use http_endpoint::vcr::Interaction;
+use http_endpoint::vcr::RecordedRequest;
+use http_endpoint::vcr::RecordedResponse;
+use http_endpoint::Endpoint;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+
+use serde::Deserialize;
+
+use serde_json::json;
+use serde_json::Error as JsonError;
+
+use test_log::test;
+
+use thiserror::Error;
+
+
+#[derive(Debug, Error)]
+#[error("no error")]
+struct NoError;
+
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+struct ApiError(String);
+
+
+EndpointDef! {
+  GetStatus(u16),
+  Ok => (), [
+    /* 204 */ NO_CONTENT,
+  ],
+  Err => GetError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn path(status: &Self::Input) -> Str {
+    format!("/status/{}", status).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/status/{status}")
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+EndpointDef! {
+  PostStatus(u16),
+  Ok => (), [
+    /* 200 */ OK,
+  ],
+  Err => PostError, [
+    /* 401 */ UNAUTHORIZED => Unauthorized,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn method() -> Method {
+    Method::POST
+  }
+
+  fn path(status: &Self::Input) -> Str {
+    format!("/status/{}", status).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/status/{status}")
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+#[derive(Debug, Deserialize)]
+#[allow(unused)]
+struct Order {
+  id: u64,
+}
+
+
+EndpointDef! {
+  GetOrder(u64),
+  Ok => Order, [
+    /* 200 */ OK,
+  ],
+  Err => GetOrderError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => JsonError,
+  ApiErr => ApiError,
+
+  fn path(id: &Self::Input) -> Str {
+    format!("/orders/{}", id).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders/{id}")
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    serde_json::from_slice(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    String::from_utf8(body.to_vec())
+      .map(ApiError)
+      .map_err(|err| err.into_bytes())
+  }
+}
+
+
+#[derive(Debug, Error)]
+enum GetCustomerError {
+  #[error("failed to create request")]
+  Http(#[from] HttpError),
+  #[error("failed to parse customer")]
+  Json(#[from] JsonError),
+  #[error("unexpected status: {0}")]
+  Status(StatusCode),
+}
+
+
+/// An endpoint implemented by hand, without declaring statuses.
+struct GetCustomer;
+
+impl Endpoint for GetCustomer {
+  type Input = u64;
+  type Output = Order;
+  type Error = GetCustomerError;
+  type ConversionError = JsonError;
+  type ApiError = NoError;
+
+  const NAME: &'static str = "GetCustomer";
+
+  fn path(id: &Self::Input) -> Str {
+    format!("/customers/{}", id).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/customers/{id}")
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    serde_json::from_slice(body)
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+
+  fn evaluate(status: StatusCode, body: &[u8]) -> Result<Self::Output, Self::Error> {
+    if status == StatusCode::OK {
+      Ok(Self::parse(body)?)
+    } else {
+      Err(GetCustomerError::Status(status))
+    }
+  }
+}
+
+
+fn interaction(method: &str, uri: &str, status: u16, body: Body) -> Interaction {
+  Interaction {
+    request: RecordedRequest {
+      method: method.to_string(),
+      uri: uri.to_string(),
+      headers: Vec::new(),
+      body: Body::default(),
+    },
+    response: RecordedResponse {
+      status,
+      headers: Vec::new(),
+      body,
+    },
+  }
+}
+
+
+/// Check that we can verify endpoint definitions against a recorded
+/// cassette.
+#[test]
+fn verify_cassette() {
+  let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("data")
+    .join("cassettes")
+    .join("status.yaml");
+  let cassette = Cassette::load(path).unwrap();
+
+  let report = Verifier::new()
+    .with_endpoint::<GetStatus>()
+    .with_endpoint::<PostStatus>()
+    .verify_cassette(&cassette);
+
+  assert!(!report.is_ok());
+  assert!(report.unmatched.is_empty());
+
+  let get = &report.endpoints[0];
+  assert_eq!(get.name, "contract::GetStatus");
+  assert_eq!(get.checked, 3);
+  assert_eq!(get.statuses, [204, 403, 404]);
+  assert_eq!(get.failures.len(), 1);
+  assert_eq!(get.failures[0].interaction, 2);
+  assert_eq!(get.failures[0].status, 403);
+  assert_eq!(get.failures[0].violation, Violation::UnexpectedStatus);
+
+  let post = &report.endpoints[1];
+  assert_eq!(post.name, "contract::PostStatus");
+  assert_eq!(post.checked, 2);
+  assert_eq!(post.statuses, [200, 401]);
+  assert!(post.failures.is_empty());
+
+  assert_eq!(
+    report.to_string(),
+    "contract::GetStatus: 3 checked, 1 failed\n  #2 GET https://httpbin.org/status/403 (403): status is not declared\ncontract::PostStatus: 2 checked, 0 failed\n"
+  );
+}
+
+/// Check that bodies failing to parse are reported.
+#[test]
+fn verify_bodies() {
+  let interactions = [
+    interaction(
+      "GET",
+      "https://example.com/orders/1",
+      200,
+      Body::Text(r#"{"id":1}"#.to_string()),
+    ),
+    interaction(
+      "GET",
+      "https://example.com/orders/2",
+      200,
+      Body::Text(r#"{"order":2}"#.to_string()),
+    ),
+    interaction(
+      "GET",
+      "https://example.com/orders/3",
+      404,
+      Body::Text("order 3 not found".to_string()),
+    ),
+    interaction(
+      "GET",
+      "https://example.com/orders/4",
+      404,
+      Body::Binary(vec![0xff, 0xfe]),
+    ),
+    interaction(
+      "GET",
+      "https://example.com/customers/1",
+      200,
+      Body::default(),
+    ),
+    interaction(
+      "DELETE",
+      "https://example.com/orders/1",
+      204,
+      Body::default(),
+    ),
+    interaction("GET", "not a URI", 200, Body::default()),
+  ];
+
+  let report = Verifier::new()
+    .with_endpoint::<GetOrder>()
+    .verify(&interactions);
+  assert!(!report.is_ok());
+  assert_eq!(report.unmatched, [4, 5, 6]);
+
+  let failures = report.failures().collect::<Vec<_>>();
+  assert_eq!(failures.len(), 2);
+  assert_eq!(failures[0].0, "contract::GetOrder");
+  assert_eq!(failures[0].1.interaction, 1);
+  assert!(
+    matches!(&failures[0].1.violation, Violation::Conversion(err) if err.contains("missing field `id`")),
+    "{:?}",
+    failures[0].1.violation
+  );
+  assert_eq!(failures[1].1.interaction, 3);
+  assert_eq!(
+    failures[1].1.violation,
+    Violation::ErrorBody("NotFound".to_string())
+  );
+
+  let json = serde_json::to_value(&report).unwrap();
+  assert_eq!(
+    json["endpoints"][0]["failures"][1],
+    json!({
+      "interaction": 3,
+      "method": "GET",
+      "uri": "https://example.com/orders/4",
+      "status": 404,
+      "violation": {"kind": "error_body", "message": "NotFound"},
+    })
+  );
+}
+
+/// Check that endpoints not declaring any statuses are verified based
+/// on the outcome of their evaluation.
+#[test]
+fn verify_manual_endpoint() {
+  let interactions = [
+    interaction(
+      "GET",
+      "https://example.com/customers/1",
+      200,
+      Body::Text(r#"{"id":1}"#.to_string()),
+    ),
+    interaction(
+      "GET",
+      "https://example.com/customers/2",
+      200,
+      Body::Text(r#"{"customer":2}"#.to_string()),
+    ),
+    interaction(
+      "GET",
+      "https://example.com/customers/3",
+      404,
+      Body::default(),
+    ),
+  ];
+
+  let meta = GetCustomer::meta();
+  assert_eq!(meta.name, "GetCustomer");
+  assert_eq!(meta.path, Some("/customers/{id}"));
+  assert!(meta.ok.is_empty());
+  assert!(ptr::eq(meta, GetCustomer::meta()));
+
+  let report = Verifier::new()
+    .with_endpoint::<GetCustomer>()
+    .verify(&interactions);
+  assert!(report.unmatched.is_empty());
+
+  let customer = &report.endpoints[0];
+  assert_eq!(customer.name, "GetCustomer");
+  assert_eq!(customer.checked, 3);
+  assert_eq!(customer.failures.len(), 2);
+  assert_eq!(customer.failures[0].interaction, 1);
+  assert_eq!(
+    customer.failures[0].violation,
+    Violation::Conversion("failed to parse customer".to_string())
+  );
+  assert_eq!(customer.failures[1].interaction, 2);
+  assert_eq!(
+    customer.failures[1].violation,
+    Violation::Conversion("unexpected status: 404 Not Found".to_string())
+  );
+}
@@ -0,0 +1,295 @@
+// Generated by http-endpoint's code generator. Do not edit.
+
+/// An error as reported by the API.
+#[derive(Clone, Debug, PartialEq, ::serde::Deserialize, ::serde::Serialize)]
+pub struct ApiError(pub ::serde_json::Value);
+
+impl ::std::fmt::Display for ApiError {
+  fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+    write!(fmt, "{}", self.0)
+  }
+}
+
+impl ::std::error::Error for ApiError {}
+
+/// Percent-encode a path segment or query value.
+#[allow(dead_code)]
+fn encode(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+  for byte in value.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+        encoded.push(char::from(byte))
+      },
+      _ => encoded.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  encoded
+}
+
+/// A category of pets.
+#[derive(Clone, Debug, PartialEq, ::serde::Deserialize, ::serde::Serialize)]
+pub struct Category {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub children: Option<Vec<Category>>,
+  pub id: i64,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub parent: Option<Box<Category>>,
+}
+
+#[derive(Clone, Debug, PartialEq, ::serde::Deserialize, ::serde::Serialize)]
+pub struct NewPet {
+  pub name: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub status: Option<Status>,
+  /// The kind of animal.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub r#type: Option<String>,
+}
+
+/// A pet in the store.
+#[derive(Clone, Debug, PartialEq, ::serde::Deserialize, ::serde::Serialize)]
+pub struct Pet {
+  pub id: i64,
+  pub name: String,
+  #[serde(default, rename = "photoUrls", skip_serializing_if = "Option::is_none")]
+  pub photo_urls: Option<Vec<String>>,
+  pub status: Status,
+  /// The kind of animal.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub r#type: Option<String>,
+}
+
+/// The status of a pet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ::serde::Deserialize, ::serde::Serialize)]
+pub enum Status {
+  #[serde(rename = "available")]
+  Available,
+  #[serde(rename = "pending")]
+  Pending,
+  #[serde(rename = "sold")]
+  Sold,
+}
+
+impl ::std::fmt::Display for Status {
+  fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+    let value = match self {
+      Self::Available => "available",
+      Self::Pending => "pending",
+      Self::Sold => "sold",
+    };
+    fmt.write_str(value)
+  }
+}
+
+/// The input to the [`GetCategories`] endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GetCategoriesInput {
+  pub ids: Vec<i64>,
+}
+
+::http_endpoint::EndpointDef! {
+  /// Retrieve multiple categories.
+  pub GetCategories(GetCategoriesInput),
+  Ok => Vec<Category>, [
+    /* 200 */ OK,
+  ],
+  Err => GetCategoriesError, [
+  ],
+  ConversionErr => ::serde_json::Error,
+  ApiErr => ApiError,
+
+  fn path(input: &Self::Input) -> ::http_endpoint::Str {
+    format!("/categories/{}", input.ids.iter().map(|value| encode(&value.to_string())).collect::<Vec<_>>().join(",")).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/categories/{ids}")
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    ::serde_json::from_slice::<Self::Output>(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    ::serde_json::from_slice::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+/// The input to the [`ListPets`] endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListPetsInput {
+  /// The maximum number of pets to return.
+  pub limit: Option<i32>,
+  pub tag: Option<Vec<String>>,
+}
+
+::http_endpoint::EndpointDef! {
+  /// List all pets.
+  pub ListPets(ListPetsInput),
+  Ok => Vec<Pet>, [
+    /* 200 */ OK,
+  ],
+  Err => ListPetsError, [
+    /* 400 */ BAD_REQUEST => BadRequest,
+  ],
+  ConversionErr => ::serde_json::Error,
+  ApiErr => ApiError,
+
+  fn path(_input: &Self::Input) -> ::http_endpoint::Str {
+    "/pets".into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/pets?limit={limit}&tag={tag}")
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<::http_endpoint::Str>, Self::ConversionError> {
+    let mut query = Vec::new();
+    if let Some(value) = &input.limit {
+      query.push(format!("limit={}", encode(&value.to_string())));
+    }
+    for value in input.tag.iter().flatten() {
+      query.push(format!("tag={}", encode(&value.to_string())));
+    }
+    if query.is_empty() {
+      Ok(None)
+    } else {
+      Ok(Some(query.join("&").into()))
+    }
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    ::serde_json::from_slice::<Self::Output>(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    ::serde_json::from_slice::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+/// The input to the [`CreatePet`] endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreatePetInput {
+  /// The request body.
+  pub body: NewPet,
+}
+
+::http_endpoint::EndpointDef! {
+  /// Create a pet.
+  pub CreatePet(CreatePetInput),
+  Ok => Pet, [
+    /* 201 */ CREATED,
+  ],
+  Err => CreatePetError, [
+    /* 409 */ CONFLICT => Conflict,
+    /* 422 */ UNPROCESSABLE_ENTITY => UnprocessableEntity,
+  ],
+  ConversionErr => ::serde_json::Error,
+  ApiErr => ApiError,
+
+  fn method() -> ::http::Method {
+    ::http::Method::POST
+  }
+
+  fn path(_input: &Self::Input) -> ::http_endpoint::Str {
+    "/pets".into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/pets")
+  }
+
+  fn body(input: &Self::Input) -> Result<Option<::http_endpoint::Bytes>, Self::ConversionError> {
+    let body = ::serde_json::to_vec(&input.body)?;
+    Ok(Some(body.into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    ::serde_json::from_slice::<Self::Output>(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    ::serde_json::from_slice::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+/// The input to the [`ShowPetById`] endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowPetByIdInput {
+  /// The ID of the pet.
+  pub pet_id: i64,
+}
+
+::http_endpoint::EndpointDef! {
+  /// Retrieve a specific pet.
+  ///
+  /// Pets are identified by their ID.
+  ///
+  /// Removed pets can not be retrieved.
+  pub ShowPetById(ShowPetByIdInput),
+  Ok => Pet, [
+    /* 200 */ OK,
+  ],
+  Err => ShowPetByIdError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => ::serde_json::Error,
+  ApiErr => ApiError,
+
+  fn path(input: &Self::Input) -> ::http_endpoint::Str {
+    format!("/pets/{}", encode(&input.pet_id.to_string())).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/pets/{petId}")
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    ::serde_json::from_slice::<Self::Output>(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    ::serde_json::from_slice::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+/// The input to the [`DeletePetsPetId`] endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeletePetsPetIdInput {
+  /// The ID of the pet.
+  pub pet_id: i64,
+}
+
+::http_endpoint::EndpointDef! {
+  /// Remove a pet.
+  pub DeletePetsPetId(DeletePetsPetIdInput),
+  Ok => (), [
+    /* 204 */ NO_CONTENT,
+  ],
+  Err => DeletePetsPetIdError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => ::serde_json::Error,
+  ApiErr => ApiError,
+
+  fn method() -> ::http::Method {
+    ::http::Method::DELETE
+  }
+
+  fn path(input: &Self::Input) -> ::http_endpoint::Str {
+    format!("/pets/{}", encode(&input.pet_id.to_string())).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/pets/{petId}")
+  }
+
+  fn parse(_body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    ::serde_json::from_slice::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
@@ -0,0 +1,248 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+use std::io::Write as _;
+use std::str::from_utf8;
+use std::str::Utf8Error;
+use std::sync::Mutex;
+
+use brotli::CompressorWriter;
+
+use flate2::write::DeflateEncoder;
+use flate2::write::GzEncoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use http::header::ACCEPT_ENCODING;
+use http::header::CONTENT_ENCODING;
+use http::header::CONTENT_LENGTH;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::Request;
+use http::Response;
+
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::Client;
+use http_endpoint::ClientError;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+use http_endpoint::Transport;
+
+use test_log::test;
+
+use thiserror::Error;
+
+
+/// The text served, in various encodings, by the [`Server`].
+const TEXT: &str = "The quick brown fox jumps over the lazy dog.";
+
+
+#[derive(Debug, Error)]
+#[error("no error")]
+struct NoError;
+
+
+EndpointDef! {
+  GetText(&'static str),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetTextError, [],
+  ConversionErr => Utf8Error,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(coding: &Self::Input) -> Str {
+    format!("/{coding}").into()
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(from_utf8(body)?.to_string())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+
+EndpointDef! {
+  GetIdentity(&'static str),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetIdentityError, [],
+  ConversionErr => Utf8Error,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(coding: &Self::Input) -> Str {
+    format!("/{coding}").into()
+  }
+
+  fn headers(_: &Self::Input) -> Result<Option<HeaderMap>, Self::ConversionError> {
+    let mut headers = HeaderMap::new();
+    let _prev = headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+    Ok(Some(headers))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(from_utf8(body)?.to_string())
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+
+/// Encode data using the given content coding.
+fn encode(coding: &str, data: &[u8]) -> Vec<u8> {
+  match coding {
+    "br" => {
+      let mut encoder = CompressorWriter::new(Vec::new(), 4096, 5, 22);
+      let () = encoder.write_all(data).unwrap();
+      encoder.into_inner()
+    },
+    "deflate" => {
+      let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+      let () = encoder.write_all(data).unwrap();
+      encoder.finish().unwrap()
+    },
+    "raw-deflate" => {
+      let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+      let () = encoder.write_all(data).unwrap();
+      encoder.finish().unwrap()
+    },
+    "gzip" => {
+      let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+      let () = encoder.write_all(data).unwrap();
+      encoder.finish().unwrap()
+    },
+    "zstd" => zstd::encode_all(data, 0).unwrap(),
+    _ => data.to_vec(),
+  }
+}
+
+
+/// A transport serving [`TEXT`] encoded using the content codings
+/// listed in the request's path.
+#[derive(Default)]
+struct Server {
+  /// The `Accept-Encoding` header values of all requests.
+  accepted: Mutex<Vec<Option<String>>>,
+}
+
+impl Transport for Server {
+  type Error = Infallible;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let accepted = request
+      .headers()
+      .get(ACCEPT_ENCODING)
+      .map(|value| value.to_str().unwrap().to_string());
+    let () = self.accepted.lock().unwrap().push(accepted);
+
+    let path = request.uri().path().trim_start_matches('/');
+    let codings = path.split('+').collect::<Vec<_>>();
+    let body = codings
+      .iter()
+      .fold(TEXT.as_bytes().to_vec(), |data, coding| {
+        encode(coding, &data)
+      });
+
+    let length = HeaderValue::from(body.len());
+    let mut response = Response::new(body);
+    let coding = path.replace("raw-", "").replace('+', ", ");
+    let _prev = response
+      .headers_mut()
+      .insert(CONTENT_ENCODING, coding.parse().unwrap());
+    let _prev = response.headers_mut().insert(CONTENT_LENGTH, length);
+    Box::pin(async move { Ok(response) })
+  }
+}
+
+
+/// Check that we advertise the supported content codings and decode
+/// responses using any of them.
+#[test(tokio::test)]
+async fn decompression() {
+  let client = Client::new(Server::default());
+
+  for coding in ["br", "deflate", "raw-deflate", "gzip", "zstd", "gzip+br"] {
+    let output = client.issue::<GetText>(&coding).await.unwrap();
+    assert_eq!(output, TEXT, "{coding}");
+  }
+
+  let accepted = client.transport().accepted.lock().unwrap();
+  assert_eq!(accepted.len(), 6);
+  assert!(accepted
+    .iter()
+    .all(|value| value.as_deref() == Some("zstd, br, gzip, deflate")));
+}
+
+/// Check that explicitly set `Accept-Encoding` headers are honored and
+/// that responses using unsupported codings are passed through.
+#[test(tokio::test)]
+async fn identity() {
+  let client = Client::new(Server::default());
+
+  let output = client.issue::<GetIdentity>(&"identity").await.unwrap();
+  assert_eq!(output, TEXT);
+  let output = client.issue::<GetIdentity>(&"compress").await.unwrap();
+  assert_eq!(output, TEXT);
+
+  let accepted = client.transport().accepted.lock().unwrap();
+  assert_eq!(
+    *accepted,
+    [Some("identity".to_string()), Some("identity".to_string())]
+  );
+}
+
+/// Check that we report bodies failing to decompress.
+#[test(tokio::test)]
+async fn corrupt_body() {
+  let client = Client::new(Server::default());
+
+  // The plain text is labeled as being gzip encoded, which it is not.
+  let err = client.issue::<GetText>(&"raw-gzip").await.unwrap_err();
+  assert!(matches!(err, ClientError::Decompress(..)), "{err:?}");
+  assert!(
+    err
+      .to_string()
+      .starts_with("failed to decompress response body: "),
+    "{err}"
+  );
+}
+
+/// Check that bodies decompressing to more than the configured limit
+/// are reported as errors.
+#[test(tokio::test)]
+async fn decompression_limit() {
+  let client = Client::new(Server::default()).with_decompression_limit(TEXT.len());
+  for coding in ["br", "deflate", "raw-deflate", "gzip", "zstd"] {
+    let output = client.issue::<GetText>(&coding).await.unwrap();
+    assert_eq!(output, TEXT, "{coding}");
+  }
+
+  let client = Client::new(Server::default()).with_decompression_limit(TEXT.len() - 1);
+  for coding in ["br", "deflate", "raw-deflate", "gzip", "zstd", "gzip+br"] {
+    let err = client.issue::<GetText>(&coding).await.unwrap_err();
+    assert!(matches!(err, ClientError::Decompress(..)), "{err:?}");
+    assert!(
+      err.to_string().ends_with(&format!(
+        "decompressed body exceeds limit of {} bytes",
+        TEXT.len() - 1
+      )),
+      "{coding}: {err}"
+    );
+  }
+}
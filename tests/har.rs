@@ -0,0 +1,164 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+use std::env::temp_dir;
+use std::fs::read;
+use std::fs::remove_file;
+use std::process::id;
+
+use http::header::AUTHORIZATION;
+use http::header::CONTENT_TYPE;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use http_endpoint::har::Recorder;
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::Transport;
+
+use serde_json::Value;
+
+use test_log::test;
+
+
+/// A transport responding with the request's body, or a binary blob if
+/// it is empty.
+struct Echo;
+
+impl Transport for Echo {
+  type Error = Infallible;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let (body, content_type) = if request.body().is_empty() {
+      (vec![0xde, 0xad, 0xbe, 0xef], "application/octet-stream")
+    } else {
+      (request.body().to_vec(), "text/plain")
+    };
+    let response = Response::builder()
+      .status(StatusCode::CREATED)
+      .header(CONTENT_TYPE, content_type)
+      .body(body)
+      .unwrap();
+    Box::pin(async move { Ok(response) })
+  }
+}
+
+
+/// Check that requests and responses are recorded as HAR entries.
+#[test(tokio::test)]
+async fn record_entries() {
+  let recorder = Recorder::new(Echo).with_body_limit(4);
+  let request = Request::post("https://example.com/orders?dry_run=true&note=x")
+    .header(CONTENT_TYPE, "text/plain")
+    .header(AUTHORIZATION, "Bearer secret")
+    .body(Bytes::Borrowed(b"hello world"))
+    .unwrap();
+  let response = recorder.send(request).await.unwrap();
+  // The response itself is passed through unmodified.
+  assert_eq!(response.body(), b"hello world");
+
+  let request = Request::get("https://example.com/blob")
+    .body(Bytes::Borrowed(&[]))
+    .unwrap();
+  let _response = recorder.send(request).await.unwrap();
+
+  let request = Request::put("https://example.com/blob")
+    .body(Bytes::Borrowed(&[0xff, 0x00]))
+    .unwrap();
+  let _response = recorder.send(request).await.unwrap();
+
+  let har = recorder.to_json();
+  let log = &har["log"];
+  assert_eq!(log["version"], "1.2");
+  assert_eq!(log["creator"]["name"], "http-endpoint");
+
+  let entries = log["entries"].as_array().unwrap();
+  assert_eq!(entries.len(), 3);
+
+  let entry = &entries[0];
+  assert!(entry["startedDateTime"].as_str().unwrap().ends_with('Z'));
+  assert!(entry["time"].as_f64().unwrap() >= 0.0);
+  assert_eq!(entry["timings"]["wait"], entry["time"]);
+
+  let request = &entry["request"];
+  assert_eq!(request["method"], "POST");
+  assert_eq!(
+    request["url"],
+    "https://example.com/orders?dry_run=true&note=x"
+  );
+  assert_eq!(request["httpVersion"], "HTTP/1.1");
+  assert_eq!(request["queryString"][0]["name"], "dry_run");
+  assert_eq!(request["queryString"][0]["value"], "true");
+  assert_eq!(request["queryString"][1]["name"], "note");
+  assert_eq!(request["headers"][1]["name"], "authorization");
+  assert_eq!(request["headers"][1]["value"], "<redacted>");
+  assert_eq!(request["bodySize"], 11);
+  assert_eq!(request["postData"]["mimeType"], "text/plain");
+  assert_eq!(request["postData"]["text"], "hell");
+
+  let response = &entry["response"];
+  assert_eq!(response["status"], 201);
+  assert_eq!(response["statusText"], "Created");
+  assert_eq!(response["bodySize"], 11);
+  assert_eq!(response["content"]["size"], 11);
+  assert_eq!(response["content"]["text"], "hell");
+  assert_eq!(response["content"]["comment"], "truncated to 4 bytes");
+
+  let entry = &entries[1];
+  assert_eq!(entry["request"].get("postData"), None);
+  let content = &entry["response"]["content"];
+  assert_eq!(content["mimeType"], "application/octet-stream");
+  assert_eq!(content["text"], "3q2+7w==");
+  assert_eq!(content["encoding"], "base64");
+  assert_eq!(content.get("comment"), None);
+
+  let post_data = &entries[2]["request"]["postData"];
+  assert_eq!(post_data["text"], "/wA=");
+  assert_eq!(post_data["encoding"], "base64");
+  assert_eq!(entries[0]["request"]["postData"].get("encoding"), None);
+}
+
+/// Check that truncation of textual bodies does not split characters.
+#[test(tokio::test)]
+async fn truncate_text() {
+  let recorder = Recorder::new(Echo).with_body_limit(3);
+  let request = Request::post("https://example.com/greeting")
+    .header(CONTENT_TYPE, "text/plain")
+    .body(Bytes::Borrowed("grüße".as_bytes()))
+    .unwrap();
+  let _response = recorder.send(request).await.unwrap();
+
+  let har = recorder.to_json();
+  let content = &har["log"]["entries"][0]["response"]["content"];
+  assert_eq!(content["size"], 7);
+  assert_eq!(content["text"], "gr");
+  assert_eq!(content.get("encoding"), None);
+  assert_eq!(content["comment"], "truncated to 2 bytes");
+}
+
+/// Check that we can write recorded traffic to a `.har` file.
+#[test(tokio::test)]
+async fn save_archive() {
+  let recorder = Recorder::new(Echo).with_redaction(false);
+  let request = Request::put("https://example.com/")
+    .header(AUTHORIZATION, "Bearer secret")
+    .body(Bytes::Borrowed(b"data"))
+    .unwrap();
+  let _response = recorder.send(request).await.unwrap();
+
+  let path = temp_dir().join(format!("http-endpoint-{}.har", id()));
+  let () = recorder.save(&path).unwrap();
+  let data = read(&path).unwrap();
+  let () = remove_file(&path).unwrap();
+
+  let har = serde_json::from_slice::<Value>(&data).unwrap();
+  // Floating point values may not survive a round trip through JSON
+  // unscathed, so compare against the equally round tripped archive.
+  let expected = serde_json::to_string(&recorder.to_json()).unwrap();
+  let expected = serde_json::from_str::<Value>(&expected).unwrap();
+  assert_eq!(har, expected);
+  let headers = &har["log"]["entries"][0]["request"]["headers"];
+  assert_eq!(headers[0]["value"], "Bearer secret");
+}
@@ -0,0 +1,352 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::io::Read as _;
+use std::io::Write as _;
+use std::net::TcpStream;
+
+use http::Method;
+use http::Request;
+use http::StatusCode;
+
+use http_endpoint::mock::Error;
+use http_endpoint::mock::MockServer;
+use http_endpoint::server::Incoming;
+use http_endpoint::server::Serve;
+use http_endpoint::Bytes;
+use http_endpoint::Client;
+use http_endpoint::ClientError;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+use http_endpoint::Transport as _;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use serde_json::Error as JsonError;
+
+use test_log::test;
+
+use thiserror::Error;
+
+
+#[derive(Debug, Error)]
+enum ConversionError {
+  #[error("invalid order ID")]
+  Id,
+  #[error(transparent)]
+  Json(#[from] JsonError),
+}
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("{0}")]
+struct ApiError(String);
+
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Order {
+  id: u64,
+  quantity: u64,
+}
+
+
+EndpointDef! {
+  GetOrder(u64),
+  Ok => Order, [
+    /* 200 */ OK,
+  ],
+  Err => GetOrderError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => ConversionError,
+  ApiErr => ApiError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(id: &Self::Input) -> Str {
+    format!("/orders/{id}").into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders/{id}")
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(serde_json::from_slice(body)?)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(ApiError(String::from_utf8_lossy(body).into_owned()))
+  }
+}
+
+impl Serve for GetOrder {
+  fn decode(request: &Incoming<'_>) -> Result<Self::Input, Self::ConversionError> {
+    request
+      .param("id")
+      .and_then(|id| id.parse().ok())
+      .ok_or(ConversionError::Id)
+  }
+
+  fn encode(output: &Self::Output) -> Result<Vec<u8>, Self::ConversionError> {
+    Ok(serde_json::to_vec(output)?)
+  }
+
+  fn encode_err(error: &Self::ApiError) -> Vec<u8> {
+    error.0.clone().into_bytes()
+  }
+}
+
+
+EndpointDef! {
+  CreateOrder(Order),
+  Ok => Order, [
+    /* 201 */ CREATED,
+  ],
+  Err => CreateOrderError, [
+    /* 422 */ UNPROCESSABLE_ENTITY => Invalid,
+  ],
+  ConversionErr => ConversionError,
+  ApiErr => ApiError,
+
+  fn method() -> Method {
+    Method::POST
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/orders".into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders")
+  }
+
+  fn body(input: &Self::Input) -> Result<Option<Bytes>, Self::ConversionError> {
+    Ok(Some(Bytes::Owned(serde_json::to_vec(input)?)))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(serde_json::from_slice(body)?)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(ApiError(String::from_utf8_lossy(body).into_owned()))
+  }
+}
+
+impl Serve for CreateOrder {
+  fn decode(request: &Incoming<'_>) -> Result<Self::Input, Self::ConversionError> {
+    Ok(serde_json::from_slice(request.body())?)
+  }
+
+  fn encode(output: &Self::Output) -> Result<Vec<u8>, Self::ConversionError> {
+    Ok(serde_json::to_vec(output)?)
+  }
+
+  fn encode_err(error: &Self::ApiError) -> Vec<u8> {
+    error.0.clone().into_bytes()
+  }
+}
+
+
+const ORDER: Order = Order {
+  id: 42,
+  quantity: 10,
+};
+
+
+/// Check that the mock server answers requests with the configured
+/// replies.
+#[test(tokio::test)]
+async fn canned_replies() {
+  let server = MockServer::new()
+    .with_output::<GetOrder>(&ORDER)
+    .unwrap()
+    .with_error::<CreateOrder>(
+      StatusCode::UNPROCESSABLE_ENTITY,
+      &ApiError("insufficient funds".to_string()),
+    )
+    .unwrap();
+  let client = Client::new(server);
+
+  let order = client.issue::<GetOrder>(&42).await.unwrap();
+  assert_eq!(order, ORDER);
+
+  let err = client.issue::<CreateOrder>(&ORDER).await.unwrap_err();
+  match err {
+    ClientError::Endpoint(CreateOrderError::Invalid(Ok(err))) => {
+      assert_eq!(err.0, "insufficient funds")
+    },
+    _ => panic!("unexpected error: {err:?}"),
+  }
+
+  assert_eq!(
+    client.transport().served(),
+    ["mock::GetOrder", "mock::CreateOrder"]
+  );
+  assert!(client.transport().mismatches().is_empty());
+
+  // Replies can only use statuses declared for the endpoint.
+  let err = MockServer::new()
+    .with_error::<GetOrder>(StatusCode::GONE, &ApiError(String::new()))
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    Error::Status("mock::GetOrder", StatusCode::GONE)
+  ));
+  assert_eq!(
+    err.to_string(),
+    "status 410 Gone is not declared for endpoint mock::GetOrder"
+  );
+}
+
+/// Check that requests not conforming to any endpoint get reported.
+#[test(tokio::test)]
+async fn report_mismatches() {
+  let server = MockServer::new()
+    .with_output::<GetOrder>(&ORDER)
+    .unwrap()
+    .with_endpoint::<CreateOrder>();
+
+  let requests = [
+    (
+      Request::get("/orders/abc"),
+      &b""[..],
+      StatusCode::BAD_REQUEST,
+    ),
+    (
+      Request::delete("/orders/1"),
+      b"",
+      StatusCode::METHOD_NOT_ALLOWED,
+    ),
+    (Request::get("/customers"), b"", StatusCode::NOT_FOUND),
+    (Request::post("/orders"), b"{}", StatusCode::BAD_REQUEST),
+    (
+      Request::post("/orders"),
+      br#"{"id":1,"quantity":2}"#,
+      StatusCode::NOT_IMPLEMENTED,
+    ),
+  ];
+  for (builder, body, status) in requests {
+    let request = builder.body(Bytes::Borrowed(body)).unwrap();
+    let response = server.send(request).await.unwrap();
+    assert_eq!(response.status(), status);
+  }
+
+  let mismatches = server
+    .mismatches()
+    .into_iter()
+    .map(|mismatch| mismatch.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    mismatches[0],
+    "GET /orders/abc: endpoint mock::GetOrder: invalid order ID"
+  );
+  assert_eq!(
+    mismatches[1],
+    "DELETE /orders/1: endpoint mock::GetOrder: unexpected request method DELETE"
+  );
+  assert_eq!(mismatches[2], "GET /customers: no matching endpoint");
+  assert!(
+    mismatches[3].starts_with("POST /orders: endpoint mock::CreateOrder: missing field"),
+    "{}",
+    mismatches[3]
+  );
+  assert_eq!(
+    mismatches[4],
+    "POST /orders: no reply configured for endpoint mock::CreateOrder"
+  );
+  assert!(server.served().is_empty());
+}
+
+/// Check that the mock server can serve requests over TCP.
+#[test]
+fn listen() {
+  let server = MockServer::new().with_output::<GetOrder>(&ORDER).unwrap();
+  let listening = server.listen().unwrap();
+  let addr = listening.addr();
+
+  let mut stream = TcpStream::connect(addr).unwrap();
+  let () = stream
+    .write_all(b"GET /orders/42 HTTP/1.1\r\nhost: localhost\r\n\r\n")
+    .unwrap();
+  let mut response = String::new();
+  let _count = stream.read_to_string(&mut response).unwrap();
+
+  assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+  assert!(
+    response.ends_with("\r\n\r\n{\"id\":42,\"quantity\":10}"),
+    "{response}"
+  );
+  assert!(listening.base_url().starts_with("http://127.0.0.1:"));
+
+  let () = drop(listening);
+  assert_eq!(server.served(), ["mock::GetOrder"]);
+  assert!(TcpStream::connect(addr).is_err());
+}
+
+/// Check that oversized requests are rejected and that idle
+/// connections do not keep the server from shutting down.
+#[test]
+fn listen_limits() {
+  let server = MockServer::new().with_output::<GetOrder>(&ORDER).unwrap();
+  let listening = server.listen().unwrap();
+  let addr = listening.addr();
+
+  let _idle = TcpStream::connect(addr).unwrap();
+
+  let mut stream = TcpStream::connect(addr).unwrap();
+  let () = stream
+    .write_all(b"POST /orders HTTP/1.1\r\ncontent-length: 1000000000\r\n\r\n")
+    .unwrap();
+  let mut response = String::new();
+  let _count = stream.read_to_string(&mut response).unwrap();
+  assert!(
+    response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"),
+    "{response}"
+  );
+
+  let () = drop(listening);
+  assert!(server.served().is_empty());
+}
+
+/// Check that requests we can't interpret are rejected.
+#[test]
+fn listen_invalid_requests() {
+  let server = MockServer::new().with_output::<GetOrder>(&ORDER).unwrap();
+  let listening = server.listen().unwrap();
+  let addr = listening.addr();
+
+  let requests = [
+    (
+      &b"POST /orders HTTP/1.1\r\ntransfer-encoding: chunked\r\n\r\n2\r\n{}\r\n0\r\n\r\n"[..],
+      "HTTP/1.1 501 Not Implemented\r\n",
+      "transfer encoding chunked is not supported",
+    ),
+    (
+      &b"GET /orders/42 HTTP/1.1\r\nhost localhost\r\n\r\n"[..],
+      "HTTP/1.1 400 Bad Request\r\n",
+      "malformed header line: host localhost",
+    ),
+    (
+      &b"POST /orders HTTP/1.1\r\ncontent-length: -1\r\n\r\n"[..],
+      "HTTP/1.1 400 Bad Request\r\n",
+      "invalid content length: -1",
+    ),
+  ];
+
+  for (request, status, message) in requests {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let () = stream.write_all(request).unwrap();
+    let mut response = String::new();
+    let _count = stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with(status), "{response}");
+    assert!(response.ends_with(message), "{response}");
+  }
+
+  let () = drop(listening);
+  assert!(server.served().is_empty());
+}
@@ -0,0 +1,319 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use http::header::AUTHORIZATION;
+use http::Method;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use http_endpoint::oauth2::Error as OAuth2Error;
+use http_endpoint::oauth2::OAuth2;
+use http_endpoint::oauth2::TokenEndpointError;
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::Client;
+use http_endpoint::ClientError;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+use http_endpoint::Transport;
+
+use test_log::test;
+
+use thiserror::Error;
+
+use tokio::time::advance;
+
+
+const TOKEN_URL: &str = "https://auth.example.com/oauth/token";
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("an unspecified error was encountered")]
+struct NoError;
+
+
+EndpointDef! {
+  GetResource(()),
+  Ok => String, [
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /* 401 */ UNAUTHORIZED => Unauthorized,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("https://api.example.com".into())
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/resource".into()
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    String::from_utf8(body.to_vec()).map_err(|_| NoError)
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+#[derive(Debug, Default)]
+struct State {
+  /// The number of tokens issued so far.
+  issued: usize,
+  /// The currently valid access tokens.
+  tokens: HashSet<String>,
+  /// The currently valid refresh tokens.
+  refresh_tokens: HashSet<String>,
+  /// The bodies of all requests to the token endpoint.
+  token_requests: Vec<String>,
+  /// The `Authorization` headers of all requests to the API.
+  api_requests: Vec<Option<String>>,
+  /// Whether to reject all API requests.
+  reject: bool,
+}
+
+
+/// A stand-in for an OAuth2 protected API along with its authorization
+/// server, answering requests in-process.
+#[derive(Debug, Default)]
+struct Server {
+  state: Mutex<State>,
+}
+
+impl Server {
+  fn with_refresh_token(refresh_token: &str) -> Self {
+    let server = Self::default();
+    server
+      .state
+      .lock()
+      .unwrap()
+      .refresh_tokens
+      .insert(refresh_token.to_string());
+    server
+  }
+
+  fn token(&self, body: &str) -> (StatusCode, String) {
+    let mut state = self.state.lock().unwrap();
+    let () = state.token_requests.push(body.to_string());
+
+    let params = body
+      .split('&')
+      .filter_map(|pair| pair.split_once('='))
+      .collect::<Vec<_>>();
+    let param = |name| {
+      params
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| *value)
+    };
+
+    let valid = match param("grant_type") {
+      Some("client_credentials") => param("client_secret") == Some("secret"),
+      Some("refresh_token") => {
+        let refresh_token = param("refresh_token").unwrap_or_default();
+        state.refresh_tokens.remove(refresh_token)
+      },
+      _ => false,
+    };
+    if !valid {
+      let body = r#"{"error":"invalid_client","error_description":"bad credentials"}"#;
+      return (StatusCode::UNAUTHORIZED, body.to_string());
+    }
+
+    state.issued += 1;
+    let token = format!("token-{}", state.issued);
+    let refresh_token = format!("refresh-{}", state.issued);
+    let _inserted = state.tokens.insert(token.clone());
+    let _inserted = state.refresh_tokens.insert(refresh_token.clone());
+
+    let body = format!(
+      r#"{{"access_token":"{token}","token_type":"Bearer","expires_in":60,"refresh_token":"{refresh_token}"}}"#
+    );
+    (StatusCode::OK, body)
+  }
+
+  fn api(&self, authorization: Option<&str>) -> (StatusCode, String) {
+    let mut state = self.state.lock().unwrap();
+    let () = state.api_requests.push(authorization.map(str::to_string));
+
+    let token = authorization.and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+      Some(token) if !state.reject && state.tokens.contains(token) => {
+        (StatusCode::OK, format!("hello {token}"))
+      },
+      _ => (StatusCode::UNAUTHORIZED, String::new()),
+    }
+  }
+}
+
+impl Transport for Server {
+  type Error = Infallible;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let (status, body) = if request.uri() == TOKEN_URL {
+      assert_eq!(request.method(), Method::POST);
+      self.token(&String::from_utf8_lossy(request.body()))
+    } else {
+      let authorization = request
+        .headers()
+        .get(AUTHORIZATION)
+        .map(|value| value.to_str().unwrap());
+      self.api(authorization)
+    };
+
+    let response = Response::builder()
+      .status(status)
+      .body(body.into_bytes())
+      .unwrap();
+    Box::pin(async move { Ok(response) })
+  }
+}
+
+
+fn client(server: Server) -> Client<OAuth2<Server>> {
+  let transport = OAuth2::client_credentials(server, TOKEN_URL, "id", "secret")
+    .unwrap()
+    .with_scope("read write");
+  Client::new(transport)
+}
+
+
+/// Check that tokens are obtained once and then cached.
+#[test(tokio::test(start_paused = true))]
+async fn token_caching() {
+  let client = client(Server::default());
+
+  let output = client.issue::<GetResource>(&()).await.unwrap();
+  assert_eq!(output, "hello token-1");
+  let output = client.issue::<GetResource>(&()).await.unwrap();
+  assert_eq!(output, "hello token-1");
+
+  let state = client.transport().transport().state.lock().unwrap();
+  assert_eq!(
+    state.token_requests,
+    ["grant_type=client_credentials&scope=read%20write&client_id=id&client_secret=secret"]
+  );
+  assert_eq!(
+    state.api_requests,
+    [
+      Some("Bearer token-1".to_string()),
+      Some("Bearer token-1".to_string())
+    ]
+  );
+}
+
+/// Check that tokens get refreshed before they expire.
+#[test(tokio::test(start_paused = true))]
+async fn proactive_refresh() {
+  let client = client(Server::default());
+
+  let output = client.issue::<GetResource>(&()).await.unwrap();
+  assert_eq!(output, "hello token-1");
+
+  // The token is valid for 60s and refreshed 30s before it expires.
+  advance(Duration::from_secs(29)).await;
+  let output = client.issue::<GetResource>(&()).await.unwrap();
+  assert_eq!(output, "hello token-1");
+
+  advance(Duration::from_secs(1)).await;
+  let output = client.issue::<GetResource>(&()).await.unwrap();
+  assert_eq!(output, "hello token-2");
+
+  let state = client.transport().transport().state.lock().unwrap();
+  assert_eq!(state.token_requests.len(), 2);
+  assert_eq!(
+    state.token_requests[1],
+    "grant_type=refresh_token&refresh_token=refresh-1&client_id=id&client_secret=secret"
+  );
+}
+
+/// Check that a request rejected with status 401 is retried once with
+/// a fresh token.
+#[test(tokio::test(start_paused = true))]
+async fn retry_unauthorized() {
+  let client = client(Server::default());
+  let _output = client.issue::<GetResource>(&()).await.unwrap();
+
+  // Revoke all tokens, as if the server had restarted.
+  let () = client
+    .transport()
+    .transport()
+    .state
+    .lock()
+    .unwrap()
+    .tokens
+    .clear();
+
+  let output = client.issue::<GetResource>(&()).await.unwrap();
+  assert_eq!(output, "hello token-2");
+
+  {
+    let mut state = client.transport().transport().state.lock().unwrap();
+    assert_eq!(
+      state.api_requests,
+      [
+        Some("Bearer token-1".to_string()),
+        Some("Bearer token-1".to_string()),
+        Some("Bearer token-2".to_string()),
+      ]
+    );
+    state.reject = true;
+  }
+
+  // We only ever retry once.
+  let err = client.issue::<GetResource>(&()).await.unwrap_err();
+  assert!(
+    matches!(err, ClientError::Endpoint(GetError::Unauthorized(..))),
+    "{err:?}"
+  );
+  let state = client.transport().transport().state.lock().unwrap();
+  assert_eq!(state.api_requests.len(), 5);
+  assert_eq!(state.token_requests.len(), 3);
+}
+
+/// Check that errors reported by the token endpoint are surfaced.
+#[test(tokio::test(start_paused = true))]
+async fn token_error() {
+  let transport = OAuth2::client_credentials(Server::default(), TOKEN_URL, "id", "wrong").unwrap();
+  let client = Client::new(transport);
+
+  let err = client.issue::<GetResource>(&()).await.unwrap_err();
+  match err {
+    ClientError::Transport(OAuth2Error::Token(TokenEndpointError::Unauthorized(Ok(err)))) => {
+      assert_eq!(err.error, "invalid_client");
+      assert_eq!(err.error_description.as_deref(), Some("bad credentials"));
+    },
+    _ => panic!("unexpected error: {err:?}"),
+  }
+
+  let state = client.transport().transport().state.lock().unwrap();
+  assert!(state.api_requests.is_empty());
+}
+
+/// Check that we can obtain tokens using the refresh token grant.
+#[test(tokio::test(start_paused = true))]
+async fn refresh_token_grant() {
+  let server = Server::with_refresh_token("initial");
+  let transport = OAuth2::refresh_token(server, TOKEN_URL, "id", None, "initial").unwrap();
+  let client = Client::new(transport);
+
+  let output = client.issue::<GetResource>(&()).await.unwrap();
+  assert_eq!(output, "hello token-1");
+
+  let state = client.transport().transport().state.lock().unwrap();
+  assert_eq!(
+    state.token_requests,
+    ["grant_type=refresh_token&refresh_token=initial&client_id=id"]
+  );
+}
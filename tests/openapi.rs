@@ -0,0 +1,240 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use http::Method;
+
+use http_endpoint::openapi::Document;
+use http_endpoint::openapi::Schema;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+
+use serde_json::json;
+use serde_json::Value;
+
+use thiserror::Error;
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("an unspecified error was encountered")]
+struct NoError;
+
+impl Schema for NoError {
+  fn schema() -> Value {
+    json!({"type": "object", "properties": {"message": {"type": "string"}}})
+  }
+}
+
+#[derive(Debug)]
+struct Order;
+
+impl Schema for Order {
+  fn schema() -> Value {
+    json!({"type": "object", "properties": {"id": {"type": "integer"}}})
+  }
+}
+
+
+EndpointDef! {
+  /// List orders.
+  ///
+  /// Orders can be filtered by status.
+  ListOrders(Option<String>),
+  Ok => Vec<Order>, [
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /* 400 */ BAD_REQUEST => InvalidStatus,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("https://orders.example.com".into())
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/orders".into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders?status={status}")
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(Vec::new())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+EndpointDef! {
+  /// Create an order.
+  CreateOrder(Order),
+  Ok => Order, [
+    /* 201 */ CREATED,
+  ],
+  Err => CreateError, [],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("https://orders.example.com".into())
+  }
+
+  fn method() -> Method {
+    Method::POST
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/orders".into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders")
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(Order)
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+EndpointDef! {
+  GetOrder(u64),
+  Ok => Order, [
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn path(id: &Self::Input) -> Str {
+    format!("/orders/{}", id).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders/{id}")
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(Order)
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+EndpointDef! {
+  Untemplated(()),
+  Ok => (), [
+    /* 200 */ OK,
+  ],
+  Err => UntemplatedError, [],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn path(_: &Self::Input) -> Str {
+    "/untemplated".into()
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+/// Check that we generate the expected OpenAPI document for the
+/// registered endpoints.
+#[test]
+fn generate() {
+  let document = Document::from_registry("Orders", "1.0.0")
+    .with_output_schema::<ListOrders>()
+    .with_error_schema::<ListOrders>()
+    .with_input_schema::<CreateOrder>()
+    .with_output_schema::<CreateOrder>()
+    .to_json()
+    .unwrap();
+
+  let expected = json!({
+    "openapi": "3.1.0",
+    "info": {
+      "title": "Orders",
+      "version": "1.0.0",
+    },
+    "paths": {
+      "/orders": {
+        "get": {
+          "operationId": "ListOrders",
+          "servers": [{"url": "https://orders.example.com"}],
+          "summary": "List orders.",
+          "description": "List orders.\n\nOrders can be filtered by status.",
+          "parameters": [{
+            "name": "status",
+            "in": "query",
+            "required": false,
+            "schema": {"type": "string"},
+          }],
+          "responses": {
+            "200": {
+              "description": "OK",
+              "content": {"application/json": {"schema": {
+                "type": "array",
+                "items": Order::schema(),
+              }}},
+            },
+            "400": {
+              "description": "InvalidStatus",
+              "content": {"application/json": {"schema": NoError::schema()}},
+            },
+          },
+        },
+        "post": {
+          "operationId": "CreateOrder",
+          "servers": [{"url": "https://orders.example.com"}],
+          "summary": "Create an order.",
+          "description": "Create an order.",
+          "requestBody": {
+            "content": {"application/json": {"schema": Order::schema()}},
+          },
+          "responses": {
+            "201": {
+              "description": "Created",
+              "content": {"application/json": {"schema": Order::schema()}},
+            },
+          },
+        },
+      },
+      "/orders/{id}": {
+        "get": {
+          "operationId": "GetOrder",
+          "parameters": [{
+            "name": "id",
+            "in": "path",
+            "required": true,
+            "schema": {"type": "string"},
+          }],
+          "responses": {
+            "200": {"description": "OK"},
+            "404": {"description": "NotFound"},
+          },
+        },
+      },
+    },
+  });
+  assert_eq!(document, expected);
+}
@@ -0,0 +1,345 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+use std::sync::Mutex;
+
+use http::header::LINK;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use http::Uri;
+
+use http_endpoint::paginate::Paginated;
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::Client;
+use http_endpoint::ClientError;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+use http_endpoint::Transport;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use serde_json::Error as JsonError;
+
+use test_log::test;
+
+use thiserror::Error;
+
+
+/// The items served by the [`Store`].
+const ITEMS: [u64; 7] = [10, 11, 12, 13, 14, 15, 16];
+
+/// The number of items per page for endpoints not specifying one.
+const PAGE_SIZE: usize = 3;
+
+
+#[derive(Debug, Error)]
+#[error("no error")]
+struct NoError;
+
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct CursorPage {
+  items: Vec<u64>,
+  next: Option<usize>,
+}
+
+
+/// Retrieve the value of a query parameter of a URI.
+fn query_param(uri: &Uri, name: &str) -> Option<usize> {
+  uri
+    .query()?
+    .split('&')
+    .filter_map(|pair| pair.split_once('='))
+    .find(|(key, _)| *key == name)
+    .and_then(|(_, value)| value.parse().ok())
+}
+
+
+/// A transport serving pages of [`ITEMS`] in different fashions.
+#[derive(Default)]
+struct Store {
+  /// The URIs of all requests sent so far.
+  requests: Mutex<Vec<String>>,
+}
+
+impl Transport for Store {
+  type Error = Infallible;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let uri = request.uri();
+    let () = self.requests.lock().unwrap().push(uri.to_string());
+
+    let page = |start: usize, len: usize| {
+      let start = start.min(ITEMS.len());
+      let end = (start + len).min(ITEMS.len());
+      ITEMS[start..end].to_vec()
+    };
+
+    let mut response = Response::new(Vec::new());
+    match uri.path() {
+      "/offset" => {
+        let offset = query_param(uri, "offset").unwrap_or(0);
+        let limit = query_param(uri, "limit").unwrap_or(PAGE_SIZE);
+        *response.body_mut() = serde_json::to_vec(&page(offset, limit)).unwrap();
+      },
+      "/cursor" => {
+        let cursor = query_param(uri, "cursor").unwrap_or(0);
+        let next = cursor + PAGE_SIZE;
+        let page = CursorPage {
+          items: page(cursor, PAGE_SIZE),
+          next: (next < ITEMS.len()).then_some(next),
+        };
+        *response.body_mut() = serde_json::to_vec(&page).unwrap();
+      },
+      "/link" => {
+        let number = query_param(uri, "page").unwrap_or(1);
+        let start = (number - 1) * PAGE_SIZE;
+        if start + PAGE_SIZE < ITEMS.len() {
+          let link = format!(
+            r#"</link?page=1>; rel="first", </link?page={}>; rel="next""#,
+            number + 1
+          );
+          let _prev = response.headers_mut().insert(LINK, link.parse().unwrap());
+        }
+        *response.body_mut() = serde_json::to_vec(&page(start, PAGE_SIZE)).unwrap();
+      },
+      _ => *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR,
+    }
+    Box::pin(async move { Ok(response) })
+  }
+}
+
+
+EndpointDef! {
+  ListOffset((usize, usize)),
+  Ok => Vec<u64>, [
+    /* 200 */ OK,
+  ],
+  Err => ListOffsetError, [],
+  ConversionErr => JsonError,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/offset".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(format!("offset={}&limit={}", input.0, input.1).into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    serde_json::from_slice(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+impl Paginated for ListOffset {
+  type Item = u64;
+
+  fn items(output: Self::Output) -> Vec<Self::Item> {
+    output
+  }
+
+  fn next((offset, limit): &Self::Input, output: &Self::Output) -> Option<Self::Input> {
+    (output.len() == *limit).then_some((offset + limit, *limit))
+  }
+}
+
+
+EndpointDef! {
+  ListCursor(usize),
+  Ok => CursorPage, [
+    /* 200 */ OK,
+  ],
+  Err => ListCursorError, [],
+  ConversionErr => JsonError,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/cursor".into()
+  }
+
+  fn query(cursor: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(format!("cursor={cursor}").into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    serde_json::from_slice(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+impl Paginated for ListCursor {
+  type Item = u64;
+
+  fn items(output: Self::Output) -> Vec<Self::Item> {
+    output.items
+  }
+
+  fn next(_: &Self::Input, output: &Self::Output) -> Option<Self::Input> {
+    output.next
+  }
+}
+
+
+EndpointDef! {
+  ListLink(usize),
+  Ok => Vec<u64>, [
+    /* 200 */ OK,
+  ],
+  Err => ListLinkError, [],
+  ConversionErr => JsonError,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("http://localhost".into())
+  }
+
+  fn path(page: &Self::Input) -> Str {
+    // Request an invalid path for page 0, to provoke an error.
+    if *page == 0 {
+      "/invalid".into()
+    } else {
+      "/link".into()
+    }
+  }
+
+  fn query(page: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(format!("page={page}").into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    serde_json::from_slice(body)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Err(body.to_vec())
+  }
+}
+
+impl Paginated for ListLink {
+  type Item = u64;
+
+  fn items(output: Self::Output) -> Vec<Self::Item> {
+    output
+  }
+
+  fn next_from_link(_: &Self::Input, link: &Uri) -> Option<Self::Input> {
+    query_param(link, "page")
+  }
+}
+
+
+/// Check that we can paginate using offsets derived from the input.
+#[test(tokio::test)]
+async fn offset_pagination() {
+  let client = Client::new(Store::default());
+  let mut pages = client.paginate::<ListOffset>((0, PAGE_SIZE));
+
+  let mut result = Vec::new();
+  while let Some(page) = pages.next().await {
+    let () = result.push(page.unwrap());
+  }
+  assert_eq!(result, [vec![10, 11, 12], vec![13, 14, 15], vec![16]]);
+  assert!(pages.next().await.is_none());
+
+  // An exactly filled last page requires an additional request to
+  // detect the end.
+  let mut pages = client.paginate::<ListOffset>((1, PAGE_SIZE)).items();
+  let mut items = Vec::new();
+  while let Some(item) = pages.next().await {
+    let () = items.push(item.unwrap());
+  }
+  assert_eq!(items, ITEMS[1..]);
+  assert_eq!(
+    client.transport().requests.lock().unwrap()[3..],
+    [
+      "http://localhost/offset?offset=1&limit=3",
+      "http://localhost/offset?offset=4&limit=3",
+      "http://localhost/offset?offset=7&limit=3",
+    ]
+  );
+}
+
+/// Check that we can paginate using cursors contained in the output
+/// and that we honor the maximum number of pages.
+#[test(tokio::test)]
+async fn cursor_pagination() {
+  let client = Client::new(Store::default());
+  let mut items = client.paginate::<ListCursor>(0).items();
+
+  let mut result = Vec::new();
+  while let Some(item) = items.next().await {
+    let () = result.push(item.unwrap());
+  }
+  assert_eq!(result, ITEMS);
+
+  let mut pages = client.paginate::<ListCursor>(0).with_max_pages(2);
+  let page = pages.next().await.unwrap().unwrap();
+  assert_eq!(page.next, Some(3));
+  let page = pages.next().await.unwrap().unwrap();
+  assert_eq!(page.next, Some(6));
+  assert!(pages.next().await.is_none());
+  assert_eq!(client.transport().requests.lock().unwrap().len(), 5);
+}
+
+/// Check that we can paginate using `Link` headers from a blocking
+/// context.
+#[test]
+fn link_pagination() {
+  let client = Client::new(Store::default());
+  let items = client
+    .paginate::<ListLink>(1)
+    .items()
+    .blocking()
+    .unwrap()
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+  assert_eq!(items, ITEMS);
+
+  let pages = client
+    .paginate::<ListLink>(2)
+    .blocking()
+    .unwrap()
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+  assert_eq!(pages, [vec![13, 14, 15], vec![16]]);
+}
+
+/// Check that pagination stops after the first error.
+#[test]
+fn pagination_error() {
+  let client = Client::new(Store::default());
+  let mut pages = client.paginate::<ListLink>(0).blocking().unwrap();
+
+  let err = pages.next().unwrap().unwrap_err();
+  assert!(
+    matches!(
+      err,
+      ClientError::Endpoint(ListLinkError::UnexpectedStatus(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        _
+      ))
+    ),
+    "{err:?}"
+  );
+  assert!(pages.next().is_none());
+}
@@ -0,0 +1,156 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use http::Method;
+use http::StatusCode;
+
+use http_endpoint::registry::endpoints;
+use http_endpoint::registry::endpoints_by_base_url;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+
+use thiserror::Error;
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("an unspecified error was encountered")]
+struct NoError;
+
+
+EndpointDef! {
+  /// List all orders.
+  ListOrders(()),
+  Ok => (), [
+    /* 200 */ OK,
+  ],
+  Err => ListError, [],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("https://orders.example.com".into())
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/orders".into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders")
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+EndpointDef! {
+  /// Delete an order.
+  DeleteOrder(u64),
+  Ok => (), [
+    /* 204 */ NO_CONTENT,
+  ],
+  Err => DeleteError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn base_url() -> Option<Str> {
+    Some("https://orders.example.com".into())
+  }
+
+  fn method() -> Method {
+    Method::DELETE
+  }
+
+  fn path(id: &Self::Input) -> Str {
+    format!("/orders/{}", id).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders/{id}")
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+EndpointDef! {
+  GetStatus(()),
+  Ok => (), [
+    /* 200 */ OK,
+  ],
+  Err => GetError, [],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn path(_: &Self::Input) -> Str {
+    "/status".into()
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+/// Check that endpoints defined using `EndpointDef` get registered.
+#[test]
+fn enumerate() {
+  let endpoints = endpoints();
+  let names = endpoints.iter().map(|meta| meta.name).collect::<Vec<_>>();
+  assert_eq!(
+    names,
+    [
+      "registry::DeleteOrder",
+      "registry::GetStatus",
+      "registry::ListOrders"
+    ]
+  );
+
+  let delete = &endpoints[0];
+  assert_eq!(delete.method, Method::DELETE);
+  assert_eq!(delete.path, Some("/orders/{id}"));
+  assert_eq!(delete.ok, &[StatusCode::NO_CONTENT]);
+  assert_eq!(delete.err, &[(StatusCode::NOT_FOUND, "NotFound")]);
+  assert_eq!(delete.description(), "Delete an order.");
+}
+
+/// Check that registered endpoints can be grouped by base URL.
+#[test]
+fn group_by_base_url() {
+  let groups = endpoints_by_base_url();
+
+  let orders = groups
+    .get(&Some("https://orders.example.com".into()))
+    .unwrap()
+    .iter()
+    .map(|meta| meta.name)
+    .collect::<Vec<_>>();
+  assert_eq!(orders, ["registry::DeleteOrder", "registry::ListOrders"]);
+
+  let other = groups
+    .get(&None)
+    .unwrap()
+    .iter()
+    .map(|meta| meta.name)
+    .collect::<Vec<_>>();
+  assert_eq!(other, ["registry::GetStatus"]);
+  assert_eq!(groups.len(), 2);
+}
@@ -0,0 +1,274 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use http::Method;
+use http::Request;
+use http::StatusCode;
+
+use http_endpoint::request;
+use http_endpoint::server::decode;
+use http_endpoint::server::matches;
+use http_endpoint::server::respond;
+use http_endpoint::server::Error;
+use http_endpoint::server::Incoming;
+use http_endpoint::server::Serve;
+use http_endpoint::Bytes;
+use http_endpoint::Endpoint;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use serde_json::Error as JsonError;
+
+use test_log::test;
+
+use thiserror::Error;
+
+
+#[derive(Debug, Error)]
+enum ConversionError {
+  #[error("missing or invalid parameter {0}")]
+  Param(&'static str),
+  #[error(transparent)]
+  Json(#[from] JsonError),
+}
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("{message}")]
+struct ApiError {
+  message: String,
+}
+
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Order {
+  id: u64,
+  symbol: String,
+  quantity: u64,
+}
+
+
+EndpointDef! {
+  GetOrder((u64, bool)),
+  Ok => Order, [
+    /* 200 */ OK,
+  ],
+  Err => GetOrderError, [
+    /* 404 */ NOT_FOUND => NotFound,
+    /* 410 */ GONE => Gone,
+  ],
+  ConversionErr => ConversionError,
+  ApiErr => ApiError,
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/orders/{}", input.0).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders/{id}?details={details}")
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(format!("details={}", input.1).into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(serde_json::from_slice(body)?)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    match String::from_utf8(body.to_vec()) {
+      Ok(message) => Ok(ApiError { message }),
+      Err(err) => Err(err.into_bytes()),
+    }
+  }
+}
+
+impl Serve for GetOrder {
+  fn decode(request: &Incoming<'_>) -> Result<Self::Input, Self::ConversionError> {
+    let id = request
+      .param("id")
+      .and_then(|id| id.parse().ok())
+      .ok_or(ConversionError::Param("id"))?;
+    let details = request
+      .query_param("details")
+      .map(|details| details.parse())
+      .transpose()
+      .map_err(|_| ConversionError::Param("details"))?
+      .unwrap_or_default();
+    Ok((id, details))
+  }
+
+  fn encode(output: &Self::Output) -> Result<Vec<u8>, Self::ConversionError> {
+    Ok(serde_json::to_vec(output)?)
+  }
+
+  fn encode_err(error: &Self::ApiError) -> Vec<u8> {
+    error.message.clone().into_bytes()
+  }
+}
+
+
+EndpointDef! {
+  CreateOrder(Order),
+  Ok => Order, [
+    /* 200 */ OK,
+    /* 201 */ CREATED,
+  ],
+  Err => CreateOrderError, [
+    /* 422 */ UNPROCESSABLE_ENTITY => Invalid,
+  ],
+  ConversionErr => ConversionError,
+  ApiErr => ApiError,
+
+  fn method() -> Method {
+    Method::POST
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/orders".into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders")
+  }
+
+  fn body(input: &Self::Input) -> Result<Option<Bytes>, Self::ConversionError> {
+    Ok(Some(Bytes::Owned(serde_json::to_vec(input)?)))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(serde_json::from_slice(body)?)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(ApiError {
+      message: String::from_utf8_lossy(body).into_owned(),
+    })
+  }
+}
+
+impl Serve for CreateOrder {
+  fn decode(request: &Incoming<'_>) -> Result<Self::Input, Self::ConversionError> {
+    Ok(serde_json::from_slice(request.body())?)
+  }
+
+  fn encode(output: &Self::Output) -> Result<Vec<u8>, Self::ConversionError> {
+    Ok(serde_json::to_vec(output)?)
+  }
+
+  fn encode_err(error: &Self::ApiError) -> Vec<u8> {
+    error.message.clone().into_bytes()
+  }
+
+  fn status(_: &Self::Output) -> StatusCode {
+    StatusCode::CREATED
+  }
+}
+
+
+fn order(id: u64) -> Order {
+  Order {
+    id,
+    symbol: "AAPL".to_string(),
+    quantity: 10,
+  }
+}
+
+/// Handle a request to retrieve an order on the server side.
+fn get_order((id, details): (u64, bool)) -> Result<Order, GetOrderError> {
+  match id {
+    1 if details => Ok(order(1)),
+    1 => Ok(Order {
+      symbol: String::new(),
+      ..order(1)
+    }),
+    2 => Err(GetOrderError::Gone(Ok(ApiError {
+      message: "order 2 was cancelled".to_string(),
+    }))),
+    _ => Err(GetOrderError::NotFound(Err(Vec::new()))),
+  }
+}
+
+
+/// Check that an endpoint can be used for both, the client and the
+/// server side of a request.
+#[test]
+fn round_trip() {
+  let req = request::<GetOrder>(None, &(1, true)).unwrap();
+  assert!(matches::<GetOrder, _>(&req));
+  assert!(!matches::<CreateOrder, _>(&req));
+
+  let input = decode::<GetOrder, _>(&req).unwrap();
+  assert_eq!(input, (1, true));
+  let response = respond::<GetOrder>(get_order(input));
+  assert_eq!(response.status(), StatusCode::OK);
+
+  let output = GetOrder::evaluate(response.status(), response.body()).unwrap();
+  assert_eq!(output, order(1));
+
+  let req = request::<GetOrder>(None, &(2, false)).unwrap();
+  let input = decode::<GetOrder, _>(&req).unwrap();
+  let response = respond::<GetOrder>(get_order(input));
+  assert_eq!(response.status(), StatusCode::GONE);
+
+  let err = GetOrder::evaluate(response.status(), response.body()).unwrap_err();
+  match err {
+    GetOrderError::Gone(Ok(err)) => assert_eq!(err.message, "order 2 was cancelled"),
+    _ => panic!("unexpected error: {err:?}"),
+  }
+
+  let req = request::<GetOrder>(None, &(3, false)).unwrap();
+  let input = decode::<GetOrder, _>(&req).unwrap();
+  let response = respond::<GetOrder>(get_order(input));
+  assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  assert_eq!(response.body(), b"");
+}
+
+/// Check that outputs are reported with the status provided by the
+/// endpoint.
+#[test]
+fn output_status() {
+  let req = request::<CreateOrder>(None, &order(7)).unwrap();
+  let input = decode::<CreateOrder, _>(&req).unwrap();
+  assert_eq!(input, order(7));
+
+  let response = respond::<CreateOrder>(Ok(input));
+  assert_eq!(response.status(), StatusCode::CREATED);
+  let output = CreateOrder::evaluate(response.status(), response.body()).unwrap();
+  assert_eq!(output, order(7));
+
+  let err = CreateOrderError::Conversion(ConversionError::Param("id"));
+  let response = respond::<CreateOrder>(Err(err));
+  assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+/// Check that we reject requests not matching an endpoint.
+#[test]
+fn reject_requests() {
+  let request = Request::delete("/orders/1").body(Vec::new()).unwrap();
+  let err = decode::<GetOrder, _>(&request).unwrap_err();
+  assert!(matches!(err, Error::Method(Method::DELETE)), "{err:?}");
+  assert_eq!(err.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+  let request = Request::get("/orders/1/items").body(Vec::new()).unwrap();
+  let err = decode::<GetOrder, _>(&request).unwrap_err();
+  let response = err.into_response();
+  assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  assert_eq!(response.body(), b"no endpoint at path /orders/1/items");
+
+  let request = Request::get("/orders/abc").body(Vec::new()).unwrap();
+  let err = decode::<GetOrder, _>(&request).unwrap_err();
+  assert_eq!(err.to_string(), "missing or invalid parameter id");
+  assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+
+  let request = Request::post("/orders").body(b"{}".to_vec()).unwrap();
+  let err = decode::<CreateOrder, _>(&request).unwrap_err();
+  assert!(
+    matches!(err, Error::Conversion(ConversionError::Json(..))),
+    "{err:?}"
+  );
+}
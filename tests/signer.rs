@@ -0,0 +1,199 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use http::HeaderName;
+use http::Method;
+use http::Request;
+use http::Response;
+
+use http_endpoint::request;
+use http_endpoint::signer::Encoding;
+use http_endpoint::signer::HmacSigner;
+use http_endpoint::signer::Timestamp;
+use http_endpoint::Auth as _;
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::Client;
+use http_endpoint::ClientError;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+use http_endpoint::Transport;
+
+use test_log::test;
+
+use thiserror::Error;
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("an unspecified error was encountered")]
+struct NoError;
+
+
+EndpointDef! {
+  CreateOrder(String),
+  Ok => (), [
+    /* 200 */ OK,
+  ],
+  Err => CreateOrderError, [
+    /* 401 */ UNAUTHORIZED => Unauthorized,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn method() -> Method {
+    Method::POST
+  }
+
+  fn path(_: &Self::Input) -> Str {
+    "/api/v3/order".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(format!("symbol={input}").into()))
+  }
+
+  fn body(_: &Self::Input) -> Result<Option<Bytes>, Self::ConversionError> {
+    Ok(Some(Bytes::Borrowed(br#"{"side":"BUY","quantity":"0.5"}"#)))
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+/// A transport counting the requests sent through it.
+#[derive(Default)]
+struct Counter(AtomicUsize);
+
+impl Transport for Counter {
+  type Error = Infallible;
+
+  fn send(
+    &self,
+    _request: Request<Bytes>,
+  ) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let _count = self.0.fetch_add(1, Ordering::Relaxed);
+    Box::pin(async { Ok(Response::new(Vec::new())) })
+  }
+}
+
+
+/// The point in time to sign requests at, 2026-10-18T13:10:45Z.
+fn time() -> SystemTime {
+  UNIX_EPOCH + Duration::from_secs(1_792_329_045)
+}
+
+
+/// Check that we sign the request created for an endpoint as
+/// configured.
+#[test]
+fn sign_endpoint_request() {
+  let signer = HmacSigner::new(
+    b"secret",
+    "{timestamp}{method}{path_and_query}{body}",
+    HeaderName::from_static("x-signature"),
+  )
+  .unwrap()
+  .with_timestamp_header(HeaderName::from_static("x-timestamp"))
+  .with_header(HeaderName::from_static("x-api-key"), "key")
+  .unwrap();
+
+  let mut request =
+    request::<CreateOrder>(Some("https://api.example.com"), &"BTCUSDT".to_string()).unwrap();
+  let () = signer.sign_at(&mut request, time()).unwrap();
+
+  let headers = request.headers();
+  assert_eq!(headers["x-timestamp"], "1792329045000");
+  assert_eq!(headers["x-api-key"], "key");
+  assert!(headers["x-api-key"].is_sensitive());
+  assert_eq!(
+    headers["x-signature"],
+    "fe3b20b1789ea9f5e8e9bcf8f85d76ba47363fd15d23dd040194af812d209e75"
+  );
+}
+
+/// Check that we honor the configured timestamp format, nonce, and
+/// signature encoding.
+#[test]
+fn sign_with_nonce() {
+  let signer = HmacSigner::new(
+    b"secret",
+    "{timestamp}\n{method}\n{path}\n{query}\n{nonce}\n{body_sha256}",
+    HeaderName::from_static("x-sign"),
+  )
+  .unwrap()
+  .with_timestamp(Timestamp::Iso8601)
+  .with_nonce(|| "42".to_string())
+  .with_nonce_header(HeaderName::from_static("x-nonce"))
+  .with_encoding(Encoding::Base64);
+
+  let mut request = Request::get("https://api.example.com/orders?status=open")
+    .body(Bytes::Borrowed(&[]))
+    .unwrap();
+  let () = signer.sign_at(&mut request, time()).unwrap();
+
+  let headers = request.headers();
+  assert_eq!(headers["x-nonce"], "42");
+  assert_eq!(
+    headers["x-sign"],
+    "8Jk4sx+SQlCzqh6sX5NgBxOBxfqM9Y4XWNblt70l9fc="
+  );
+  assert_eq!(headers.get("x-timestamp"), None);
+
+  // Signing as of now uses the current time.
+  let signer = signer
+    .with_timestamp(Timestamp::Seconds)
+    .with_timestamp_header(HeaderName::from_static("x-timestamp"));
+  let () = signer.authenticate(&mut request).unwrap();
+  let timestamp = request.headers()["x-timestamp"]
+    .to_str()
+    .unwrap()
+    .parse::<u64>()
+    .unwrap();
+  assert!(timestamp > 1_792_329_045, "{timestamp}");
+}
+
+/// Check that a nonce not usable as header value is reported as error.
+#[test(tokio::test)]
+async fn sign_with_invalid_nonce() {
+  let signer = HmacSigner::new(
+    b"secret",
+    "{timestamp}{nonce}",
+    HeaderName::from_static("x-sign"),
+  )
+  .unwrap()
+  .with_nonce(|| "4\n2".to_string())
+  .with_nonce_header(HeaderName::from_static("x-nonce"));
+
+  let mut request = Request::get("https://api.example.com/orders")
+    .body(Bytes::Borrowed(&[]))
+    .unwrap();
+  let _err = signer.sign_at(&mut request, time()).unwrap_err();
+  assert!(request.headers().is_empty());
+
+  let err = signer.authenticate(&mut request).unwrap_err();
+  assert!(request.headers().is_empty());
+  assert_eq!(err.to_string(), "failed to parse header value");
+
+  let client = Client::new(Counter::default())
+    .with_base_url("https://api.example.com")
+    .with_auth(signer);
+  let err = client
+    .issue::<CreateOrder>(&"BTCUSDT".to_string())
+    .await
+    .unwrap_err();
+  assert!(matches!(err, ClientError::Auth(..)), "{err:?}");
+  assert_eq!(client.transport().0.load(Ordering::Relaxed), 0);
+}
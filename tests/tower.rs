@@ -0,0 +1,116 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use http_endpoint::tower::EndpointLayer;
+use http_endpoint::tower::EndpointService;
+use http_endpoint::Bytes;
+use http_endpoint::ClientError;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+
+use test_log::test;
+
+use thiserror::Error;
+
+use tower::service_fn;
+use tower::ServiceBuilder;
+use tower::ServiceExt as _;
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("an unspecified error was encountered")]
+struct NoError;
+
+
+EndpointDef! {
+  GetLength(Vec<u8>),
+  Ok => usize, [
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /* 400 */ BAD_REQUEST => BadRequest,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn path(_: &Self::Input) -> Str {
+    "/length".into()
+  }
+
+  fn body(input: &Self::Input) -> Result<Option<Bytes>, Self::ConversionError> {
+    Ok(Some(input.clone().into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    let body = std::str::from_utf8(body).map_err(|_| NoError)?;
+    body.parse().map_err(|_| NoError)
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+/// An HTTP service responding with the length of the request body,
+/// refusing empty bodies.
+async fn length(request: Request<Bytes>) -> Result<Response<Vec<u8>>, Infallible> {
+  assert_eq!(request.uri(), "http://localhost/length");
+
+  let len = request.body().len();
+  let status = if len == 0 {
+    StatusCode::BAD_REQUEST
+  } else {
+    StatusCode::OK
+  };
+  let response = Response::builder()
+    .status(status)
+    .body(len.to_string().into_bytes())
+    .unwrap();
+  Ok(response)
+}
+
+
+/// Check that we can issue requests through a `tower` service.
+#[test(tokio::test)]
+async fn issue() {
+  let service =
+    EndpointService::<_, GetLength>::new(service_fn(length), Some("http://localhost".into()));
+  let output = service.oneshot(vec![1, 2, 3]).await.unwrap();
+  assert_eq!(output, 3);
+}
+
+/// Check that endpoint errors are reported properly by the service.
+#[test(tokio::test)]
+async fn issue_error() {
+  let service = ServiceBuilder::new()
+    .layer(EndpointLayer::<GetLength>::new(Some(
+      "http://localhost".into(),
+    )))
+    .service_fn(length);
+  let err = service.oneshot(Vec::new()).await.unwrap_err();
+  match err {
+    ClientError::Endpoint(GetError::BadRequest(Ok(NoError))) => (),
+    _ => panic!("unexpected error: {:?}", err),
+  }
+}
+
+/// Check that other layers can be stacked on top of the endpoint
+/// service.
+#[test(tokio::test)]
+async fn layering() {
+  let service = ServiceBuilder::new()
+    .map_request(|input: Vec<u8>| input.repeat(2))
+    .layer(EndpointLayer::<GetLength>::new(Some(
+      "http://localhost/".into(),
+    )))
+    .service_fn(length);
+  let output = service.oneshot(vec![1, 2]).await.unwrap();
+  assert_eq!(output, 4);
+}
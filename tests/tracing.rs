@@ -0,0 +1,183 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use http::header::AUTHORIZATION;
+use http::header::CONTENT_TYPE;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::StatusCode;
+
+use http_endpoint::request;
+use http_endpoint::Endpoint as _;
+use http_endpoint::EndpointDef;
+use http_endpoint::Str;
+
+use thiserror::Error;
+
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing::subscriber::with_default;
+use tracing::Event;
+use tracing::Subscriber;
+
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::layer::Layer;
+use tracing_subscriber::layer::SubscriberExt as _;
+use tracing_subscriber::Registry;
+
+
+#[derive(Debug, Error, PartialEq)]
+#[error("an unspecified error was encountered")]
+struct NoError;
+
+
+EndpointDef! {
+  GetOrder(u64),
+  Ok => (), [
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /* 404 */ NOT_FOUND => NotFound,
+  ],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/orders/{}", input).into()
+  }
+
+  fn path_template() -> Option<&'static str> {
+    Some("/orders/{id}")
+  }
+
+  fn headers(_: &Self::Input) -> Result<Option<HeaderMap>, Self::ConversionError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    Ok(Some(headers))
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+EndpointDef! {
+  GetStatus(u16),
+  Ok => (), [
+    /* 200 */ OK,
+  ],
+  Err => GetStatusError, [],
+  ConversionErr => NoError,
+  ApiErr => NoError,
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/status/{}", input).into()
+  }
+
+  fn parse(_: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    Ok(())
+  }
+
+  fn parse_err(_: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    Ok(NoError)
+  }
+}
+
+
+type Fields = HashMap<String, String>;
+
+/// A visitor collecting the fields of an event.
+struct Visitor<'f>(&'f mut Fields);
+
+impl Visit for Visitor<'_> {
+  fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+    self
+      .0
+      .insert(field.name().to_string(), format!("{value:?}"));
+  }
+
+  fn record_str(&mut self, field: &Field, value: &str) {
+    self.0.insert(field.name().to_string(), value.to_string());
+  }
+}
+
+/// A layer recording the fields of all events.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Fields>>>);
+
+impl<S> Layer<S> for Recorder
+where
+  S: Subscriber,
+{
+  fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+    let mut fields = Fields::new();
+    event.record(&mut Visitor(&mut fields));
+    self.0.lock().unwrap().push(fields);
+  }
+}
+
+
+/// Check that request creation and response evaluation are traced.
+#[test]
+fn trace_events() {
+  let recorder = Recorder::default();
+  let subscriber = Registry::default().with(recorder.clone());
+
+  with_default(subscriber, || {
+    let _request = request::<GetOrder>(Some("https://example.com"), &42).unwrap();
+    GetOrder::evaluate(StatusCode::OK, b"").unwrap();
+    let _err = GetOrder::evaluate(StatusCode::NOT_FOUND, b"gone").unwrap_err();
+  });
+
+  let events = recorder.0.lock().unwrap();
+  assert_eq!(events.len(), 3);
+
+  let created = &events[0];
+  assert_eq!(created["message"], "created request");
+  assert_eq!(created["endpoint"], "tracing::GetOrder");
+  assert_eq!(created["method"], "GET");
+  assert_eq!(created["path"], "/orders/{id}");
+  assert!(!created["headers"].contains("secret"), "{created:?}");
+  assert!(created["headers"].contains("<redacted>"), "{created:?}");
+  assert!(created["headers"].contains("text/plain"), "{created:?}");
+
+  let ok = &events[1];
+  assert_eq!(ok["message"], "evaluated response");
+  assert_eq!(ok["status"], "200");
+  assert_eq!(ok["body_size"], "0");
+  assert!(!ok.contains_key("error"), "{ok:?}");
+
+  let err = &events[2];
+  assert_eq!(err["status"], "404");
+  assert_eq!(err["body_size"], "4");
+  assert_eq!(err["error"], "NotFound");
+}
+
+/// Check that no path is traced for endpoints without path template.
+#[test]
+fn trace_without_path_template() {
+  let recorder = Recorder::default();
+  let subscriber = Registry::default().with(recorder.clone());
+
+  with_default(subscriber, || {
+    let _request = request::<GetStatus>(Some("https://example.com"), &200).unwrap();
+    GetStatus::evaluate(StatusCode::OK, b"").unwrap();
+  });
+
+  let events = recorder.0.lock().unwrap();
+  assert_eq!(events.len(), 2);
+  assert_eq!(events[0]["endpoint"], "tracing::GetStatus");
+  assert!(!events[0].contains_key("path"), "{:?}", events[0]);
+  assert!(!events[1].contains_key("path"), "{:?}", events[1]);
+}
@@ -0,0 +1,180 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+use std::env::temp_dir;
+use std::fs::remove_file;
+use std::process::id;
+
+use http::header::AUTHORIZATION;
+use http::header::CONTENT_TYPE;
+use http::HeaderName;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+
+use http_endpoint::vcr::Body;
+use http_endpoint::vcr::Cassette;
+use http_endpoint::vcr::Error;
+use http_endpoint::vcr::Matcher;
+use http_endpoint::vcr::Recorder;
+use http_endpoint::vcr::Replayer;
+use http_endpoint::BoxFuture;
+use http_endpoint::Bytes;
+use http_endpoint::Transport;
+
+use test_log::test;
+
+
+/// A transport responding with the request's path and query, in binary
+/// form for paths ending in `.bin`.
+struct Echo;
+
+impl Transport for Echo {
+  type Error = Infallible;
+
+  fn send(&self, request: Request<Bytes>) -> BoxFuture<'_, Result<Response<Vec<u8>>, Self::Error>> {
+    let path = request.uri().path_and_query().unwrap().to_string();
+    let body = if path.ends_with(".bin") {
+      vec![0xde, 0xad, 0xbe, 0xef]
+    } else {
+      path.into_bytes()
+    };
+    let response = Response::builder()
+      .status(StatusCode::OK)
+      .header(CONTENT_TYPE, "text/plain")
+      .body(body)
+      .unwrap();
+    Box::pin(async move { Ok(response) })
+  }
+}
+
+
+fn get(uri: &str) -> Request<Bytes> {
+  Request::get(uri).body(Bytes::Borrowed(&[])).unwrap()
+}
+
+
+/// Record a few interactions.
+async fn record() -> Cassette {
+  let recorder = Recorder::new(Echo);
+  let _response = recorder
+    .send(get("https://example.com/a?x=1&y=2"))
+    .await
+    .unwrap();
+  let request = Request::post("https://example.com/b")
+    .header(AUTHORIZATION, "Bearer secret")
+    .header("x-tenant", "acme")
+    .body(Bytes::Borrowed(b"payload"))
+    .unwrap();
+  let _response = recorder.send(request).await.unwrap();
+  let _response = recorder
+    .send(get("https://example.com/c.bin"))
+    .await
+    .unwrap();
+  recorder.into_cassette()
+}
+
+
+/// Check that interactions are recorded as expected.
+#[test(tokio::test)]
+async fn record_interactions() {
+  let cassette = record().await;
+  assert_eq!(cassette.interactions.len(), 3);
+
+  let interaction = &cassette.interactions[0];
+  assert_eq!(interaction.request.method, "GET");
+  assert_eq!(interaction.request.uri, "https://example.com/a?x=1&y=2");
+  assert_eq!(interaction.response.status, 200);
+  assert_eq!(interaction.response.body, Body::Text("/a?x=1&y=2".into()));
+
+  let interaction = &cassette.interactions[1];
+  assert_eq!(interaction.request.headers[0].name, "authorization");
+  assert_eq!(interaction.request.headers[0].value, "<redacted>");
+  assert_eq!(interaction.request.headers[1].value, "acme");
+  assert_eq!(interaction.request.body, Body::Text("payload".into()));
+
+  let interaction = &cassette.interactions[2];
+  assert_eq!(
+    interaction.response.body,
+    Body::Binary(vec![0xde, 0xad, 0xbe, 0xef])
+  );
+}
+
+/// Check that cassettes survive a round trip through JSON and YAML
+/// files.
+#[test(tokio::test)]
+async fn save_and_load() {
+  let cassette = record().await;
+
+  for ext in ["json", "yaml"] {
+    let path = temp_dir().join(format!("http-endpoint-vcr-{}.{}", id(), ext));
+    let () = cassette.save(&path).unwrap();
+    let loaded = Cassette::load(&path).unwrap();
+    let () = remove_file(&path).unwrap();
+    assert_eq!(loaded, cassette);
+  }
+}
+
+/// Check that recorded interactions can be replayed.
+#[test(tokio::test)]
+async fn replay_interactions() {
+  let replayer = Replayer::new(record().await);
+
+  // Query parameters are matched irrespective of their order.
+  let response = replayer
+    .send(get("https://example.com/a?y=2&x=1"))
+    .await
+    .unwrap();
+  assert_eq!(response.status(), StatusCode::OK);
+  assert_eq!(response.headers()[CONTENT_TYPE], "text/plain");
+  assert_eq!(response.body(), b"/a?x=1&y=2");
+
+  let response = replayer
+    .send(get("https://example.com/c.bin"))
+    .await
+    .unwrap();
+  assert_eq!(response.body(), &[0xde, 0xad, 0xbe, 0xef]);
+
+  // Each interaction is only replayed once.
+  let err = replayer
+    .send(get("https://example.com/a?x=1&y=2"))
+    .await
+    .unwrap_err();
+  assert!(matches!(err, Error::Unmatched { .. }), "{err}");
+}
+
+/// Check that the matching criteria can be configured.
+#[test(tokio::test)]
+async fn replay_matching() {
+  let cassette = record().await;
+  let post = |body: &'static [u8], tenant| {
+    Request::post("https://example.com/b")
+      .header("x-tenant", tenant)
+      .body(Bytes::Borrowed(body))
+      .unwrap()
+  };
+
+  let replayer = Replayer::new(cassette.clone());
+  let err = replayer.send(post(b"other", "acme")).await.unwrap_err();
+  assert!(matches!(err, Error::Unmatched { .. }), "{err}");
+  let err = replayer
+    .send(get("https://example.com/a?x=1"))
+    .await
+    .unwrap_err();
+  assert!(matches!(err, Error::Unmatched { .. }), "{err}");
+
+  let matcher = Matcher::new()
+    .with_body(false)
+    .with_header(HeaderName::from_static("x-tenant"));
+  let replayer = Replayer::new(cassette.clone()).with_matcher(matcher);
+  let err = replayer.send(post(b"other", "other")).await.unwrap_err();
+  assert!(matches!(err, Error::Unmatched { .. }), "{err}");
+  let response = replayer.send(post(b"other", "acme")).await.unwrap();
+  assert_eq!(response.body(), b"/b");
+
+  let matcher = Matcher::new().with_query(false);
+  let replayer = Replayer::new(cassette).with_matcher(matcher);
+  let response = replayer.send(get("https://example.com/a")).await.unwrap();
+  assert_eq!(response.body(), b"/a?x=1&y=2");
+}
@@ -0,0 +1,194 @@
+// Copyright (C) 2026 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::str::Utf8Error;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use http::HeaderName;
+use http::Method;
+use http::Request;
+use http::StatusCode;
+
+use http_endpoint::signer::Encoding;
+use http_endpoint::signer::HmacSigner;
+use http_endpoint::signer::Timestamp;
+use http_endpoint::webhook::Error;
+use http_endpoint::webhook::Webhook;
+use http_endpoint::Bytes;
+use http_endpoint::Str;
+use http_endpoint::WebhookDef;
+
+use serde::Deserialize;
+
+use serde_json::Error as JsonError;
+
+use test_log::test;
+
+
+WebhookDef! {
+  /// A webhook as sent by GitHub.
+  Push(String),
+  ConversionErr => Utf8Error,
+
+  fn path() -> Str {
+    "/hooks/github".into()
+  }
+
+  fn signature_header() -> HeaderName {
+    HeaderName::from_static("x-hub-signature-256")
+  }
+
+  fn signature_prefix() -> &'static str {
+    "sha256="
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Payload, Self::ConversionError> {
+    std::str::from_utf8(body).map(str::to_string)
+  }
+}
+
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Payment {
+  id: u64,
+  amount: u64,
+}
+
+WebhookDef! {
+  PaymentReceived(Payment),
+  ConversionErr => JsonError,
+
+  fn method() -> Method {
+    Method::PUT
+  }
+
+  fn path() -> Str {
+    "/hooks/payments".into()
+  }
+
+  fn signature_header() -> HeaderName {
+    HeaderName::from_static("x-signature")
+  }
+
+  fn signature_encoding() -> Encoding {
+    Encoding::Base64
+  }
+
+  fn timestamp_header() -> Option<HeaderName> {
+    Some(HeaderName::from_static("x-timestamp"))
+  }
+
+  fn tolerance() -> Duration {
+    Duration::from_secs(60)
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Payload, Self::ConversionError> {
+    serde_json::from_slice(body)
+  }
+}
+
+
+/// The point in time requests are sent at, 2026-10-18T13:10:45Z.
+fn time() -> SystemTime {
+  UNIX_EPOCH + Duration::from_secs(1_792_329_045)
+}
+
+/// Create a payment webhook request signed at the given time.
+fn payment_request(body: &'static [u8], time: SystemTime) -> Request<Bytes> {
+  let signer = HmacSigner::new(
+    b"secret",
+    "{timestamp}.{body}",
+    HeaderName::from_static("x-signature"),
+  )
+  .unwrap()
+  .with_timestamp(Timestamp::Seconds)
+  .with_timestamp_header(HeaderName::from_static("x-timestamp"))
+  .with_encoding(Encoding::Base64);
+
+  let mut request = Request::put("https://example.com/hooks/payments")
+    .body(Bytes::Borrowed(body))
+    .unwrap();
+  let () = signer.sign_at(&mut request, time).unwrap();
+  request
+}
+
+
+/// Check that we can verify a webhook request as documented by GitHub.
+#[test]
+fn verify_signature() {
+  let request = Request::post("/hooks/github")
+    .header(
+      "x-hub-signature-256",
+      "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+    )
+    .body(b"Hello, World!".to_vec())
+    .unwrap();
+
+  let payload = Push::verify(&request, b"It's a Secret to Everybody").unwrap();
+  assert_eq!(payload, "Hello, World!");
+  assert_eq!(Push::NAME, "webhook::Push");
+
+  let err = Push::verify(&request, b"wrong").unwrap_err();
+  assert!(matches!(err, Error::Signature), "{err:?}");
+  assert_eq!(err.status(), StatusCode::UNAUTHORIZED);
+}
+
+/// Check that requests with the wrong method or path, or lacking a
+/// signature, are rejected.
+#[test]
+fn reject_invalid_request() {
+  let request = Request::get("/hooks/github").body(Vec::new()).unwrap();
+  let err = Push::verify(&request, b"secret").unwrap_err();
+  assert!(matches!(err, Error::Method(Method::GET)), "{err:?}");
+  assert_eq!(err.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+  let request = Request::post("/hooks/gitlab").body(Vec::new()).unwrap();
+  let err = Push::verify(&request, b"secret").unwrap_err();
+  assert_eq!(err.to_string(), "unexpected request path /hooks/gitlab");
+  assert_eq!(err.status(), StatusCode::NOT_FOUND);
+
+  let request = Request::post("/hooks/github").body(Vec::new()).unwrap();
+  let err = Push::verify(&request, b"secret").unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "header x-hub-signature-256 is missing or invalid"
+  );
+
+  let request = Request::post("/hooks/github")
+    .header("x-hub-signature-256", "sha1=abcd")
+    .body(Vec::new())
+    .unwrap();
+  let err = Push::verify(&request, b"secret").unwrap_err();
+  assert!(matches!(err, Error::Header(..)), "{err:?}");
+}
+
+/// Check that requests signed with a timestamp are verified and their
+/// payload parsed.
+#[test]
+fn verify_timestamp() {
+  let request = payment_request(br#"{"id":1,"amount":42}"#, time());
+  let payload = PaymentReceived::verify_at(&request, b"secret", time()).unwrap();
+  assert_eq!(payload, Payment { id: 1, amount: 42 });
+
+  let later = time() + Duration::from_secs(60);
+  let _payload = PaymentReceived::verify_at(&request, b"secret", later).unwrap();
+
+  let later = time() + Duration::from_secs(61);
+  let err = PaymentReceived::verify_at(&request, b"secret", later).unwrap_err();
+  assert!(matches!(err, Error::Timestamp(1_792_329_045)), "{err:?}");
+
+  // Tampering with the timestamp invalidates the signature.
+  let mut request = request;
+  let _prev = request
+    .headers_mut()
+    .insert("x-timestamp", "1792329100".parse().unwrap());
+  let err = PaymentReceived::verify_at(&request, b"secret", later).unwrap_err();
+  assert!(matches!(err, Error::Signature), "{err:?}");
+
+  let request = payment_request(br#"{"id":1}"#, time());
+  let err = PaymentReceived::verify_at(&request, b"secret", time()).unwrap_err();
+  assert!(matches!(err, Error::Conversion(..)), "{err:?}");
+  assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+}